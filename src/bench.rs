@@ -1,5 +1,15 @@
 use crate::config::PingThingsArgs;
+use crate::fees::compute::ComputeProfiler;
+use crate::fees::FeeEstimator;
+use crate::geyser::signatures::SignatureWatch;
 use crate::meteora::fetch_and_swap::fetch_and_execute_swap;
+use crate::meteora::fetch_pool::{fetch_pool_accounts, fetch_vaults};
+use crate::nonce::NoncePool;
+use crate::pools::reserves::ReserveCache;
+use crate::tx_senders::constants::VAULT_PROGRAM_ID;
+use crate::tx_senders::lookup_table::LookupTables;
+use crate::tx_senders::submission::SubmissionManager;
+use crate::wallet::load_keypair;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::hash::Hash;
 use solana_sdk::pubkey::Pubkey;
@@ -30,6 +40,7 @@ pub struct Bench {
 impl Bench {
     pub fn new(
         config: PingThingsArgs,
+        user: Arc<Keypair>,
         pool: Pubkey,
         token_a: Pubkey,
         token_b: Pubkey,
@@ -44,8 +55,6 @@ impl Bench {
         protocol_fee: Pubkey,
         vault_program: Pubkey,
     ) -> Self {
-        let user = Arc::new(Keypair::from_base58_string(&config.private_key));
-
         Self {
             config,
             user,
@@ -65,21 +74,73 @@ impl Bench {
         }
     }
 
+    /// Заполнение аккаунтов пула по его состоянию в сети
+    pub async fn for_pool(config: PingThingsArgs, pool: Pubkey) -> anyhow::Result<Self> {
+        let rpc = RpcClient::new(config.http_rpc.clone());
+        let info = fetch_pool_accounts(&rpc, &pool).await?;
+        let [vault_a, vault_b] = fetch_vaults(&rpc, &info).await?;
+        let user = Arc::new(load_keypair(&config)?);
+
+        Ok(Self::new(
+            config,
+            user,
+            pool,
+            info.token_a_mint,
+            info.token_b_mint,
+            info.a_vault,
+            info.b_vault,
+            vault_a.token_vault,
+            vault_b.token_vault,
+            vault_a.lp_mint,
+            vault_b.lp_mint,
+            info.a_vault_lp,
+            info.b_vault_lp,
+            info.protocol_token_a_fee,
+            VAULT_PROGRAM_ID,
+        ))
+    }
+
     pub async fn run(self, repeats: usize) {
         let mut handles = vec![];
+        let rpc = RpcClient::new(self.config.http_rpc.clone());
+        let lookup_tables = match LookupTables::load(&rpc, &self.config.lookup_tables).await {
+            Ok(tables) => Arc::new(tables),
+            Err(e) => {
+                error!("Не удалось загрузить lookup tables: {:?}", e);
+                return;
+            }
+        };
         let start = Instant::now();
+        let fees = Arc::new(FeeEstimator::new());
+        let compute = Arc::new(ComputeProfiler::new());
 
         for i in 0..repeats {
             let bench = self.clone();
             let user = bench.user.clone();
+            let fees = fees.clone();
+            let compute = compute.clone();
+            let lookup_tables = lookup_tables.clone();
 
             let handle = tokio::spawn(async move {
                 let result = fetch_and_execute_swap(
                     &bench.config,
+                    &fees,
+                    &compute,
+                    &lookup_tables,
+                    // Пустой кэш: пул не отслеживается, состояние читается по RPC
+                    &ReserveCache::new(),
+                    // Отдельный менеджер на каждый свап: защита от повторной покупки пула здесь не нужна
+                    &SubmissionManager::new(
+                        Arc::new(RpcClient::new(bench.config.http_rpc.clone())),
+                        Arc::new(SignatureWatch::new()),
+                        Arc::new(NoncePool::default()),
+                    ),
                     &user,
+                    None,
                     &bench.pool,
                     &bench.token_a,
                     &bench.token_b,
+                    bench.config.buy_amount.to_lamports(),
                 )
                 .await;
 
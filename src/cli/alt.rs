@@ -0,0 +1,66 @@
+use crate::cli::nonce::send;
+use crate::cli::AltCommand;
+use crate::config::PingThingsArgs;
+use crate::tx_senders::lookup_table::{fetch_lookup_table, static_accounts};
+use crate::wallet::load_keypair;
+
+use anyhow::Result;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::address_lookup_table::instruction::{create_lookup_table, extend_lookup_table};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+pub async fn run(args: &PingThingsArgs, action: AltCommand) -> Result<()> {
+    let rpc = RpcClient::new(args.http_rpc.clone());
+
+    match action {
+        AltCommand::Create => {
+            let payer = load_keypair(args)?;
+            // Слот должен присутствовать в SlotHashes, поэтому берётся finalized
+            let recent_slot = rpc
+                .get_slot_with_commitment(CommitmentConfig::finalized())
+                .await?;
+            let (instruction, table) = create_lookup_table(payer.pubkey(), payer.pubkey(), recent_slot);
+            let signature = send(&rpc, &[instruction], &payer, &[&payer]).await?;
+            println!("Lookup table: {table}");
+            println!("Транзакция создания: {signature}");
+
+            extend(&rpc, &payer, &table, static_accounts()).await?;
+            println!("Добавьте адрес в lookup_tables в config.yaml");
+            Ok(())
+        }
+        AltCommand::Extend { table } => {
+            let payer = load_keypair(args)?;
+            let existing = fetch_lookup_table(&rpc, &table).await?;
+            let missing: Vec<Pubkey> = static_accounts()
+                .into_iter()
+                .filter(|address| !existing.addresses.contains(address))
+                .collect();
+
+            if missing.is_empty() {
+                println!("Все статические аккаунты уже в таблице");
+                return Ok(());
+            }
+            extend(&rpc, &payer, &table, missing).await
+        }
+        AltCommand::Show { table } => {
+            let table = fetch_lookup_table(&rpc, &table).await?;
+            println!("Lookup table {} ({} адресов):", table.key, table.addresses.len());
+            for (index, address) in table.addresses.iter().enumerate() {
+                println!("  {index:>3} {address}");
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn extend(rpc: &RpcClient, payer: &Keypair, table: &Pubkey, addresses: Vec<Pubkey>) -> Result<()> {
+    // Ограничение размера транзакции: не больше ~30 адресов за одно расширение
+    for chunk in addresses.chunks(20) {
+        let instruction = extend_lookup_table(*table, payer.pubkey(), Some(payer.pubkey()), chunk.to_vec());
+        let signature = send(rpc, &[instruction], payer, &[payer]).await?;
+        println!("Добавлено {} адресов: {signature}", chunk.len());
+    }
+    Ok(())
+}
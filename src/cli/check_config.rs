@@ -0,0 +1,40 @@
+use crate::config::PingThingsArgs;
+use crate::wallet::pool::WalletPool;
+
+use anyhow::Result;
+use std::path::Path;
+
+pub fn run(path: &Path) -> Result<()> {
+    let args = match PingThingsArgs::from_path(path) {
+        Ok(args) => args,
+        Err(e) => {
+            println!("Конфигурация {} невалидна: {e}", path.display());
+            return Err(e.into());
+        }
+    };
+
+    let wallets = match WalletPool::from_config(&args) {
+        Ok(pool) => pool
+            .wallets()
+            .iter()
+            .map(|wallet| format!("{} {}", wallet.name, wallet.pubkey()))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Err(e) => {
+            println!("Кошельки в {} невалидны: {e}", path.display());
+            return Err(e.into());
+        }
+    };
+
+    println!("Конфигурация {} загружена", path.display());
+    println!("  кошельки:           {wallets} ({:?})", args.wallet_selection);
+    println!("  http_rpc:           {}", args.http_rpc);
+    println!("  geyser_url:         {}", if args.geyser_url.is_empty() { "не задан" } else { &args.geyser_url });
+    println!("  отправщики:         {}", args.rpc.len());
+    println!("  compute_unit_price: {}", args.compute_unit_price);
+    println!("  compute_unit_limit: {}", args.compute_unit_limit);
+    println!("  tip:                {} ({})", args.tip, args.tip.to_lamports());
+    println!("  buy_amount:         {} ({})", args.buy_amount, args.buy_amount.to_lamports());
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use crate::config::PingThingsArgs;
+use crate::core::encoded::{decode_encoded_transaction, fetch_transaction};
+use crate::core::alt::AltResolver;
+use crate::core::decoder::DecoderRegistry;
+use crate::core::{extract_instructions, extract_instructions_resolved, ParsedInstruction};
+use crate::meteora::events::parse_events;
+use crate::tx_senders::constants::{
+    METEORA_DAMM_V2_PROGRAM_ID, METEORA_DLMM_PROGRAM_ID, METEORA_PROGRAM_ID, RAYDIUM_AMM_V4_PROGRAM_ID,
+    RAYDIUM_CPMM_PROGRAM_ID, VAULT_PROGRAM_ID,
+};
+
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransactionWithStatusMeta,
+    TransactionStatusMeta,
+};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+pub async fn run(args: &PingThingsArgs, target: &str) -> Result<()> {
+    let rpc = Arc::new(RpcClient::new(args.http_rpc.clone()));
+    let (tx, meta) = if Path::new(target).exists() {
+        load_from_file(Path::new(target))?
+    } else {
+        let signature = Signature::from_str(target)
+            .map_err(|_| anyhow!("{target} не является ни файлом, ни сигнатурой"))?;
+        let (tx, meta) = fetch_transaction(&rpc, &signature).await?;
+        (tx, Some(meta))
+    };
+
+    println!("Транзакция: {}", tx.signatures.first().copied().unwrap_or_default());
+
+    let logs = meta
+        .as_ref()
+        .and_then(|meta| meta.log_messages.clone())
+        .unwrap_or_default();
+
+    // Сырая транзакция без meta: lookup tables запрашиваются через RPC
+    let instructions = match meta {
+        Some(meta) => extract_instructions(meta, tx)?,
+        None => extract_instructions_resolved(&AltResolver::new(rpc), tx).await?,
+    };
+    let mut found = 0;
+    let decoders = DecoderRegistry::default();
+
+    for (index, parsed) in instructions.iter().enumerate() {
+        let ix = &parsed.instruction;
+        let programs = [
+            METEORA_PROGRAM_ID,
+            VAULT_PROGRAM_ID,
+            METEORA_DLMM_PROGRAM_ID,
+            METEORA_DAMM_V2_PROGRAM_ID,
+            RAYDIUM_AMM_V4_PROGRAM_ID,
+            RAYDIUM_CPMM_PROGRAM_ID,
+        ];
+        if !programs.contains(&ix.program_id) {
+            continue;
+        }
+        let label = decoders.program_name(&ix.program_id).unwrap_or_default();
+
+        found += 1;
+        print_instruction(index, label, parsed);
+        match decoders.decode(ix) {
+            Some(decoded) => println!("    => {decoded:?}"),
+            None => println!("    => неизвестная инструкция"),
+        }
+    }
+
+    if found == 0 {
+        println!("Инструкции Meteora не найдены");
+    }
+
+    for event in parse_events(&logs) {
+        println!("Событие: {event:?}");
+    }
+
+    Ok(())
+}
+
+fn print_instruction(index: usize, label: &str, parsed: &ParsedInstruction) {
+    let ix = &parsed.instruction;
+    let discriminator: String = ix.data.iter().take(8).map(|b| format!("{b:02x}")).collect();
+    let origin = if parsed.is_inner() {
+        format!(" CPI из #{} (глубина {})", parsed.parent_index, parsed.stack_height)
+    } else {
+        String::new()
+    };
+    println!(
+        "#{index} {} ({label}){origin} discriminator: {discriminator} | данные: {} байт",
+        ix.program_id,
+        ix.data.len()
+    );
+
+    for (position, account) in ix.accounts.iter().enumerate() {
+        println!(
+            "    [{position:>2}] {} {}{}",
+            account.pubkey,
+            if account.is_writable { "W" } else { "-" },
+            if account.is_signer { "S" } else { "-" },
+        );
+    }
+}
+
+/// Файл может содержать JSON-ответ `getTransaction` или сериализованную транзакцию в base64/base58
+fn load_from_file(path: &Path) -> Result<(VersionedTransaction, Option<TransactionStatusMeta>)> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("не удалось прочитать {}", path.display()))?;
+    let content = content.trim();
+
+    if content.starts_with('{') {
+        let mut value: serde_json::Value = serde_json::from_str(content)?;
+        if let Some(result) = value.get_mut("result") {
+            value = result.take();
+        }
+
+        let encoded = match serde_json::from_value::<EncodedConfirmedTransactionWithStatusMeta>(
+            value.clone(),
+        ) {
+            Ok(confirmed) => confirmed.transaction,
+            Err(_) => serde_json::from_value::<EncodedTransactionWithStatusMeta>(value)
+                .context("неизвестный формат JSON транзакции")?,
+        };
+
+        let (tx, meta) = decode_encoded_transaction(encoded)?;
+        return Ok((tx, Some(meta)));
+    }
+
+    let raw = base64::engine::general_purpose::STANDARD
+        .decode(content)
+        .or_else(|_| bs58::decode(content).into_vec())
+        .map_err(|_| anyhow!("содержимое файла не является base64 или base58"))?;
+    let tx: VersionedTransaction = bincode::deserialize(&raw)?;
+
+    Ok((tx, None))
+}
@@ -0,0 +1,83 @@
+use crate::cli::KeystoreCommand;
+use crate::wallet::keystore::Keystore;
+use crate::wallet::{read_keypair_file, read_keystore, read_passphrase};
+
+use anyhow::{bail, Result};
+use solana_sdk::signature::{Keypair, Signer};
+use std::fs::OpenOptions;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+pub fn run(action: KeystoreCommand) -> Result<()> {
+    match action {
+        KeystoreCommand::Create { output, from } => {
+            if output.exists() {
+                bail!("{} уже существует", output.display());
+            }
+
+            let keypair = match from {
+                Some(path) => read_keypair_file(&path)?,
+                None => Keypair::new(),
+            };
+
+            let passphrase = read_passphrase("Новый пароль keystore: ")?;
+            if passphrase.is_empty() {
+                bail!("пустой пароль недопустим");
+            }
+            let confirm = read_passphrase("Повторите пароль: ")?;
+            if *passphrase != *confirm {
+                bail!("пароли не совпадают");
+            }
+
+            let keystore = Keystore::encrypt(&keypair, &passphrase).map_err(anyhow::Error::msg)?;
+            write_private(&output, &serde_json::to_string_pretty(&keystore)?)?;
+
+            println!("Keystore {} создан для {}", output.display(), keypair.pubkey());
+            Ok(())
+        }
+        KeystoreCommand::Show { path } => {
+            let passphrase = read_passphrase(&format!("Пароль keystore {}: ", path.display()))?;
+            let keypair = read_keystore(&path, &passphrase)?;
+
+            println!("{}", keypair.pubkey());
+            Ok(())
+        }
+    }
+}
+
+/// Новый файл, доступный только владельцу: keystore не должен читаться другими пользователями
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let mut file = options.open(path)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn write_private_creates_owner_only_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("keystore-test-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        write_private(&path, "{}").unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        // Повторная запись не перезаписывает существующий keystore
+        let second = write_private(&path, "{}");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(second.is_err());
+    }
+}
@@ -0,0 +1,168 @@
+pub mod alt;
+pub mod check_config;
+pub mod decode;
+pub mod keystore;
+pub mod nonce;
+pub mod pump_fun;
+pub mod simulate;
+
+use clap::{Parser, Subcommand};
+use solana_sdk::pubkey::Pubkey;
+use std::path::PathBuf;
+
+/// Снайпер ликвидности Meteora
+#[derive(Debug, Parser)]
+#[command(name = "meteora-liquidity-sniper", version, about)]
+pub struct Cli {
+    /// Путь к файлу конфигурации
+    #[arg(short, long, global = true, default_value = "./config.yaml")]
+    pub config: PathBuf,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Запуск снайпера (режим по умолчанию)
+    Run,
+
+    /// Параллельный прогон свапов по заданному пулу
+    Bench {
+        /// Адрес пула Meteora
+        #[arg(long)]
+        pool: Pubkey,
+
+        /// Количество свапов
+        #[arg(long, default_value_t = 10)]
+        repeats: usize,
+    },
+
+    /// Вывод инструкций Meteora из транзакции (сигнатура или файл)
+    Decode {
+        /// Сигнатура транзакции или путь к файлу
+        target: String,
+    },
+
+    /// Симуляция свапа без отправки транзакции
+    Simulate {
+        /// Адрес пула Meteora
+        #[arg(long)]
+        pool: Pubkey,
+    },
+
+    /// Проверка файла конфигурации
+    CheckConfig,
+
+    /// Работа с зашифрованным keystore кошелька
+    Keystore {
+        #[command(subcommand)]
+        action: KeystoreCommand,
+    },
+
+    /// Управление address lookup table для наших транзакций
+    Alt {
+        #[command(subcommand)]
+        action: AltCommand,
+    },
+
+    /// Управление durable nonce аккаунтами
+    Nonce {
+        #[command(subcommand)]
+        action: NonceCommand,
+    },
+
+    /// Котировки и продажа токенов на bonding curve Pump.fun
+    PumpFun {
+        #[command(subcommand)]
+        action: PumpFunCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum KeystoreCommand {
+    /// Шифрование ключа в keystore (новый ключ, если --from не указан)
+    Create {
+        /// Путь к создаваемому keystore
+        output: PathBuf,
+
+        /// Исходный ключ: JSON-файл Solana CLI
+        #[arg(long)]
+        from: Option<PathBuf>,
+    },
+
+    /// Проверка пароля и вывод публичного ключа
+    Show {
+        /// Путь к keystore
+        path: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum NonceCommand {
+    /// Создание nonce аккаунта с authority основного кошелька
+    Create {
+        /// Файл, куда будет сохранён ключ нового аккаунта (JSON Solana CLI)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Текущее значение nonce и authority
+    Show {
+        /// Адрес nonce аккаунта
+        account: Pubkey,
+    },
+
+    /// Сдвиг значения nonce (отменяет заранее подписанные транзакции)
+    Advance {
+        /// Адрес nonce аккаунта
+        account: Pubkey,
+    },
+
+    /// Вывод SOL из nonce аккаунта на основной кошелёк
+    Withdraw {
+        /// Адрес nonce аккаунта
+        account: Pubkey,
+
+        /// Сумма в SOL, весь баланс (с закрытием аккаунта) если не указана
+        #[arg(long)]
+        amount: Option<f64>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AltCommand {
+    /// Создание lookup table со статическими аккаунтами, authority — основной кошелёк
+    Create,
+
+    /// Добавление недостающих статических аккаунтов в существующую таблицу
+    Extend {
+        /// Адрес lookup table
+        table: Pubkey,
+    },
+
+    /// Содержимое lookup table
+    Show {
+        /// Адрес lookup table
+        table: Pubkey,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PumpFunCommand {
+    /// Состояние bonding curve и котировки покупки на buy_amount и продажи всего баланса
+    Quote {
+        /// Mint токена
+        mint: Pubkey,
+    },
+
+    /// Продажа токенов основного кошелька с допуском pump_fun.slippage_bps
+    Sell {
+        /// Mint токена
+        mint: Pubkey,
+
+        /// Количество в минимальных единицах, весь баланс если не указано
+        #[arg(long)]
+        amount: Option<u64>,
+    },
+}
@@ -0,0 +1,106 @@
+use crate::cli::NonceCommand;
+use crate::config::{PingThingsArgs, Sol};
+use crate::nonce::fetch_nonce_account;
+use crate::wallet::load_keypair;
+
+use anyhow::{anyhow, bail, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::nonce::state::State;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+
+pub async fn run(args: &PingThingsArgs, action: NonceCommand) -> Result<()> {
+    let rpc = RpcClient::new(args.http_rpc.clone());
+    let payer = load_keypair(args)?;
+
+    match action {
+        NonceCommand::Create { output } => {
+            let nonce_keypair = Keypair::new();
+            if let Some(output) = &output {
+                if output.exists() {
+                    bail!("{} уже существует", output.display());
+                }
+                write_keypair_file(&nonce_keypair, output).map_err(|e| anyhow!(e.to_string()))?;
+            }
+
+            let rent = rpc
+                .get_minimum_balance_for_rent_exemption(State::size())
+                .await?;
+            let instructions = system_instruction::create_nonce_account(
+                &payer.pubkey(),
+                &nonce_keypair.pubkey(),
+                &payer.pubkey(),
+                rent,
+            );
+            let signature = send(&rpc, &instructions, &payer, &[&payer, &nonce_keypair]).await?;
+
+            println!("Nonce аккаунт: {}", nonce_keypair.pubkey());
+            println!("Authority: {}", payer.pubkey());
+            println!("Транзакция: {signature}");
+            Ok(())
+        }
+        NonceCommand::Show { account } => {
+            let nonce = fetch_nonce_account(&rpc, &account).await?;
+
+            println!("Аккаунт: {}", nonce.address);
+            println!("Authority: {}", nonce.authority);
+            println!("Nonce: {}", nonce.nonce);
+            println!("Комиссия за подпись: {} lamports", nonce.lamports_per_signature);
+            Ok(())
+        }
+        NonceCommand::Advance { account } => {
+            check_authority(&rpc, &account, &payer.pubkey()).await?;
+
+            let instruction = system_instruction::advance_nonce_account(&account, &payer.pubkey());
+            let signature = send(&rpc, &[instruction], &payer, &[&payer]).await?;
+
+            let nonce = fetch_nonce_account(&rpc, &account).await?;
+            println!("Новое значение nonce: {}", nonce.nonce);
+            println!("Транзакция: {signature}");
+            Ok(())
+        }
+        NonceCommand::Withdraw { account, amount } => {
+            check_authority(&rpc, &account, &payer.pubkey()).await?;
+
+            let lamports = match amount {
+                Some(amount) => Sol(amount).to_lamports().0,
+                None => rpc.get_balance(&account).await?,
+            };
+            let instruction = system_instruction::withdraw_nonce_account(
+                &account,
+                &payer.pubkey(),
+                &payer.pubkey(),
+                lamports,
+            );
+            let signature = send(&rpc, &[instruction], &payer, &[&payer]).await?;
+
+            println!("Выведено {} lamports на {}", lamports, payer.pubkey());
+            println!("Транзакция: {signature}");
+            Ok(())
+        }
+    }
+}
+
+async fn check_authority(rpc: &RpcClient, account: &Pubkey, authority: &Pubkey) -> Result<()> {
+    let nonce = fetch_nonce_account(rpc, account).await?;
+    if nonce.authority != *authority {
+        bail!("authority аккаунта {account} — {}, а не {authority}", nonce.authority);
+    }
+    Ok(())
+}
+
+/// Подписание и отправка служебной транзакции с ожиданием подтверждения
+pub async fn send(
+    rpc: &RpcClient,
+    instructions: &[Instruction],
+    payer: &Keypair,
+    signers: &[&Keypair],
+) -> Result<Signature> {
+    let blockhash = rpc.get_latest_blockhash().await?;
+    let tx = Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), signers, blockhash);
+
+    Ok(rpc.send_and_confirm_transaction(&tx).await?)
+}
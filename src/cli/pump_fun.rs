@@ -0,0 +1,97 @@
+use crate::cli::PumpFunCommand;
+use crate::config::{PingThingsArgs, RpcType};
+use crate::pump_fun::bonding_curve::{
+    bonding_curve_accounts, fetch_bonding_curve, fetch_global, min_sol_output,
+};
+use crate::tx_senders::transaction::{
+    build_transaction_with_config, PumpFunSide, PumpFunTrade, TransactionConfig,
+};
+use crate::wallet::load_keypair;
+
+use anyhow::{bail, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use std::sync::Arc;
+
+pub async fn run(args: &PingThingsArgs, action: PumpFunCommand) -> Result<()> {
+    let rpc = RpcClient::new(args.http_rpc.clone());
+    let keypair = Arc::new(load_keypair(args)?);
+    let global = fetch_global(&rpc).await?;
+
+    match action {
+        PumpFunCommand::Quote { mint } => {
+            let (bonding_curve, _) = bonding_curve_accounts(&mint);
+            let curve = fetch_bonding_curve(&rpc, &bonding_curve).await?;
+
+            println!("Bonding curve: {bonding_curve}");
+            println!("Виртуальные резервы: {} SOL lamports / {} токенов",
+                curve.virtual_sol_reserves, curve.virtual_token_reserves);
+            println!("Реальные резервы: {} SOL lamports / {} токенов",
+                curve.real_sol_reserves, curve.real_token_reserves);
+            println!("Выпуск: {} токенов", curve.token_total_supply);
+            println!("Комиссия: {} б.п., получатель {} (authority {})",
+                global.fee_basis_points, global.fee_recipient, global.authority);
+            if curve.complete {
+                println!("Кривая заполнена, торговля перенесена на AMM");
+                return Ok(());
+            }
+
+            let amount = args.buy_amount.to_lamports();
+            println!("Покупка на {}: {} токенов", amount,
+                curve.buy_quote(amount, global.fee_basis_points));
+
+            let balance = token_balance(&rpc, &keypair.pubkey(), &mint).await;
+            if balance > 0 {
+                println!("Продажа {} токенов: {}", balance,
+                    curve.sell_quote(balance, global.fee_basis_points));
+            }
+            Ok(())
+        }
+        PumpFunCommand::Sell { mint, amount } => {
+            let (bonding_curve, associated_bonding_curve) = bonding_curve_accounts(&mint);
+            let curve = fetch_bonding_curve(&rpc, &bonding_curve).await?;
+            if curve.complete {
+                bail!("bonding curve {bonding_curve} заполнена, продажа возможна только на AMM");
+            }
+
+            let balance = token_balance(&rpc, &keypair.pubkey(), &mint).await;
+            let token_amount = amount.unwrap_or(balance);
+            if token_amount == 0 || token_amount > balance {
+                bail!("недостаточно токенов: баланс {balance}, к продаже {token_amount}");
+            }
+
+            let quote = curve.sell_quote(token_amount, global.fee_basis_points);
+            let min_output = min_sol_output(quote, args.pump_fun.slippage_bps);
+            println!("Котировка: {quote}, минимум с учётом проскальзывания: {min_output}");
+
+            let trade = PumpFunTrade {
+                mint,
+                bonding_curve,
+                associated_bonding_curve,
+                side: PumpFunSide::Sell {
+                    token_amount,
+                    min_sol_output: min_output.0,
+                },
+            };
+            let tx_config = TransactionConfig::new(args, keypair);
+            let blockhash = rpc.get_latest_blockhash().await?;
+            let tx = build_transaction_with_config(&tx_config, &RpcType::SolanaRpc, blockhash, &trade)?;
+            let signature = rpc.send_and_confirm_transaction(&tx).await?;
+
+            println!("Транзакция: {signature}");
+            Ok(())
+        }
+    }
+}
+
+/// Баланс токена на ATA кошелька; отсутствующий аккаунт считается нулевым балансом
+async fn token_balance(rpc: &RpcClient, owner: &Pubkey, mint: &Pubkey) -> u64 {
+    let account = get_associated_token_address(owner, mint);
+    rpc.get_token_account_balance(&account)
+        .await
+        .ok()
+        .and_then(|balance| balance.amount.parse().ok())
+        .unwrap_or(0)
+}
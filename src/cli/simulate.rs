@@ -0,0 +1,64 @@
+use crate::config::PingThingsArgs;
+use crate::fees::compute::ComputeProfiler;
+use crate::fees::FeeEstimator;
+use crate::meteora::fetch_and_swap::build_pool_swap;
+use crate::meteora::fetch_pool::fetch_pool_accounts;
+use crate::pools::reserves::ReserveCache;
+use crate::tx_senders::lookup_table::LookupTables;
+use crate::wallet::load_keypair;
+
+use anyhow::Result;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+
+/// Dry-run свапа: транзакция собирается как для боевой отправки, но только симулируется
+pub async fn run(args: &PingThingsArgs, pool: &Pubkey) -> Result<()> {
+    let user = load_keypair(args)?;
+    let rpc = RpcClient::new(args.http_rpc.clone());
+
+    let pool_info = fetch_pool_accounts(&rpc, pool).await?;
+    // Фоновое профилирование не нужно: симуляция ниже сама показывает расход
+    let mut profile_args = args.clone();
+    profile_args.compute_profile.enabled = false;
+    let compute = Arc::new(ComputeProfiler::new());
+    let lookup_tables = LookupTables::load(&rpc, &args.lookup_tables).await?;
+
+    let tx = build_pool_swap(
+        &profile_args,
+        &FeeEstimator::new(),
+        &compute,
+        &lookup_tables,
+        &ReserveCache::new(),
+        &rpc,
+        &user,
+        pool,
+        &pool_info.token_a_mint,
+        &pool_info.token_b_mint,
+    )
+    .await?;
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(CommitmentConfig::processed()),
+        ..Default::default()
+    };
+    let result = rpc.simulate_transaction_with_config(&tx, config).await?.value;
+
+    match &result.err {
+        Some(err) => println!("Симуляция завершилась ошибкой: {err}"),
+        None => println!("Симуляция успешна"),
+    }
+    println!("Размер транзакции: {} байт", bincode::serialize(&tx)?.len());
+    if let Some(units) = result.units_consumed {
+        println!("Потрачено compute units: {units}");
+    }
+    for line in result.logs.unwrap_or_default() {
+        println!("    {line}");
+    }
+
+    Ok(())
+}
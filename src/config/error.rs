@@ -0,0 +1,37 @@
+use std::fmt;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Ошибка конкретного поля конфигурации
+#[derive(Debug, Clone)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("не удалось прочитать файл конфигурации {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("не удалось разобрать файл конфигурации {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_yaml::Error,
+    },
+
+    #[error("некорректное значение {var}: {message}")]
+    Env { var: String, message: String },
+
+    #[error("конфигурация невалидна:\n{}", .0.iter().map(|e| format!("  - {e}")).collect::<Vec<_>>().join("\n"))]
+    Invalid(Vec<FieldError>),
+}
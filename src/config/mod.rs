@@ -1,20 +1,284 @@
+pub mod error;
+pub mod reload;
+pub mod units;
+
+pub use error::{ConfigError, FieldError};
+pub use reload::SharedConfig;
+pub use units::{Lamports, MicroLamports, Sol};
+
 use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
 use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tracing::warn;
+use zeroize::Zeroizing;
 
-#[derive(Debug, Clone, Deserialize)]
+/// Префикс переменных окружения, переопределяющих поля конфигурации
+/// (например `SNIPER_PRIVATE_KEY`, `SNIPER_RPC_BLOXROUTE_AUTH`)
+pub const ENV_PREFIX: &str = "SNIPER_";
+
+/// Максимальный лимит compute units на транзакцию
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct PingThingsArgs {
     pub rpc: HashMap<String, RpcConfig>,
     pub http_rpc: String,
     pub ws_rpc: String,
     pub geyser_url: String,
     pub geyser_x_token: String,
-    pub private_key: String,
-    pub compute_unit_price: u64,
+    /// Base58-ключ; затирается в памяти при освобождении
+    pub private_key: Zeroizing<String>,
+    pub keypair: KeypairConfig,
+    pub wallets: Vec<WalletConfig>,
+    pub wallet_selection: WalletSelection,
+    pub compute_unit_price: MicroLamports,
+    pub priority_fee: PriorityFeeConfig,
     pub compute_unit_limit: u32,
-    pub tip: f64,
-    pub buy_amount: f64,
-    pub min_amount_out: f64
+    pub compute_profile: ComputeProfileConfig,
+    pub submission: SubmissionConfig,
+    pub swap: SwapConfig,
+    pub launch: LaunchConfig,
+    pub reserves: ReservesConfig,
+    pub nonce: NonceConfig,
+    /// Address lookup tables для сборки наших транзакций (см. команду `alt`)
+    pub lookup_tables: Vec<String>,
+    /// Активные стратегии; пустой список — только `new_pool`
+    pub strategies: Vec<StrategyKind>,
+    pub pump_fun: PumpFunConfig,
+    pub raydium: RaydiumConfig,
+    pub tip: Sol,
+    pub buy_amount: Sol,
+    /// Устарело: лимит покупки Pump.fun считается по котировке bonding curve
+    /// и `pump_fun.slippage_bps`. Значение принимается для старых конфигураций и игнорируется
+    pub min_amount_out: Option<f64>,
+}
+
+/// Альтернативные источники ключа кошелька (используется ровно один, включая `private_key`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeypairConfig {
+    /// JSON-файл Solana CLI (`solana-keygen new -o id.json`)
+    pub path: Option<PathBuf>,
+    /// Имя переменной окружения с ключом (base58 или JSON-массив байт)
+    pub env: Option<String>,
+    /// Зашифрованный keystore, пароль из `SNIPER_KEYSTORE_PASSPHRASE` или с терминала
+    pub keystore: Option<PathBuf>,
+}
+
+/// Динамическая оценка цены compute unit
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PriorityFeeConfig {
+    /// `false` — всегда используется `compute_unit_price`
+    pub dynamic: bool,
+    /// Перцентиль недавних комиссий (1..=100)
+    pub percentile: u8,
+    /// Верхняя граница цены
+    pub max: MicroLamports,
+    /// Окно наблюдений из Geyser, секунды
+    pub window_secs: u64,
+}
+
+impl Default for PriorityFeeConfig {
+    fn default() -> Self {
+        Self {
+            dynamic: false,
+            percentile: 75,
+            max: MicroLamports(50_000_000),
+            window_secs: 60,
+        }
+    }
+}
+
+/// Подбор лимита compute units по симуляции форм транзакций
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ComputeProfileConfig {
+    pub enabled: bool,
+    /// Запас сверх измеренного расхода, проценты
+    pub margin_percent: u32,
+}
+
+impl Default for ComputeProfileConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            margin_percent: 20,
+        }
+    }
+}
+
+/// Параметры свапов в пулах, для которых считается котировка (DLMM, DAMM v2, Raydium)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SwapConfig {
+    /// Допуск проскальзывания относительно котировки, б.п.
+    pub slippage_bps: u16,
+}
+
+impl Default for SwapConfig {
+    fn default() -> Self {
+        Self { slippage_bps: 500 }
+    }
+}
+
+/// Покупки в пулах с точкой активации (слот или время открытия торговли)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LaunchConfig {
+    /// Откладывать покупку до активации; `false` — отправлять сразу
+    pub schedule: bool,
+    /// За сколько слотов до активации отправлять покупку (0 — в слоте активации)
+    pub lead_slots: u64,
+    /// Задержка после времени активации: часы блоков отстают от локальных, миллисекунды
+    pub timestamp_delay_ms: u64,
+    /// Пулы, которые откроются позже этого срока, пропускаются, секунды
+    pub max_wait_secs: u64,
+}
+
+impl Default for LaunchConfig {
+    fn default() -> Self {
+        Self {
+            schedule: true,
+            lead_slots: 0,
+            timestamp_delay_ms: 500,
+            max_wait_secs: 3600,
+        }
+    }
+}
+
+/// Кэш резервов пулов Meteora и Raydium по account-обновлениям Geyser
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReservesConfig {
+    /// Подписываться на аккаунты обнаруженных пулов (требует перезапуска)
+    pub enabled: bool,
+    /// Сколько пулов отслеживать одновременно; самые старые вытесняются
+    pub max_pools: usize,
+}
+
+impl Default for ReservesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_pools: 200,
+        }
+    }
+}
+
+/// Переотправка транзакции до подтверждения
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubmissionConfig {
+    /// Период переотправки, миллисекунды
+    pub rebroadcast_interval_ms: u64,
+    /// Подписывать заново со свежим blockhash после истечения прежнего
+    pub resign: bool,
+    pub max_resigns: u32,
+    /// Повышение цены compute unit при каждой повторной подписи, проценты
+    pub fee_escalation_percent: u32,
+    /// Предельное время переотправки одной подписи, секунды: по его истечении подпись
+    /// считается просроченной, даже если nonce не сдвинут или высота блока неизвестна
+    pub signature_timeout_secs: u64,
+    /// Уровень, на котором покупка считается прошедшей
+    pub commitment: ConfirmationLevel,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfirmationLevel {
+    /// Достаточно появления транзакции в потоке Geyser
+    Processed,
+    #[default]
+    Confirmed,
+}
+
+impl Default for SubmissionConfig {
+    fn default() -> Self {
+        Self {
+            rebroadcast_interval_ms: 400,
+            resign: true,
+            max_resigns: 2,
+            fee_escalation_percent: 25,
+            signature_timeout_secs: 120,
+            commitment: ConfirmationLevel::Confirmed,
+        }
+    }
+}
+
+/// Durable nonce: транзакции не зависят от recent blockhash
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NonceConfig {
+    pub enabled: bool,
+    /// Адреса nonce аккаунтов; authority каждого — один из кошельков
+    pub accounts: Vec<String>,
+}
+
+/// Дополнительный кошелёк для ротации
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WalletConfig {
+    pub name: String,
+    /// Base58-ключ; затирается в памяти при освобождении
+    pub private_key: Zeroizing<String>,
+    pub keypair: KeypairConfig,
+    /// Лимит суммарной экспозиции кошелька, без лимита если не задан
+    pub budget: Option<Sol>,
+}
+
+/// Снайпинг новых токенов Pump.fun через отправщиков Bloxroute/NextBlock
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PumpFunConfig {
+    pub enabled: bool,
+    /// Допуск проскальзывания относительно котировки bonding curve, б.п.
+    pub slippage_bps: u16,
+    /// Кривые, заполненные не меньше чем на столько процентов, отслеживаются до миграции
+    pub graduation_progress_percent: u8,
+}
+
+impl Default for PumpFunConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            slippage_bps: 500,
+            graduation_progress_percent: 80,
+        }
+    }
+}
+
+/// Пулы Raydium AMM v4 и CPMM: подписка на программы и покупки через общий контроллер
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RaydiumConfig {
+    pub enabled: bool,
+}
+
+/// Стратегия, получающая события от контроллера
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyKind {
+    /// Покупка в каждом новом пуле с WSOL
+    NewPool,
+    /// Покупка только в пулах токенов, мигрировавших с bonding curve Pump.fun
+    Graduation,
+    /// Продажа позиции, когда из пула выводят ликвидность
+    LiquidityExit,
+}
+
+/// Выбор кошелька под очередную сделку
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletSelection {
+    #[default]
+    RoundRobin,
+    LeastExposure,
+    Random,
 }
 
 #[derive(Debug, Deserialize, Default, Clone)]
@@ -27,6 +291,7 @@ pub enum RpcType {
     Nextblock,
 }
 #[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct RpcConfig {
     pub url: String,
     #[serde(default)]
@@ -35,9 +300,354 @@ pub struct RpcConfig {
     pub rpc_type: RpcType,
 }
 
+#[derive(Clone, Copy)]
+enum FieldKind {
+    Str,
+    Int,
+    Float,
+    Bool,
+    /// Список строк через запятую
+    List,
+}
+
+/// Поля верхнего уровня, которые можно переопределить через окружение.
+/// Списки задаются через запятую; `wallets` и `rpc` — только в файле
+/// (поля отправщиков переопределяются по отдельности, см. `ENV_RPC_FIELDS`).
+const ENV_FIELDS: &[(&str, FieldKind)] = &[
+    ("http_rpc", FieldKind::Str),
+    ("ws_rpc", FieldKind::Str),
+    ("geyser_url", FieldKind::Str),
+    ("geyser_x_token", FieldKind::Str),
+    ("private_key", FieldKind::Str),
+    ("wallet_selection", FieldKind::Str),
+    ("compute_unit_price", FieldKind::Int),
+    ("compute_unit_limit", FieldKind::Int),
+    ("tip", FieldKind::Float),
+    ("buy_amount", FieldKind::Float),
+    ("min_amount_out", FieldKind::Float),
+    ("lookup_tables", FieldKind::List),
+    ("strategies", FieldKind::List),
+];
+
+/// Вложенные секции конфигурации: `SNIPER_<СЕКЦИЯ>_<ПОЛЕ>`
+const ENV_SECTIONS: &[(&str, &[(&str, FieldKind)])] = &[
+    (
+        "keypair",
+        &[
+            ("path", FieldKind::Str),
+            ("env", FieldKind::Str),
+            ("keystore", FieldKind::Str),
+        ],
+    ),
+    (
+        "priority_fee",
+        &[
+            ("dynamic", FieldKind::Bool),
+            ("percentile", FieldKind::Int),
+            ("max", FieldKind::Int),
+            ("window_secs", FieldKind::Int),
+        ],
+    ),
+    (
+        "swap",
+        &[("slippage_bps", FieldKind::Int)],
+    ),
+    (
+        "launch",
+        &[
+            ("schedule", FieldKind::Bool),
+            ("lead_slots", FieldKind::Int),
+            ("timestamp_delay_ms", FieldKind::Int),
+            ("max_wait_secs", FieldKind::Int),
+        ],
+    ),
+    (
+        "reserves",
+        &[("enabled", FieldKind::Bool), ("max_pools", FieldKind::Int)],
+    ),
+    (
+        "submission",
+        &[
+            ("rebroadcast_interval_ms", FieldKind::Int),
+            ("resign", FieldKind::Bool),
+            ("max_resigns", FieldKind::Int),
+            ("fee_escalation_percent", FieldKind::Int),
+            ("signature_timeout_secs", FieldKind::Int),
+            ("commitment", FieldKind::Str),
+        ],
+    ),
+    ("nonce", &[("enabled", FieldKind::Bool), ("accounts", FieldKind::List)]),
+    (
+        "pump_fun",
+        &[
+            ("enabled", FieldKind::Bool),
+            ("slippage_bps", FieldKind::Int),
+            ("graduation_progress_percent", FieldKind::Int),
+        ],
+    ),
+    ("raydium", &[("enabled", FieldKind::Bool)]),
+    (
+        "compute_profile",
+        &[
+            ("enabled", FieldKind::Bool),
+            ("margin_percent", FieldKind::Int),
+        ],
+    ),
+];
+
+const ENV_RPC_FIELDS: &[(&str, FieldKind)] = &[
+    ("url", FieldKind::Str),
+    ("auth", FieldKind::Str),
+    ("rpc_type", FieldKind::Str),
+];
+
 impl PingThingsArgs {
-    pub fn new() -> Self {
-        let config_yaml = fs::read_to_string("./config.yaml").expect("cannot find config file");
-        serde_yaml::from_str::<PingThingsArgs>(&config_yaml).expect("invalid config file")
+    /// Загрузка конфигурации: YAML-файл, затем переопределения из окружения (и `.env`), затем валидация
+    pub fn from_path(path: &Path) -> Result<Self, ConfigError> {
+        dotenv::dotenv().ok();
+
+        let config_yaml = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let parse_error = |source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        let mut value: Value = serde_yaml::from_str(&config_yaml).map_err(parse_error)?;
+        if value.is_null() {
+            value = Value::Mapping(Mapping::new());
+        }
+        apply_env_overrides(&mut value)?;
+
+        let args = serde_yaml::from_value::<PingThingsArgs>(value).map_err(parse_error)?;
+        args.validate()?;
+        if args.min_amount_out.is_some() {
+            warn!("Поле min_amount_out устарело и игнорируется: лимит покупки задаёт pump_fun.slippage_bps");
+        }
+        Ok(args)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut errors = Vec::new();
+        let mut error = |field: &str, message: &str| {
+            errors.push(FieldError {
+                field: field.to_string(),
+                message: message.to_string(),
+            })
+        };
+
+        if self.http_rpc.is_empty() {
+            error("http_rpc", "обязательное поле");
+        } else if !is_url(&self.http_rpc, &["http://", "https://"]) {
+            error("http_rpc", "ожидается http(s):// URL");
+        }
+
+        if !self.ws_rpc.is_empty() && !is_url(&self.ws_rpc, &["ws://", "wss://"]) {
+            error("ws_rpc", "ожидается ws(s):// URL");
+        }
+
+        if !self.geyser_url.is_empty() && !is_url(&self.geyser_url, &["http://", "https://"]) {
+            error("geyser_url", "ожидается http(s):// URL");
+        }
+
+        if self.compute_unit_limit == 0 {
+            error("compute_unit_limit", "ожидается положительное значение");
+        } else if self.compute_unit_limit > MAX_COMPUTE_UNIT_LIMIT {
+            error(
+                "compute_unit_limit",
+                &format!("не может превышать {MAX_COMPUTE_UNIT_LIMIT}"),
+            );
+        }
+
+        if self.priority_fee.percentile == 0 || self.priority_fee.percentile > 100 {
+            error("priority_fee.percentile", "ожидается значение от 1 до 100");
+        }
+
+        if self.priority_fee.dynamic && self.priority_fee.max.0 == 0 {
+            error("priority_fee.max", "при dynamic: true ожидается положительный лимит");
+        }
+
+        if self.submission.rebroadcast_interval_ms == 0 {
+            error("submission.rebroadcast_interval_ms", "ожидается положительное значение");
+        }
+        if self.submission.signature_timeout_secs == 0 {
+            error("submission.signature_timeout_secs", "ожидается положительное значение");
+        }
+
+        for (index, account) in self.nonce.accounts.iter().enumerate() {
+            if solana_sdk::pubkey::Pubkey::from_str(account).is_err() {
+                error(&format!("nonce.accounts[{index}]"), "ожидается адрес аккаунта");
+            }
+        }
+        for (index, table) in self.lookup_tables.iter().enumerate() {
+            if solana_sdk::pubkey::Pubkey::from_str(table).is_err() {
+                error(&format!("lookup_tables[{index}]"), "ожидается адрес lookup table");
+            }
+        }
+
+        if self.nonce.enabled && self.nonce.accounts.is_empty() {
+            error("nonce.accounts", "при enabled: true нужен хотя бы один аккаунт");
+        }
+
+        if !self.buy_amount.0.is_finite() || self.buy_amount.0 <= 0.0 {
+            error("buy_amount", "ожидается положительная сумма в SOL");
+        }
+
+        if !self.tip.0.is_finite() || self.tip.0 < 0.0 {
+            error("tip", "ожидается неотрицательная сумма в SOL");
+        }
+
+        if self.swap.slippage_bps > 10_000 {
+            error("swap.slippage_bps", "ожидается значение от 0 до 10000");
+        }
+
+        if self.pump_fun.slippage_bps > 10_000 {
+            error("pump_fun.slippage_bps", "ожидается значение от 0 до 10000");
+        }
+
+        if self.pump_fun.graduation_progress_percent > 100 {
+            error("pump_fun.graduation_progress_percent", "ожидается значение от 0 до 100");
+        }
+
+        let mut wallet_names = std::collections::HashSet::new();
+        for (index, wallet) in self.wallets.iter().enumerate() {
+            if wallet.name.is_empty() {
+                error(&format!("wallets[{index}].name"), "обязательное поле");
+            } else if !wallet_names.insert(wallet.name.as_str()) {
+                error(&format!("wallets[{index}].name"), "имя кошелька повторяется");
+            }
+
+            if let Some(budget) = wallet.budget {
+                if !budget.0.is_finite() || budget.0 < self.buy_amount.0 {
+                    error(
+                        &format!("wallets[{index}].budget"),
+                        "бюджет должен быть не меньше buy_amount",
+                    );
+                }
+            }
+        }
+
+        for (name, rpc) in &self.rpc {
+            if !is_url(&rpc.url, &["http://", "https://"]) {
+                error(&format!("rpc.{name}.url"), "ожидается http(s):// URL");
+            }
+
+            let needs_auth = matches!(rpc.rpc_type, RpcType::Bloxroute | RpcType::Nextblock);
+            if needs_auth && rpc.auth.as_deref().unwrap_or_default().is_empty() {
+                error(
+                    &format!("rpc.{name}.auth"),
+                    &format!("обязателен ключ (можно задать через {})", rpc_env_var(name, "auth")),
+                );
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(errors))
+        }
     }
-}
\ No newline at end of file
+}
+
+fn is_url(value: &str, schemes: &[&str]) -> bool {
+    schemes
+        .iter()
+        .any(|scheme| value.len() > scheme.len() && value.starts_with(scheme))
+}
+
+fn env_var_name(field: &str) -> String {
+    format!("{ENV_PREFIX}{}", field.to_uppercase())
+}
+
+fn rpc_env_var(name: &str, field: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    format!("{ENV_PREFIX}RPC_{name}_{}", field.to_uppercase())
+}
+
+fn read_env(var: &str) -> Option<String> {
+    std::env::var(var).ok().filter(|value| !value.is_empty())
+}
+
+fn env_value(var: &str, raw: String, kind: FieldKind) -> Result<Value, ConfigError> {
+    let invalid = |message: &str| ConfigError::Env {
+        var: var.to_string(),
+        message: message.to_string(),
+    };
+
+    match kind {
+        FieldKind::Str => Ok(Value::String(raw)),
+        FieldKind::Int => raw
+            .trim()
+            .parse::<u64>()
+            .map(|v| Value::Number(v.into()))
+            .map_err(|_| invalid("ожидается целое неотрицательное число")),
+        FieldKind::Float => raw
+            .trim()
+            .parse::<f64>()
+            .map(|v| Value::Number(v.into()))
+            .map_err(|_| invalid("ожидается число")),
+        FieldKind::Bool => raw
+            .trim()
+            .parse::<bool>()
+            .map(Value::Bool)
+            .map_err(|_| invalid("ожидается true или false")),
+        FieldKind::List => Ok(Value::Sequence(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        )),
+    }
+}
+
+fn apply_env_overrides(value: &mut Value) -> Result<(), ConfigError> {
+    let Value::Mapping(root) = value else {
+        return Ok(());
+    };
+
+    for (field, kind) in ENV_FIELDS {
+        let var = env_var_name(field);
+        if let Some(raw) = read_env(&var) {
+            root.insert(Value::String(field.to_string()), env_value(&var, raw, *kind)?);
+        }
+    }
+
+    for (section, fields) in ENV_SECTIONS {
+        for (field, kind) in fields.iter() {
+            let var = env_var_name(&format!("{section}_{field}"));
+            let Some(raw) = read_env(&var) else {
+                continue;
+            };
+
+            let entry = root
+                .entry(Value::String(section.to_string()))
+                .or_insert_with(|| Value::Mapping(Mapping::new()));
+            if let Value::Mapping(entry) = entry {
+                entry.insert(Value::String(field.to_string()), env_value(&var, raw, *kind)?);
+            }
+        }
+    }
+
+    if let Some(Value::Mapping(rpcs)) = root.get_mut("rpc") {
+        for (name, rpc) in rpcs.iter_mut() {
+            let (Some(name), Value::Mapping(rpc)) = (name.as_str(), rpc) else {
+                continue;
+            };
+
+            for (field, kind) in ENV_RPC_FIELDS {
+                let var = rpc_env_var(name, field);
+                if let Some(raw) = read_env(&var) {
+                    rpc.insert(Value::String(field.to_string()), env_value(&var, raw, *kind)?);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
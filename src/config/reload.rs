@@ -0,0 +1,190 @@
+use crate::config::PingThingsArgs;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tracing::{error, info, warn};
+
+/// Период проверки файла конфигурации на изменения
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Текущая конфигурация, разделяемая между контроллером и отправщиками.
+/// Читатели получают снимок `Arc<PingThingsArgs>`, который не меняется во время сделки.
+#[derive(Clone)]
+pub struct SharedConfig(Arc<RwLock<Arc<PingThingsArgs>>>);
+
+impl SharedConfig {
+    pub fn new(args: PingThingsArgs) -> Self {
+        Self(Arc::new(RwLock::new(Arc::new(args))))
+    }
+
+    pub fn load(&self) -> Arc<PingThingsArgs> {
+        self.0.read().unwrap().clone()
+    }
+
+    fn store(&self, args: PingThingsArgs) {
+        *self.0.write().unwrap() = Arc::new(args);
+    }
+}
+
+/// Слежение за файлом конфигурации и применение параметров стратегии без перезапуска.
+/// Невалидные версии файла отклоняются, изменения полей подключения только логируются.
+pub async fn watch_config(path: PathBuf, config: SharedConfig) {
+    let mut last_modified = modified(&path);
+    let mut interval = tokio::time::interval(WATCH_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let current = modified(&path);
+        if current.is_none() || current == last_modified {
+            continue;
+        }
+        last_modified = current;
+
+        let new_args = match PingThingsArgs::from_path(&path) {
+            Ok(args) => args,
+            Err(e) => {
+                error!("Перезагрузка конфигурации отклонена: {}", e);
+                continue;
+            }
+        };
+
+        let old_args = config.load();
+        let restart_required = restart_fields_changed(&old_args, &new_args);
+        if !restart_required.is_empty() {
+            warn!(
+                "Изменения в {} применятся только после перезапуска",
+                restart_required.join(", ")
+            );
+        }
+
+        let changes = hot_reload_diff(&old_args, &new_args);
+        if changes.is_empty() {
+            continue;
+        }
+
+        let mut merged = (*old_args).clone();
+        apply_hot_fields(&mut merged, &new_args);
+        config.store(merged);
+
+        info!("Конфигурация перезагружена: {}", changes.join("; "));
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Копирование полей, которые можно менять на лету
+fn apply_hot_fields(target: &mut PingThingsArgs, source: &PingThingsArgs) {
+    target.buy_amount = source.buy_amount;
+    target.tip = source.tip;
+    target.compute_unit_price = source.compute_unit_price;
+    target.priority_fee = source.priority_fee.clone();
+    target.compute_unit_limit = source.compute_unit_limit;
+    target.compute_profile = source.compute_profile.clone();
+    target.submission = source.submission.clone();
+    target.swap = source.swap.clone();
+    target.launch = source.launch.clone();
+    target.reserves.max_pools = source.reserves.max_pools;
+    target.pump_fun.slippage_bps = source.pump_fun.slippage_bps;
+    target.pump_fun.graduation_progress_percent = source.pump_fun.graduation_progress_percent;
+}
+
+fn hot_reload_diff(old: &PingThingsArgs, new: &PingThingsArgs) -> Vec<String> {
+    let mut changes = Vec::new();
+    let mut diff = |field: &str, before: String, after: String| {
+        if before != after {
+            changes.push(format!("{field}: {before} -> {after}"));
+        }
+    };
+
+    diff("buy_amount", old.buy_amount.to_string(), new.buy_amount.to_string());
+    diff("tip", old.tip.to_string(), new.tip.to_string());
+    diff(
+        "compute_unit_price",
+        old.compute_unit_price.to_string(),
+        new.compute_unit_price.to_string(),
+    );
+    diff(
+        "priority_fee",
+        format!("{:?}", old.priority_fee),
+        format!("{:?}", new.priority_fee),
+    );
+    diff(
+        "compute_unit_limit",
+        old.compute_unit_limit.to_string(),
+        new.compute_unit_limit.to_string(),
+    );
+    diff(
+        "compute_profile",
+        format!("{:?}", old.compute_profile),
+        format!("{:?}", new.compute_profile),
+    );
+    diff(
+        "submission",
+        format!("{:?}", old.submission),
+        format!("{:?}", new.submission),
+    );
+    diff(
+        "swap.slippage_bps",
+        old.swap.slippage_bps.to_string(),
+        new.swap.slippage_bps.to_string(),
+    );
+    diff(
+        "launch",
+        format!("{:?}", old.launch),
+        format!("{:?}", new.launch),
+    );
+    diff(
+        "reserves.max_pools",
+        old.reserves.max_pools.to_string(),
+        new.reserves.max_pools.to_string(),
+    );
+    diff(
+        "pump_fun.slippage_bps",
+        old.pump_fun.slippage_bps.to_string(),
+        new.pump_fun.slippage_bps.to_string(),
+    );
+    diff(
+        "pump_fun.graduation_progress_percent",
+        old.pump_fun.graduation_progress_percent.to_string(),
+        new.pump_fun.graduation_progress_percent.to_string(),
+    );
+
+    changes
+}
+
+/// Поля подключения и ключи: их смена требует переподключения к Geyser
+fn restart_fields_changed(old: &PingThingsArgs, new: &PingThingsArgs) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    let mut check = |field: &'static str, changed: bool| {
+        if changed {
+            fields.push(field);
+        }
+    };
+
+    check("http_rpc", old.http_rpc != new.http_rpc);
+    check("ws_rpc", old.ws_rpc != new.ws_rpc);
+    check("geyser_url", old.geyser_url != new.geyser_url);
+    check("geyser_x_token", old.geyser_x_token != new.geyser_x_token);
+    check("private_key", old.private_key != new.private_key);
+    check("keypair", format!("{:?}", old.keypair) != format!("{:?}", new.keypair));
+    check("wallets", format!("{:?}", old.wallets) != format!("{:?}", new.wallets));
+    check("wallet_selection", old.wallet_selection != new.wallet_selection);
+    check("lookup_tables", old.lookup_tables != new.lookup_tables);
+    check("pump_fun", old.pump_fun.enabled != new.pump_fun.enabled);
+    check("raydium", old.raydium.enabled != new.raydium.enabled);
+    check("reserves", old.reserves.enabled != new.reserves.enabled);
+    check("strategies", old.strategies != new.strategies);
+    check("nonce", format!("{:?}", old.nonce) != format!("{:?}", new.nonce));
+    check(
+        "rpc",
+        format!("{:?}", old.rpc.iter().collect::<BTreeMap<_, _>>())
+            != format!("{:?}", new.rpc.iter().collect::<BTreeMap<_, _>>()),
+    );
+
+    fields
+}
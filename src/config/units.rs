@@ -0,0 +1,55 @@
+use serde::Deserialize;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::fmt;
+
+/// Сумма в SOL (дробное значение, как в кошельках)
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Deserialize)]
+#[serde(transparent)]
+pub struct Sol(pub f64);
+
+/// Сумма в лампортах (1 SOL = 10^9 лампортов)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(transparent)]
+pub struct Lamports(pub u64);
+
+/// Цена compute unit в микролампортах (10^-6 лампорта)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(transparent)]
+pub struct MicroLamports(pub u64);
+
+impl Sol {
+    pub fn to_lamports(self) -> Lamports {
+        Lamports((self.0 * LAMPORTS_PER_SOL as f64).round() as u64)
+    }
+}
+
+impl Lamports {
+    pub fn to_sol(self) -> Sol {
+        Sol(self.0 as f64 / LAMPORTS_PER_SOL as f64)
+    }
+}
+
+impl MicroLamports {
+    /// Итоговая приоритетная комиссия для заданного лимита compute units
+    pub fn total_for(self, compute_units: u32) -> Lamports {
+        Lamports((self.0 as u128 * compute_units as u128 / 1_000_000) as u64)
+    }
+}
+
+impl fmt::Display for Sol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} SOL", self.0)
+    }
+}
+
+impl fmt::Display for Lamports {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} lamports", self.0)
+    }
+}
+
+impl fmt::Display for MicroLamports {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} micro-lamports/CU", self.0)
+    }
+}
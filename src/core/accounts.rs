@@ -0,0 +1,27 @@
+use crate::core::decoder;
+
+use anyhow::{anyhow, Result};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Смещение поля `amount` в аккаунте SPL Token (после mint и owner)
+pub const TOKEN_AMOUNT_OFFSET: usize = 64;
+/// Смещение поля `supply` в аккаунте mint (после mint_authority)
+pub const MINT_SUPPLY_OFFSET: usize = 36;
+
+/// u64 из данных аккаунта по смещению
+pub fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    decoder::read_u64(data, offset).ok_or_else(|| anyhow!("аккаунт короче {} байт", offset + 8))
+}
+
+/// Баланс токен-аккаунта SPL Token
+pub fn token_amount(data: &[u8]) -> Result<u64> {
+    read_u64(data, TOKEN_AMOUNT_OFFSET)
+}
+
+/// Unix-время, секунды; сравнивается с `open_time` и точками активации пулов
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,118 @@
+use crate::geyser::subscriptions::AccountSubscriptions;
+use crate::tx_senders::lookup_table::fetch_lookup_table;
+
+use anyhow::{anyhow, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::message::v0::{LoadedAddresses, MessageAddressTableLookup};
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tracing::debug;
+
+/// Кэш содержимого address lookup tables для разбора v0 транзакций без meta.
+/// Таблицы запрашиваются через RPC при первом обращении, дальше обновляются
+/// по account-обновлениям из Geyser (таблицы только расширяются или закрываются).
+/// Подписка оформляется только на закэшированные таблицы.
+pub struct AltResolver {
+    rpc: Arc<RpcClient>,
+    tables: RwLock<HashMap<Pubkey, Vec<Pubkey>>>,
+    subscriptions: Option<Arc<AccountSubscriptions>>,
+}
+
+impl AltResolver {
+    pub fn new(rpc: Arc<RpcClient>) -> Self {
+        Self {
+            rpc,
+            tables: RwLock::new(HashMap::new()),
+            subscriptions: None,
+        }
+    }
+
+    pub fn with_subscriptions(mut self, subscriptions: Arc<AccountSubscriptions>) -> Self {
+        self.subscriptions = Some(subscriptions);
+        self
+    }
+
+    /// Адреса, подгружаемые транзакцией из lookup tables (пусто для legacy)
+    pub async fn resolve(&self, tx: &VersionedTransaction) -> Result<LoadedAddresses> {
+        let VersionedMessage::V0(message) = &tx.message else {
+            return Ok(LoadedAddresses::default());
+        };
+
+        let mut loaded = LoadedAddresses::default();
+        for lookup in &message.address_table_lookups {
+            let addresses = self.table_for(lookup).await?;
+            let select = |indexes: &[u8]| {
+                indexes
+                    .iter()
+                    .map(|&index| addresses[index as usize])
+                    .collect::<Vec<_>>()
+            };
+            loaded.writable.extend(select(&lookup.writable_indexes));
+            loaded.readonly.extend(select(&lookup.readonly_indexes));
+        }
+
+        Ok(loaded)
+    }
+
+    /// Содержимое таблицы, покрывающее все индексы lookup; при нехватке адресов кэш обновляется через RPC
+    async fn table_for(&self, lookup: &MessageAddressTableLookup) -> Result<Vec<Pubkey>> {
+        let max_index = lookup
+            .writable_indexes
+            .iter()
+            .chain(&lookup.readonly_indexes)
+            .copied()
+            .max()
+            .map_or(0, |index| index as usize + 1);
+
+        if let Some(addresses) = self.tables.read().unwrap().get(&lookup.account_key) {
+            if addresses.len() >= max_index {
+                return Ok(addresses.clone());
+            }
+        }
+
+        let table = fetch_lookup_table(&self.rpc, &lookup.account_key).await?;
+        debug!("Lookup table {} загружена ({} адресов)", table.key, table.addresses.len());
+        if table.addresses.len() < max_index {
+            return Err(anyhow!(
+                "в lookup table {} {} адресов, транзакция ссылается на индекс {}",
+                table.key,
+                table.addresses.len(),
+                max_index - 1
+            ));
+        }
+
+        let inserted = self
+            .tables
+            .write()
+            .unwrap()
+            .insert(lookup.account_key, table.addresses.clone())
+            .is_none();
+        if let (true, Some(subscriptions)) = (inserted, &self.subscriptions) {
+            subscriptions.subscribe(&[lookup.account_key]);
+        }
+        Ok(table.addresses)
+    }
+
+    /// Обновление из потока Geyser: учитываются только таблицы, уже находящиеся в кэше
+    pub fn update(&self, key: &Pubkey, data: &[u8]) {
+        let mut tables = self.tables.write().unwrap();
+        let Some(cached) = tables.get_mut(key) else {
+            return;
+        };
+
+        match AddressLookupTable::deserialize(data) {
+            Ok(table) => *cached = table.addresses.to_vec(),
+            // Таблица закрыта: при следующем обращении она будет запрошена заново
+            Err(_) => {
+                tables.remove(key);
+                if let Some(subscriptions) = &self.subscriptions {
+                    subscriptions.unsubscribe(&[*key]);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::{TransactionStatusMeta, TransactionTokenBalance};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Изменение баланса токена в минимальных единицах
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenDelta {
+    pub amount: i128,
+    pub decimals: u8,
+}
+
+/// Изменения балансов, вызванные транзакцией
+#[derive(Debug, Clone, Default)]
+pub struct BalanceChanges {
+    /// Lamports по адресу аккаунта (включая комиссию для плательщика)
+    pub sol: HashMap<Pubkey, i128>,
+    /// Токены по паре (владелец, mint)
+    pub tokens: HashMap<(Pubkey, Pubkey), TokenDelta>,
+}
+
+impl BalanceChanges {
+    pub fn sol_delta(&self, account: &Pubkey) -> i128 {
+        self.sol.get(account).copied().unwrap_or_default()
+    }
+
+    pub fn token_delta(&self, owner: &Pubkey, mint: &Pubkey) -> i128 {
+        self.tokens
+            .get(&(*owner, *mint))
+            .map(|delta| delta.amount)
+            .unwrap_or_default()
+    }
+
+    /// Все изменения токенов владельца по mint
+    pub fn tokens_of(&self, owner: &Pubkey) -> impl Iterator<Item = (&Pubkey, &TokenDelta)> {
+        let owner = *owner;
+        self.tokens
+            .iter()
+            .filter(move |((token_owner, _), _)| *token_owner == owner)
+            .map(|((_, mint), delta)| (mint, delta))
+    }
+}
+
+/// Ключи аккаунтов в порядке индексов `pre_balances`/`post_balances`:
+/// статические ключи, затем writable и readonly адреса из lookup tables
+pub fn account_keys(transaction: &VersionedTransaction, meta: &TransactionStatusMeta) -> Vec<Pubkey> {
+    let mut keys = transaction.message.static_account_keys().to_vec();
+    if let VersionedMessage::V0(_) = transaction.message {
+        keys.extend(&meta.loaded_addresses.writable);
+        keys.extend(&meta.loaded_addresses.readonly);
+    }
+    keys
+}
+
+/// Разница pre/post балансов SOL и токенов. Аккаунты, созданные или закрытые
+/// в транзакции, считаются с нулевым балансом до или после.
+pub fn balance_changes(transaction: &VersionedTransaction, meta: &TransactionStatusMeta) -> BalanceChanges {
+    let keys = account_keys(transaction, meta);
+    let mut changes = BalanceChanges::default();
+
+    for (index, (pre, post)) in meta.pre_balances.iter().zip(&meta.post_balances).enumerate() {
+        let Some(key) = keys.get(index) else {
+            continue;
+        };
+        let delta = *post as i128 - *pre as i128;
+        if delta != 0 {
+            *changes.sol.entry(*key).or_default() += delta;
+        }
+    }
+
+    let empty = Vec::new();
+    let pre_tokens = meta.pre_token_balances.as_ref().unwrap_or(&empty);
+    let post_tokens = meta.post_token_balances.as_ref().unwrap_or(&empty);
+
+    for (balances, sign) in [(pre_tokens, -1i128), (post_tokens, 1i128)] {
+        for balance in balances {
+            let Some((owner, mint, amount, decimals)) = parse_token_balance(balance) else {
+                continue;
+            };
+            let entry = changes.tokens.entry((owner, mint)).or_default();
+            entry.amount += sign * amount as i128;
+            entry.decimals = decimals;
+        }
+    }
+    changes.tokens.retain(|_, delta| delta.amount != 0);
+
+    changes
+}
+
+fn parse_token_balance(balance: &TransactionTokenBalance) -> Option<(Pubkey, Pubkey, u64, u8)> {
+    let owner = Pubkey::from_str(&balance.owner).ok()?;
+    let mint = Pubkey::from_str(&balance.mint).ok()?;
+    let amount = balance.ui_token_amount.amount.parse().ok()?;
+    Some((owner, mint, amount, balance.ui_token_amount.decimals))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::message::{v0, Message};
+    use yellowstone_grpc_proto::convert_from::create_tx_meta;
+    use yellowstone_grpc_proto::prelude::{
+        TokenBalance, TransactionStatusMeta as YellowstoneMeta, UiTokenAmount,
+    };
+
+    fn legacy(keys: &[Pubkey]) -> VersionedTransaction {
+        VersionedTransaction {
+            signatures: vec![Default::default()],
+            message: VersionedMessage::Legacy(Message {
+                account_keys: keys.to_vec(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn token_balance(owner: &Pubkey, mint: &Pubkey, amount: u64, decimals: u8) -> TokenBalance {
+        TokenBalance {
+            owner: owner.to_string(),
+            mint: mint.to_string(),
+            ui_token_amount: Some(UiTokenAmount {
+                amount: amount.to_string(),
+                decimals: decimals as u32,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn meta(meta: YellowstoneMeta) -> TransactionStatusMeta {
+        create_tx_meta(meta).expect("meta из потока Geyser")
+    }
+
+    #[test]
+    fn sol_changes_by_account() {
+        let (payer, pool, program) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let tx = legacy(&[payer, pool, program]);
+        let meta = meta(YellowstoneMeta {
+            pre_balances: vec![1_000_000, 0, 1],
+            post_balances: vec![894_000, 100_000, 1],
+            ..Default::default()
+        });
+
+        let changes = balance_changes(&tx, &meta);
+        assert_eq!(changes.sol_delta(&payer), -106_000);
+        assert_eq!(changes.sol_delta(&pool), 100_000);
+        assert!(!changes.sol.contains_key(&program), "неизменный баланс не попадает в изменения");
+    }
+
+    #[test]
+    fn token_changes_of_created_closed_and_unchanged_accounts() {
+        let (wallet, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (mint, wsol, stable) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let tx = legacy(&[wallet]);
+        let meta = meta(YellowstoneMeta {
+            pre_token_balances: vec![
+                token_balance(&wallet, &wsol, 300, 9),
+                token_balance(&other, &stable, 50, 6),
+            ],
+            // Аккаунт токена создан в транзакции, WSOL закрыт
+            post_token_balances: vec![
+                token_balance(&wallet, &mint, 500, 6),
+                token_balance(&other, &stable, 50, 6),
+            ],
+            ..Default::default()
+        });
+
+        let changes = balance_changes(&tx, &meta);
+        let cases = [
+            // (владелец, mint, изменение)
+            (wallet, mint, 500),
+            (wallet, wsol, -300),
+            (other, stable, 0),
+        ];
+        for (owner, mint, expected) in cases {
+            assert_eq!(changes.token_delta(&owner, &mint), expected, "{owner} / {mint}");
+        }
+        assert_eq!(changes.tokens[&(wallet, mint)].decimals, 6);
+        assert!(!changes.tokens.contains_key(&(other, stable)));
+
+        let mut mints: Vec<Pubkey> = changes.tokens_of(&wallet).map(|(mint, _)| *mint).collect();
+        mints.sort();
+        let mut expected = vec![mint, wsol];
+        expected.sort();
+        assert_eq!(mints, expected);
+        assert_eq!(changes.tokens_of(&other).count(), 0);
+    }
+
+    #[test]
+    fn token_balance_with_bad_owner_is_skipped() {
+        let (wallet, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut broken = token_balance(&wallet, &mint, 500, 6);
+        broken.owner = String::new();
+        let meta = meta(YellowstoneMeta {
+            post_token_balances: vec![broken],
+            ..Default::default()
+        });
+
+        assert!(balance_changes(&legacy(&[wallet]), &meta).tokens.is_empty());
+    }
+
+    #[test]
+    fn lookup_table_addresses_follow_static_keys() {
+        let payer = Pubkey::new_unique();
+        let (writable, readonly) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let tx = VersionedTransaction {
+            signatures: vec![Default::default()],
+            message: VersionedMessage::V0(v0::Message {
+                account_keys: vec![payer],
+                ..Default::default()
+            }),
+        };
+        let meta = meta(YellowstoneMeta {
+            loaded_writable_addresses: vec![writable.to_bytes().to_vec()],
+            loaded_readonly_addresses: vec![readonly.to_bytes().to_vec()],
+            pre_balances: vec![1_000, 0, 7],
+            post_balances: vec![900, 100, 7],
+            ..Default::default()
+        });
+
+        assert_eq!(account_keys(&tx, &meta), [payer, writable, readonly]);
+        let changes = balance_changes(&tx, &meta);
+        assert_eq!(changes.sol_delta(&writable), 100);
+        assert_eq!(changes.sol_delta(&readonly), 0);
+    }
+}
@@ -0,0 +1,335 @@
+use crate::core::decoder::{account, read_u64, DecodedInstruction, ProgramDecoder};
+use crate::core::logs::anchor_discriminator;
+use crate::tx_senders::constants::{
+    METEORA_DAMM_V2_PROGRAM_ID, METEORA_DLMM_PROGRAM_ID, METEORA_PROGRAM_ID, VAULT_PROGRAM_ID,
+};
+
+use lazy_static::lazy_static;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+
+/// Инструкции Meteora Dynamic AMM
+#[derive(Debug, Clone)]
+pub enum MeteoraAmmInstruction {
+    /// Одна из инструкций создания пула
+    InitializePool {
+        kind: &'static str,
+        pool: Pubkey,
+        lp_mint: Pubkey,
+        token_a_mint: Pubkey,
+        token_b_mint: Pubkey,
+        a_vault: Pubkey,
+        b_vault: Pubkey,
+        payer: Pubkey,
+    },
+    Swap {
+        pool: Pubkey,
+        user: Pubkey,
+        in_amount: u64,
+        minimum_out_amount: u64,
+    },
+    AddLiquidity {
+        pool: Pubkey,
+        user: Pubkey,
+        pool_token_amount: u64,
+    },
+    RemoveLiquidity {
+        pool: Pubkey,
+        user: Pubkey,
+        pool_token_amount: u64,
+    },
+}
+
+/// Позиции аккаунтов в инструкции создания пула
+struct InitializeLayout {
+    kind: &'static str,
+    lp_mint: usize,
+    token_a_mint: usize,
+    token_b_mint: usize,
+    a_vault: usize,
+    b_vault: usize,
+    payer: usize,
+}
+
+/// lp_mint, token_a_mint, token_b_mint, a_vault, b_vault, payer (pool всегда первый)
+const POOL_LAYOUT: (usize, usize, usize, usize, usize, usize) = (1, 2, 3, 4, 5, 17);
+/// Пулы с config: после pool идёт аккаунт config, остальные сдвинуты на один
+const CONFIG_POOL_LAYOUT: (usize, usize, usize, usize, usize, usize) = (2, 3, 4, 5, 6, 18);
+
+lazy_static! {
+    static ref INITIALIZE: Vec<([u8; 8], InitializeLayout)> = [
+        ("initialize_permissionless_pool", POOL_LAYOUT),
+        ("initialize_permissionless_pool_with_fee_tier", POOL_LAYOUT),
+        ("initialize_customizable_permissionless_constant_product_pool", POOL_LAYOUT),
+        ("initialize_permissionless_constant_product_pool_with_config", CONFIG_POOL_LAYOUT),
+        ("initialize_permissionless_constant_product_pool_with_config2", CONFIG_POOL_LAYOUT),
+    ]
+    .into_iter()
+    .map(|(kind, (lp_mint, token_a_mint, token_b_mint, a_vault, b_vault, payer))| {
+        (
+            anchor_discriminator("global", kind),
+            InitializeLayout { kind, lp_mint, token_a_mint, token_b_mint, a_vault, b_vault, payer },
+        )
+    })
+    .collect();
+    static ref SWAP: [u8; 8] = anchor_discriminator("global", "swap");
+    static ref ADD_BALANCE_LIQUIDITY: [u8; 8] = anchor_discriminator("global", "add_balance_liquidity");
+    static ref ADD_IMBALANCE_LIQUIDITY: [u8; 8] = anchor_discriminator("global", "add_imbalance_liquidity");
+    static ref REMOVE_BALANCE_LIQUIDITY: [u8; 8] = anchor_discriminator("global", "remove_balance_liquidity");
+    /// Создание пары DLMM: адреса lb_pair, mint X, mint Y и создателя
+    static ref INITIALIZE_LB_PAIR: Vec<([u8; 8], &'static str, (usize, usize, usize, usize))> = [
+        ("initialize_lb_pair", (0, 2, 3, 8)),
+        ("initialize_lb_pair2", (0, 2, 3, 8)),
+        ("initialize_customizable_permissionless_lb_pair", (0, 2, 3, 8)),
+        ("initialize_customizable_permissionless_lb_pair2", (0, 2, 3, 8)),
+        // Первым идёт base keypair, из которого выводится адрес пары
+        ("initialize_permission_lb_pair", (1, 3, 4, 8)),
+    ]
+    .into_iter()
+    .map(|(kind, layout)| (anchor_discriminator("global", kind), kind, layout))
+    .collect();
+    /// Создание пула DAMM v2: адреса pool, mint A, mint B, создателя и NFT позиции
+    static ref INITIALIZE_DAMM_V2_POOL: Vec<([u8; 8], &'static str, (usize, usize, usize, usize, usize))> = [
+        ("initialize_pool", (6, 8, 9, 0, 1)),
+        // Перед config идёт подпись pool_creator_authority
+        ("initialize_pool_with_dynamic_config", (7, 9, 10, 0, 1)),
+        // Без аккаунта config: комиссии передаются в аргументах
+        ("initialize_customizable_pool", (5, 7, 8, 0, 1)),
+    ]
+    .into_iter()
+    .map(|(kind, layout)| (anchor_discriminator("global", kind), kind, layout))
+    .collect();
+    static ref DEPOSIT: [u8; 8] = anchor_discriminator("global", "deposit");
+    static ref WITHDRAW: [u8; 8] = anchor_discriminator("global", "withdraw");
+}
+
+pub struct MeteoraAmmDecoder;
+
+impl ProgramDecoder for MeteoraAmmDecoder {
+    fn program_id(&self) -> Pubkey {
+        METEORA_PROGRAM_ID
+    }
+
+    fn name(&self) -> &'static str {
+        "Meteora AMM"
+    }
+
+    fn decode(&self, ix: &Instruction) -> Option<DecodedInstruction> {
+        let discriminator: [u8; 8] = ix.data.get(..8)?.try_into().ok()?;
+
+        if let Some((_, layout)) = INITIALIZE.iter().find(|(known, _)| *known == discriminator) {
+            return Some(DecodedInstruction::MeteoraAmm(MeteoraAmmInstruction::InitializePool {
+                kind: layout.kind,
+                pool: account(ix, 0)?,
+                lp_mint: account(ix, layout.lp_mint)?,
+                token_a_mint: account(ix, layout.token_a_mint)?,
+                token_b_mint: account(ix, layout.token_b_mint)?,
+                a_vault: account(ix, layout.a_vault)?,
+                b_vault: account(ix, layout.b_vault)?,
+                payer: account(ix, layout.payer)?,
+            }));
+        }
+
+        let decoded = if discriminator == *SWAP {
+            MeteoraAmmInstruction::Swap {
+                pool: account(ix, 0)?,
+                user: account(ix, 12)?,
+                in_amount: read_u64(&ix.data, 8)?,
+                minimum_out_amount: read_u64(&ix.data, 16)?,
+            }
+        } else if discriminator == *ADD_BALANCE_LIQUIDITY || discriminator == *ADD_IMBALANCE_LIQUIDITY {
+            MeteoraAmmInstruction::AddLiquidity {
+                pool: account(ix, 0)?,
+                user: account(ix, 13)?,
+                pool_token_amount: read_u64(&ix.data, 8)?,
+            }
+        } else if discriminator == *REMOVE_BALANCE_LIQUIDITY {
+            MeteoraAmmInstruction::RemoveLiquidity {
+                pool: account(ix, 0)?,
+                user: account(ix, 13)?,
+                pool_token_amount: read_u64(&ix.data, 8)?,
+            }
+        } else {
+            return None;
+        };
+
+        Some(DecodedInstruction::MeteoraAmm(decoded))
+    }
+}
+
+/// Инструкции программы хранилищ Meteora (Dynamic Vault)
+#[derive(Debug, Clone)]
+pub enum MeteoraVaultInstruction {
+    Deposit {
+        vault: Pubkey,
+        user: Pubkey,
+        token_amount: u64,
+        minimum_lp_token_amount: u64,
+    },
+    Withdraw {
+        vault: Pubkey,
+        user: Pubkey,
+        unmint_amount: u64,
+        min_out_amount: u64,
+    },
+}
+
+pub struct MeteoraVaultDecoder;
+
+impl ProgramDecoder for MeteoraVaultDecoder {
+    fn program_id(&self) -> Pubkey {
+        VAULT_PROGRAM_ID
+    }
+
+    fn name(&self) -> &'static str {
+        "Meteora Vault"
+    }
+
+    fn decode(&self, ix: &Instruction) -> Option<DecodedInstruction> {
+        let discriminator: [u8; 8] = ix.data.get(..8)?.try_into().ok()?;
+
+        let decoded = if discriminator == *DEPOSIT {
+            MeteoraVaultInstruction::Deposit {
+                vault: account(ix, 0)?,
+                user: account(ix, 5)?,
+                token_amount: read_u64(&ix.data, 8)?,
+                minimum_lp_token_amount: read_u64(&ix.data, 16)?,
+            }
+        } else if discriminator == *WITHDRAW {
+            MeteoraVaultInstruction::Withdraw {
+                vault: account(ix, 0)?,
+                user: account(ix, 5)?,
+                unmint_amount: read_u64(&ix.data, 8)?,
+                min_out_amount: read_u64(&ix.data, 16)?,
+            }
+        } else {
+            return None;
+        };
+
+        Some(DecodedInstruction::MeteoraVault(decoded))
+    }
+}
+
+/// Инструкции Meteora DLMM (liquidity book)
+#[derive(Debug, Clone)]
+pub enum MeteoraDlmmInstruction {
+    /// Одна из инструкций создания пары
+    InitializeLbPair {
+        kind: &'static str,
+        lb_pair: Pubkey,
+        token_x_mint: Pubkey,
+        token_y_mint: Pubkey,
+        creator: Pubkey,
+        active_id: i32,
+        bin_step: u16,
+    },
+    Swap {
+        lb_pair: Pubkey,
+        user: Pubkey,
+        amount_in: u64,
+        min_amount_out: u64,
+    },
+}
+
+pub struct MeteoraDlmmDecoder;
+
+impl ProgramDecoder for MeteoraDlmmDecoder {
+    fn program_id(&self) -> Pubkey {
+        METEORA_DLMM_PROGRAM_ID
+    }
+
+    fn name(&self) -> &'static str {
+        "Meteora DLMM"
+    }
+
+    fn decode(&self, ix: &Instruction) -> Option<DecodedInstruction> {
+        let discriminator: [u8; 8] = ix.data.get(..8)?.try_into().ok()?;
+
+        // У всех вариантов создания аргументы начинаются с active_id: i32 и bin_step: u16
+        if let Some((_, kind, (lb_pair, token_x, token_y, creator))) =
+            INITIALIZE_LB_PAIR.iter().find(|(known, _, _)| *known == discriminator)
+        {
+            return Some(DecodedInstruction::MeteoraDlmm(MeteoraDlmmInstruction::InitializeLbPair {
+                kind,
+                lb_pair: account(ix, *lb_pair)?,
+                token_x_mint: account(ix, *token_x)?,
+                token_y_mint: account(ix, *token_y)?,
+                creator: account(ix, *creator)?,
+                active_id: i32::from_le_bytes(ix.data.get(8..12)?.try_into().ok()?),
+                bin_step: u16::from_le_bytes(ix.data.get(12..14)?.try_into().ok()?),
+            }));
+        }
+
+        if discriminator != *SWAP {
+            return None;
+        }
+
+        Some(DecodedInstruction::MeteoraDlmm(MeteoraDlmmInstruction::Swap {
+            lb_pair: account(ix, 0)?,
+            user: account(ix, 10)?,
+            amount_in: read_u64(&ix.data, 8)?,
+            min_amount_out: read_u64(&ix.data, 16)?,
+        }))
+    }
+}
+
+/// Инструкции Meteora DAMM v2 (cp-amm)
+#[derive(Debug, Clone)]
+pub enum MeteoraDammV2Instruction {
+    /// Одна из инструкций создания пула
+    InitializePool {
+        kind: &'static str,
+        pool: Pubkey,
+        token_a_mint: Pubkey,
+        token_b_mint: Pubkey,
+        creator: Pubkey,
+        /// Первая позиция ликвидности пула оформляется как NFT
+        position_nft_mint: Pubkey,
+    },
+    Swap {
+        pool: Pubkey,
+        user: Pubkey,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    },
+}
+
+pub struct MeteoraDammV2Decoder;
+
+impl ProgramDecoder for MeteoraDammV2Decoder {
+    fn program_id(&self) -> Pubkey {
+        METEORA_DAMM_V2_PROGRAM_ID
+    }
+
+    fn name(&self) -> &'static str {
+        "Meteora DAMM v2"
+    }
+
+    fn decode(&self, ix: &Instruction) -> Option<DecodedInstruction> {
+        let discriminator: [u8; 8] = ix.data.get(..8)?.try_into().ok()?;
+
+        if let Some((_, kind, (pool, token_a, token_b, creator, position_nft_mint))) =
+            INITIALIZE_DAMM_V2_POOL.iter().find(|(known, _, _)| *known == discriminator)
+        {
+            return Some(DecodedInstruction::MeteoraDammV2(MeteoraDammV2Instruction::InitializePool {
+                kind,
+                pool: account(ix, *pool)?,
+                token_a_mint: account(ix, *token_a)?,
+                token_b_mint: account(ix, *token_b)?,
+                creator: account(ix, *creator)?,
+                position_nft_mint: account(ix, *position_nft_mint)?,
+            }));
+        }
+
+        if discriminator != *SWAP {
+            return None;
+        }
+
+        Some(DecodedInstruction::MeteoraDammV2(MeteoraDammV2Instruction::Swap {
+            pool: account(ix, 1)?,
+            user: account(ix, 8)?,
+            amount_in: read_u64(&ix.data, 8)?,
+            minimum_amount_out: read_u64(&ix.data, 16)?,
+        }))
+    }
+}
@@ -0,0 +1,212 @@
+pub mod meteora;
+pub mod native;
+pub mod pump_fun;
+pub mod raydium;
+
+use crate::core::decoder::meteora::{
+    MeteoraAmmDecoder, MeteoraAmmInstruction, MeteoraDammV2Decoder, MeteoraDammV2Instruction,
+    MeteoraDlmmDecoder, MeteoraDlmmInstruction, MeteoraVaultDecoder, MeteoraVaultInstruction,
+};
+use crate::core::decoder::native::{
+    AssociatedTokenDecoder, ComputeBudgetDecoder, SplTokenDecoder, SystemDecoder, TokenInstruction,
+};
+use crate::core::decoder::pump_fun::{PumpFunDecoder, PumpFunInstruction};
+use crate::core::decoder::raydium::{
+    RaydiumAmmV4Decoder, RaydiumAmmV4Instruction, RaydiumCpmmDecoder, RaydiumCpmmInstruction,
+};
+
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction::SystemInstruction;
+use spl_associated_token_account::instruction::AssociatedTokenAccountInstruction;
+use std::collections::HashMap;
+
+/// Инструкция, разобранная декодером своей программы
+#[derive(Debug, Clone)]
+pub enum DecodedInstruction {
+    MeteoraAmm(MeteoraAmmInstruction),
+    MeteoraVault(MeteoraVaultInstruction),
+    MeteoraDlmm(MeteoraDlmmInstruction),
+    MeteoraDammV2(MeteoraDammV2Instruction),
+    PumpFun(PumpFunInstruction),
+    RaydiumAmmV4(RaydiumAmmV4Instruction),
+    RaydiumCpmm(RaydiumCpmmInstruction),
+    SplToken(TokenInstruction),
+    AssociatedToken(AssociatedTokenAccountInstruction),
+    ComputeBudget(ComputeBudgetInstruction),
+    System(SystemInstruction),
+}
+
+/// Декодер инструкций одной программы
+pub trait ProgramDecoder: Send + Sync {
+    fn program_id(&self) -> Pubkey;
+
+    fn name(&self) -> &'static str;
+
+    /// `None`, если инструкция программе не известна или данные повреждены
+    fn decode(&self, instruction: &Instruction) -> Option<DecodedInstruction>;
+}
+
+/// Декодеры по адресу программы
+pub struct DecoderRegistry {
+    decoders: HashMap<Pubkey, Box<dyn ProgramDecoder>>,
+}
+
+impl DecoderRegistry {
+    pub fn empty() -> Self {
+        Self {
+            decoders: HashMap::new(),
+        }
+    }
+
+    /// Регистрация декодера; декодер той же программы заменяется
+    pub fn register(&mut self, decoder: Box<dyn ProgramDecoder>) {
+        self.decoders.insert(decoder.program_id(), decoder);
+    }
+
+    pub fn decode(&self, instruction: &Instruction) -> Option<DecodedInstruction> {
+        self.decoders.get(&instruction.program_id)?.decode(instruction)
+    }
+
+    pub fn program_name(&self, program_id: &Pubkey) -> Option<&'static str> {
+        self.decoders.get(program_id).map(|decoder| decoder.name())
+    }
+}
+
+impl Default for DecoderRegistry {
+    /// Все известные проекту программы
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Box::new(MeteoraAmmDecoder));
+        registry.register(Box::new(MeteoraVaultDecoder));
+        registry.register(Box::new(MeteoraDlmmDecoder));
+        registry.register(Box::new(MeteoraDammV2Decoder));
+        registry.register(Box::new(PumpFunDecoder));
+        registry.register(Box::new(RaydiumAmmV4Decoder));
+        registry.register(Box::new(RaydiumCpmmDecoder));
+        registry.register(Box::new(SplTokenDecoder));
+        registry.register(Box::new(AssociatedTokenDecoder));
+        registry.register(Box::new(ComputeBudgetDecoder));
+        registry.register(Box::new(SystemDecoder));
+        registry
+    }
+}
+
+/// Адрес аккаунта инструкции по позиции
+pub(crate) fn account(instruction: &Instruction, index: usize) -> Option<Pubkey> {
+    instruction.accounts.get(index).map(|meta| meta.pubkey)
+}
+
+/// Чтение little-endian u64 из данных инструкции
+pub(crate) fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::decoder::pump_fun::{BUY_DISCRIMINATOR, SELL_DISCRIMINATOR, WITHDRAW_DISCRIMINATOR};
+    use crate::core::logs::anchor_discriminator;
+    use crate::tx_senders::constants::METEORA_PROGRAM_ID;
+    use solana_sdk::instruction::AccountMeta;
+
+    fn instruction(program_id: Pubkey, accounts: &[Pubkey], data: Vec<u8>) -> Instruction {
+        Instruction {
+            program_id,
+            accounts: accounts.iter().map(|key| AccountMeta::new(*key, false)).collect(),
+            data,
+        }
+    }
+
+    fn anchor_data(name: &str, args: &[u64]) -> Vec<u8> {
+        let mut data = anchor_discriminator("global", name).to_vec();
+        for arg in args {
+            data.extend_from_slice(&arg.to_le_bytes());
+        }
+        data
+    }
+
+    fn keys(count: usize) -> Vec<Pubkey> {
+        (0..count).map(|_| Pubkey::new_unique()).collect()
+    }
+
+    #[test]
+    fn pump_fun_discriminators_match_anchor() {
+        assert_eq!(BUY_DISCRIMINATOR, anchor_discriminator("global", "buy"));
+        assert_eq!(SELL_DISCRIMINATOR, anchor_discriminator("global", "sell"));
+        assert_eq!(WITHDRAW_DISCRIMINATOR, anchor_discriminator("global", "withdraw"));
+    }
+
+    #[test]
+    fn meteora_swap_accounts_and_amounts() {
+        let registry = DecoderRegistry::default();
+        let accounts = keys(15);
+        let ix = instruction(METEORA_PROGRAM_ID, &accounts, anchor_data("swap", &[1_000, 900]));
+
+        let Some(DecodedInstruction::MeteoraAmm(MeteoraAmmInstruction::Swap {
+            pool, user, in_amount, minimum_out_amount,
+        })) = registry.decode(&ix)
+        else {
+            panic!("ожидался свап Meteora AMM");
+        };
+        assert_eq!(pool, accounts[0]);
+        assert_eq!(user, accounts[12]);
+        assert_eq!((in_amount, minimum_out_amount), (1_000, 900));
+    }
+
+    #[test]
+    fn meteora_initialize_layouts() {
+        let registry = DecoderRegistry::default();
+        let accounts = keys(20);
+        let cases = [
+            // (инструкция, позиция lp_mint, позиция плательщика)
+            ("initialize_permissionless_pool", 1, 17),
+            ("initialize_permissionless_constant_product_pool_with_config2", 2, 18),
+        ];
+
+        for (kind, lp_mint_index, payer_index) in cases {
+            let ix = instruction(METEORA_PROGRAM_ID, &accounts, anchor_data(kind, &[]));
+            let Some(DecodedInstruction::MeteoraAmm(MeteoraAmmInstruction::InitializePool {
+                kind: decoded_kind, pool, lp_mint, payer, ..
+            })) = registry.decode(&ix)
+            else {
+                panic!("{kind}: ожидалось создание пула");
+            };
+            assert_eq!(decoded_kind, kind);
+            assert_eq!(pool, accounts[0], "{kind}");
+            assert_eq!(lp_mint, accounts[lp_mint_index], "{kind}");
+            assert_eq!(payer, accounts[payer_index], "{kind}");
+        }
+    }
+
+    #[test]
+    fn malformed_instructions_are_not_decoded() {
+        let registry = DecoderRegistry::default();
+        let cases = [
+            ("нет аргументов", instruction(METEORA_PROGRAM_ID, &keys(15), anchor_data("swap", &[]))),
+            ("не хватает аккаунтов", instruction(METEORA_PROGRAM_ID, &keys(5), anchor_data("swap", &[1, 2]))),
+            ("короткий дискриминатор", instruction(METEORA_PROGRAM_ID, &keys(15), vec![1, 2, 3])),
+            ("неизвестная инструкция", instruction(METEORA_PROGRAM_ID, &keys(15), anchor_data("claim_fee", &[1]))),
+            ("неизвестная программа", instruction(Pubkey::new_unique(), &keys(15), anchor_data("swap", &[1, 2]))),
+        ];
+
+        for (name, ix) in cases {
+            assert!(registry.decode(&ix).is_none(), "{name}");
+        }
+    }
+
+    #[test]
+    fn native_programs_are_registered() {
+        let registry = DecoderRegistry::default();
+        let ix = ComputeBudgetInstruction::set_compute_unit_price(5_000);
+
+        assert!(matches!(
+            registry.decode(&ix),
+            Some(DecodedInstruction::ComputeBudget(ComputeBudgetInstruction::SetComputeUnitPrice(5_000)))
+        ));
+        assert_eq!(registry.program_name(&ix.program_id), Some("Compute Budget"));
+        assert_eq!(registry.program_name(&METEORA_PROGRAM_ID), Some("Meteora AMM"));
+        assert_eq!(registry.program_name(&Pubkey::new_unique()), None);
+    }
+}
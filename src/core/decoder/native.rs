@@ -0,0 +1,147 @@
+use crate::core::decoder::{account, read_u64, DecodedInstruction, ProgramDecoder};
+
+use borsh::BorshDeserialize;
+use solana_sdk::compute_budget::{self, ComputeBudgetInstruction};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::system_program;
+use spl_associated_token_account::instruction::AssociatedTokenAccountInstruction;
+
+/// Инструкции SPL Token, которые встречаются в свапах
+#[derive(Debug, Clone)]
+pub enum TokenInstruction {
+    InitializeAccount { account: Pubkey, mint: Pubkey },
+    Transfer { source: Pubkey, destination: Pubkey, authority: Pubkey, amount: u64 },
+    MintTo { mint: Pubkey, account: Pubkey, amount: u64 },
+    Burn { account: Pubkey, mint: Pubkey, amount: u64 },
+    CloseAccount { account: Pubkey, destination: Pubkey },
+    TransferChecked {
+        source: Pubkey,
+        mint: Pubkey,
+        destination: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+        decimals: u8,
+    },
+    SyncNative { account: Pubkey },
+    /// Прочие инструкции: только тег
+    Other(u8),
+}
+
+pub struct SplTokenDecoder;
+
+impl ProgramDecoder for SplTokenDecoder {
+    fn program_id(&self) -> Pubkey {
+        spl_token::id()
+    }
+
+    fn name(&self) -> &'static str {
+        "SPL Token"
+    }
+
+    fn decode(&self, ix: &Instruction) -> Option<DecodedInstruction> {
+        let (&tag, _) = ix.data.split_first()?;
+        let amount = || read_u64(&ix.data, 1);
+
+        let decoded = match tag {
+            // InitializeAccount, InitializeAccount2, InitializeAccount3
+            1 | 16 | 18 => TokenInstruction::InitializeAccount {
+                account: account(ix, 0)?,
+                mint: account(ix, 1)?,
+            },
+            3 => TokenInstruction::Transfer {
+                source: account(ix, 0)?,
+                destination: account(ix, 1)?,
+                authority: account(ix, 2)?,
+                amount: amount()?,
+            },
+            7 => TokenInstruction::MintTo {
+                mint: account(ix, 0)?,
+                account: account(ix, 1)?,
+                amount: amount()?,
+            },
+            8 => TokenInstruction::Burn {
+                account: account(ix, 0)?,
+                mint: account(ix, 1)?,
+                amount: amount()?,
+            },
+            9 => TokenInstruction::CloseAccount {
+                account: account(ix, 0)?,
+                destination: account(ix, 1)?,
+            },
+            12 => TokenInstruction::TransferChecked {
+                source: account(ix, 0)?,
+                mint: account(ix, 1)?,
+                destination: account(ix, 2)?,
+                authority: account(ix, 3)?,
+                amount: amount()?,
+                decimals: *ix.data.get(9)?,
+            },
+            17 => TokenInstruction::SyncNative {
+                account: account(ix, 0)?,
+            },
+            other => TokenInstruction::Other(other),
+        };
+
+        Some(DecodedInstruction::SplToken(decoded))
+    }
+}
+
+pub struct AssociatedTokenDecoder;
+
+impl ProgramDecoder for AssociatedTokenDecoder {
+    fn program_id(&self) -> Pubkey {
+        spl_associated_token_account::id()
+    }
+
+    fn name(&self) -> &'static str {
+        "Associated Token Account"
+    }
+
+    fn decode(&self, ix: &Instruction) -> Option<DecodedInstruction> {
+        // Пустые данные — исторический формат Create
+        if ix.data.is_empty() {
+            return Some(DecodedInstruction::AssociatedToken(AssociatedTokenAccountInstruction::Create));
+        }
+        AssociatedTokenAccountInstruction::try_from_slice(&ix.data)
+            .ok()
+            .map(DecodedInstruction::AssociatedToken)
+    }
+}
+
+pub struct ComputeBudgetDecoder;
+
+impl ProgramDecoder for ComputeBudgetDecoder {
+    fn program_id(&self) -> Pubkey {
+        compute_budget::id()
+    }
+
+    fn name(&self) -> &'static str {
+        "Compute Budget"
+    }
+
+    fn decode(&self, ix: &Instruction) -> Option<DecodedInstruction> {
+        ComputeBudgetInstruction::try_from_slice(&ix.data)
+            .ok()
+            .map(DecodedInstruction::ComputeBudget)
+    }
+}
+
+pub struct SystemDecoder;
+
+impl ProgramDecoder for SystemDecoder {
+    fn program_id(&self) -> Pubkey {
+        system_program::id()
+    }
+
+    fn name(&self) -> &'static str {
+        "System"
+    }
+
+    fn decode(&self, ix: &Instruction) -> Option<DecodedInstruction> {
+        bincode::deserialize::<SystemInstruction>(&ix.data)
+            .ok()
+            .map(DecodedInstruction::System)
+    }
+}
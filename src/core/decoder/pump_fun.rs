@@ -0,0 +1,109 @@
+use crate::core::decoder::{account, read_u64, DecodedInstruction, ProgramDecoder};
+use crate::tx_senders::constants::PUMP_FUN_PROGRAM_ADDR;
+
+use borsh::BorshDeserialize;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Дискриминаторы Anchor: sha256("global:<name>")[..8]
+pub const CREATE_DISCRIMINATOR: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
+pub const BUY_DISCRIMINATOR: [u8; 8] = 16927863322537952870u64.to_le_bytes();
+pub const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+pub const WITHDRAW_DISCRIMINATOR: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
+
+#[derive(Debug, Clone)]
+pub enum PumpFunInstruction {
+    /// Создание токена на bonding curve
+    Create {
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        associated_bonding_curve: Pubkey,
+        user: Pubkey,
+        name: String,
+        symbol: String,
+        uri: String,
+    },
+    Buy {
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        user: Pubkey,
+        /// Количество токенов
+        amount: u64,
+        max_sol_cost: u64,
+    },
+    Sell {
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        user: Pubkey,
+        amount: u64,
+        min_sol_output: u64,
+    },
+    /// Вывод ликвидности заполненной кривой аккаунтом миграции
+    Withdraw {
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        user: Pubkey,
+    },
+}
+
+#[derive(BorshDeserialize)]
+struct CreateArgs {
+    name: String,
+    symbol: String,
+    uri: String,
+}
+
+pub struct PumpFunDecoder;
+
+impl ProgramDecoder for PumpFunDecoder {
+    fn program_id(&self) -> Pubkey {
+        Pubkey::from_str(PUMP_FUN_PROGRAM_ADDR).unwrap()
+    }
+
+    fn name(&self) -> &'static str {
+        "Pump.fun"
+    }
+
+    fn decode(&self, ix: &Instruction) -> Option<DecodedInstruction> {
+        let discriminator: [u8; 8] = ix.data.get(..8)?.try_into().ok()?;
+
+        let decoded = match discriminator {
+            CREATE_DISCRIMINATOR => {
+                // Новые версии программы дописывают аргументы после uri
+                let args = CreateArgs::deserialize(&mut &ix.data[8..]).ok()?;
+                PumpFunInstruction::Create {
+                    mint: account(ix, 0)?,
+                    bonding_curve: account(ix, 2)?,
+                    associated_bonding_curve: account(ix, 3)?,
+                    user: account(ix, 7)?,
+                    name: args.name,
+                    symbol: args.symbol,
+                    uri: args.uri,
+                }
+            }
+            BUY_DISCRIMINATOR => PumpFunInstruction::Buy {
+                mint: account(ix, 2)?,
+                bonding_curve: account(ix, 3)?,
+                user: account(ix, 6)?,
+                amount: read_u64(&ix.data, 8)?,
+                max_sol_cost: read_u64(&ix.data, 16)?,
+            },
+            SELL_DISCRIMINATOR => PumpFunInstruction::Sell {
+                mint: account(ix, 2)?,
+                bonding_curve: account(ix, 3)?,
+                user: account(ix, 6)?,
+                amount: read_u64(&ix.data, 8)?,
+                min_sol_output: read_u64(&ix.data, 16)?,
+            },
+            WITHDRAW_DISCRIMINATOR => PumpFunInstruction::Withdraw {
+                mint: account(ix, 2)?,
+                bonding_curve: account(ix, 3)?,
+                user: account(ix, 6)?,
+            },
+            _ => return None,
+        };
+
+        Some(DecodedInstruction::PumpFun(decoded))
+    }
+}
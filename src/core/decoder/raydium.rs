@@ -0,0 +1,141 @@
+use crate::core::decoder::{account, read_u64, DecodedInstruction, ProgramDecoder};
+use crate::core::logs::anchor_discriminator;
+use crate::tx_senders::constants::{RAYDIUM_AMM_V4_PROGRAM_ID, RAYDIUM_CPMM_PROGRAM_ID};
+
+use lazy_static::lazy_static;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+
+/// Тег инструкции `initialize2` AMM v4 (первый байт данных, не Anchor)
+const AMM_V4_INITIALIZE2: u8 = 1;
+const AMM_V4_SWAP_BASE_IN: u8 = 9;
+
+/// Инструкции Raydium AMM v4 (liquidity pool v4)
+#[derive(Debug, Clone)]
+pub enum RaydiumAmmV4Instruction {
+    /// Создание пула поверх рынка OpenBook
+    Initialize2 {
+        amm: Pubkey,
+        lp_mint: Pubkey,
+        coin_mint: Pubkey,
+        pc_mint: Pubkey,
+        market: Pubkey,
+        user: Pubkey,
+        /// Время открытия торговли, секунды
+        open_time: u64,
+        init_pc_amount: u64,
+        init_coin_amount: u64,
+    },
+    SwapBaseIn {
+        amm: Pubkey,
+        user: Pubkey,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    },
+}
+
+pub struct RaydiumAmmV4Decoder;
+
+impl ProgramDecoder for RaydiumAmmV4Decoder {
+    fn program_id(&self) -> Pubkey {
+        RAYDIUM_AMM_V4_PROGRAM_ID
+    }
+
+    fn name(&self) -> &'static str {
+        "Raydium AMM v4"
+    }
+
+    fn decode(&self, ix: &Instruction) -> Option<DecodedInstruction> {
+        let decoded = match *ix.data.first()? {
+            // tag, nonce: u8, open_time, init_pc_amount, init_coin_amount: u64
+            AMM_V4_INITIALIZE2 => RaydiumAmmV4Instruction::Initialize2 {
+                amm: account(ix, 4)?,
+                lp_mint: account(ix, 7)?,
+                coin_mint: account(ix, 8)?,
+                pc_mint: account(ix, 9)?,
+                market: account(ix, 16)?,
+                user: account(ix, 17)?,
+                open_time: read_u64(&ix.data, 2)?,
+                init_pc_amount: read_u64(&ix.data, 10)?,
+                init_coin_amount: read_u64(&ix.data, 18)?,
+            },
+            // Без amm_target_orders аккаунтов 17, подписант всегда последний
+            AMM_V4_SWAP_BASE_IN => RaydiumAmmV4Instruction::SwapBaseIn {
+                amm: account(ix, 1)?,
+                user: ix.accounts.last()?.pubkey,
+                amount_in: read_u64(&ix.data, 1)?,
+                minimum_amount_out: read_u64(&ix.data, 9)?,
+            },
+            _ => return None,
+        };
+
+        Some(DecodedInstruction::RaydiumAmmV4(decoded))
+    }
+}
+
+lazy_static! {
+    static ref CPMM_INITIALIZE: [u8; 8] = anchor_discriminator("global", "initialize");
+    static ref CPMM_SWAP_BASE_INPUT: [u8; 8] = anchor_discriminator("global", "swap_base_input");
+}
+
+/// Инструкции Raydium CPMM (constant product без OpenBook)
+#[derive(Debug, Clone)]
+pub enum RaydiumCpmmInstruction {
+    Initialize {
+        pool: Pubkey,
+        amm_config: Pubkey,
+        token_0_mint: Pubkey,
+        token_1_mint: Pubkey,
+        creator: Pubkey,
+        init_amount_0: u64,
+        init_amount_1: u64,
+        /// Время открытия торговли, секунды
+        open_time: u64,
+    },
+    SwapBaseInput {
+        pool: Pubkey,
+        user: Pubkey,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    },
+}
+
+pub struct RaydiumCpmmDecoder;
+
+impl ProgramDecoder for RaydiumCpmmDecoder {
+    fn program_id(&self) -> Pubkey {
+        RAYDIUM_CPMM_PROGRAM_ID
+    }
+
+    fn name(&self) -> &'static str {
+        "Raydium CPMM"
+    }
+
+    fn decode(&self, ix: &Instruction) -> Option<DecodedInstruction> {
+        let discriminator: [u8; 8] = ix.data.get(..8)?.try_into().ok()?;
+
+        let decoded = if discriminator == *CPMM_INITIALIZE {
+            RaydiumCpmmInstruction::Initialize {
+                pool: account(ix, 3)?,
+                amm_config: account(ix, 1)?,
+                token_0_mint: account(ix, 4)?,
+                token_1_mint: account(ix, 5)?,
+                creator: account(ix, 0)?,
+                init_amount_0: read_u64(&ix.data, 8)?,
+                init_amount_1: read_u64(&ix.data, 16)?,
+                open_time: read_u64(&ix.data, 24)?,
+            }
+        } else if discriminator == *CPMM_SWAP_BASE_INPUT {
+            RaydiumCpmmInstruction::SwapBaseInput {
+                pool: account(ix, 3)?,
+                user: account(ix, 0)?,
+                amount_in: read_u64(&ix.data, 8)?,
+                minimum_amount_out: read_u64(&ix.data, 16)?,
+            }
+        } else {
+            return None;
+        };
+
+        Some(DecodedInstruction::RaydiumCpmm(decoded))
+    }
+}
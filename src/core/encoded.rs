@@ -0,0 +1,126 @@
+use crate::geyser::{Error, GeyserResult};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::message::v0::LoadedAddresses;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::{
+    EncodedTransactionWithStatusMeta, InnerInstruction, InnerInstructions,
+    TransactionStatusMeta, TransactionTokenBalance, UiInstruction, UiTransactionEncoding,
+    UiTransactionStatusMeta, UiTransactionTokenBalance,
+};
+use std::str::FromStr;
+
+/// Загрузка транзакции с meta через RPC `getTransaction`
+pub async fn fetch_transaction(
+    rpc: &RpcClient,
+    signature: &Signature,
+) -> GeyserResult<(VersionedTransaction, TransactionStatusMeta)> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+
+    let confirmed = rpc
+        .get_transaction_with_config(signature, config)
+        .await
+        .map_err(|err| Error::Custom(err.to_string()))?;
+
+    decode_encoded_transaction(confirmed.transaction)
+}
+
+/// Перевод JSON-представления транзакции (ответ `getTransaction`) во внутренние типы
+pub fn decode_encoded_transaction(
+    encoded: EncodedTransactionWithStatusMeta,
+) -> GeyserResult<(VersionedTransaction, TransactionStatusMeta)> {
+    let transaction = encoded
+        .transaction
+        .decode()
+        .ok_or_else(|| Error::Custom("не удалось декодировать транзакцию".to_string()))?;
+
+    let meta = match encoded.meta {
+        Some(ui_meta) => meta_from_ui(ui_meta)?,
+        None => TransactionStatusMeta::default(),
+    };
+
+    Ok((transaction, meta))
+}
+
+fn meta_from_ui(ui: UiTransactionStatusMeta) -> GeyserResult<TransactionStatusMeta> {
+    let inner_instructions = Option::<Vec<_>>::from(ui.inner_instructions)
+        .map(|inner| {
+            inner
+                .into_iter()
+                .map(|group| {
+                    let instructions = group
+                        .instructions
+                        .into_iter()
+                        .filter_map(|ix| match ix {
+                            UiInstruction::Compiled(compiled) => {
+                                let data = bs58::decode(&compiled.data).into_vec().ok()?;
+                                Some(InnerInstruction {
+                                    instruction: CompiledInstruction {
+                                        program_id_index: compiled.program_id_index,
+                                        accounts: compiled.accounts,
+                                        data,
+                                    },
+                                    stack_height: compiled.stack_height,
+                                })
+                            }
+                            UiInstruction::Parsed(_) => None,
+                        })
+                        .collect();
+
+                    InnerInstructions {
+                        index: group.index,
+                        instructions,
+                    }
+                })
+                .collect()
+        });
+
+    let loaded_addresses = match Option::from(ui.loaded_addresses) {
+        Some(loaded) => LoadedAddresses {
+            writable: parse_pubkeys(&loaded.writable)?,
+            readonly: parse_pubkeys(&loaded.readonly)?,
+        },
+        None => LoadedAddresses::default(),
+    };
+
+    Ok(TransactionStatusMeta {
+        status: ui.status,
+        fee: ui.fee,
+        pre_balances: ui.pre_balances,
+        post_balances: ui.post_balances,
+        inner_instructions,
+        log_messages: ui.log_messages.into(),
+        pre_token_balances: Option::<Vec<_>>::from(ui.pre_token_balances)
+            .map(|balances| balances.into_iter().map(token_balance_from_ui).collect()),
+        post_token_balances: Option::<Vec<_>>::from(ui.post_token_balances)
+            .map(|balances| balances.into_iter().map(token_balance_from_ui).collect()),
+        rewards: ui.rewards.into(),
+        loaded_addresses,
+        return_data: None,
+        compute_units_consumed: ui.compute_units_consumed.into(),
+    })
+}
+
+fn token_balance_from_ui(balance: UiTransactionTokenBalance) -> TransactionTokenBalance {
+    TransactionTokenBalance {
+        account_index: balance.account_index,
+        mint: balance.mint,
+        ui_token_amount: balance.ui_token_amount,
+        owner: Option::from(balance.owner).unwrap_or_default(),
+        program_id: Option::from(balance.program_id).unwrap_or_default(),
+    }
+}
+
+fn parse_pubkeys(keys: &[String]) -> GeyserResult<Vec<Pubkey>> {
+    keys.iter()
+        .map(|key| Pubkey::from_str(key).map_err(|err| Error::Custom(format!("{key}: {err}"))))
+        .collect()
+}
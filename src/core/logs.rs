@@ -0,0 +1,81 @@
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Данные из строки `Program data:` вместе с программой, которая их записала
+#[derive(Debug, Clone)]
+pub struct ProgramData {
+    pub program_id: Pubkey,
+    /// Глубина вызова программы: 1 — инструкция верхнего уровня
+    pub stack_height: usize,
+    pub data: Vec<u8>,
+}
+
+/// Проход по логам транзакции с отслеживанием стека вызовов программ.
+/// `Program data:` относится к программе на вершине стека в момент записи.
+pub fn program_data(logs: &[String]) -> Vec<ProgramData> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    let mut result = Vec::new();
+
+    for line in logs {
+        if let Some(encoded) = line.strip_prefix("Program data: ") {
+            let Some(program_id) = stack.last() else {
+                continue;
+            };
+            // Событие может состоять из нескольких base64-частей через пробел
+            let data: Option<Vec<u8>> = encoded
+                .split_whitespace()
+                .map(|part| base64::engine::general_purpose::STANDARD.decode(part).ok())
+                .collect::<Option<Vec<_>>>()
+                .map(|parts| parts.concat());
+
+            if let Some(data) = data {
+                result.push(ProgramData {
+                    program_id: *program_id,
+                    stack_height: stack.len(),
+                    data,
+                });
+            }
+            continue;
+        }
+
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        let mut parts = rest.split_whitespace();
+        let (Some(program), Some(action)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let Ok(program_id) = Pubkey::from_str(program) else {
+            continue;
+        };
+
+        match action {
+            "invoke" => stack.push(program_id),
+            "success" | "failed:" => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    result
+}
+
+/// Дискриминатор Anchor: первые 8 байт sha256 от `<namespace>:<name>`
+pub fn anchor_discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("{namespace}:{name}").as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+/// Разделение данных события Anchor на дискриминатор и тело
+pub fn split_event(data: &[u8]) -> Option<([u8; 8], &[u8])> {
+    if data.len() < 8 {
+        return None;
+    }
+    let (discriminator, body) = data.split_at(8);
+    Some((discriminator.try_into().ok()?, body))
+}
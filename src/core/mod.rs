@@ -1,101 +1,134 @@
-use solana_sdk::instruction::AccountMeta;
-use solana_sdk::message::v0::{LoadedAddresses, LoadedMessage};
+pub mod accounts;
+pub mod alt;
+pub mod balances;
+pub mod decoder;
+pub mod encoded;
+pub mod logs;
+
+use solana_sdk::instruction::{AccountMeta, CompiledInstruction, Instruction};
+use solana_sdk::message::v0::LoadedMessage;
 use solana_sdk::message::VersionedMessage;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::reserved_account_keys::ReservedAccountKeys;
 use solana_sdk::transaction::VersionedTransaction;
 use solana_transaction_status::TransactionStatusMeta;
-use crate::geyser::GeyserResult;
+use std::collections::HashMap;
+use crate::core::alt::AltResolver;
+use crate::geyser::{Error, GeyserResult};
+
+/// Инструкция транзакции: верхнего уровня или вызванная через CPI
+#[derive(Debug, Clone)]
+pub struct ParsedInstruction {
+    pub instruction: Instruction,
+    /// Индекс инструкции верхнего уровня (для CPI — той, внутри которой выполнен вызов)
+    pub parent_index: usize,
+    /// 1 — инструкция верхнего уровня, 2 и больше — вложенные вызовы
+    pub stack_height: u32,
+}
+
+impl ParsedInstruction {
+    pub fn is_inner(&self) -> bool {
+        self.stack_height > 1
+    }
+}
 
+/// Инструкции транзакции в порядке выполнения: каждая инструкция верхнего уровня,
+/// затем её CPI из `meta.inner_instructions`
 pub fn extract_instructions(
     meta_data: TransactionStatusMeta,
     transaction: VersionedTransaction
-) -> GeyserResult<Vec<solana_sdk::instruction::Instruction>> {
-    let message = transaction.message.clone();
-    let meta = meta_data.clone();
-
-    let mut instructions =
-        Vec::<solana_sdk::instruction::Instruction>::new();
+) -> GeyserResult<Vec<ParsedInstruction>> {
+    let message = &transaction.message;
+    let meta = meta_data;
 
-    match message {
+    // Ключи аккаунтов (для v0 — вместе с адресами из lookup tables) и их флаги
+    let (account_keys, writable, signers): (Vec<Pubkey>, Vec<bool>, Vec<bool>) = match message {
         VersionedMessage::Legacy(legacy) => {
-            for (i, compiled_instruction) in legacy.instructions.iter().enumerate() {
-                let program_id = *legacy
-                    .account_keys
-                    .get(compiled_instruction.program_id_index as usize)
-                    .unwrap_or(&Pubkey::default());
-
-                let accounts: Vec<_> = compiled_instruction
-                    .accounts
-                    .iter()
-                    .filter_map(|account_index| {
-                        let account_pubkey = legacy.account_keys.get(*account_index as usize)?;
-                        Some(AccountMeta {
-                            pubkey: *account_pubkey,
-                            is_writable: legacy.is_maybe_writable(*account_index as usize, None),
-                            is_signer: legacy.is_signer(*account_index as usize),
-                        })
-                    })
-                    .collect();
-
-                instructions.push(solana_sdk::instruction::Instruction {
-                    program_id,
-                    accounts,
-                    data: compiled_instruction.data.clone(),
-                });
-            }
+            let count = legacy.account_keys.len();
+            (
+                legacy.account_keys.clone(),
+                (0..count).map(|i| legacy.is_maybe_writable(i, None)).collect(),
+                (0..count).map(|i| legacy.is_signer(i)).collect(),
+            )
         }
         VersionedMessage::V0(v0) => {
-            let loaded_addresses = LoadedAddresses {
-                writable: meta
-                    .loaded_addresses
-                    .writable
-                    .iter()
-                    .map(|key| key.clone())
-                    .collect(),
-                readonly: meta
-                    .loaded_addresses
-                    .readonly
-                    .iter()
-                    .map(|key| key.clone())
-                    .collect(),
-            };
-
             let loaded_message = LoadedMessage::new(
                 v0.clone(),
-                loaded_addresses,
+                meta.loaded_addresses.clone(),
                 &ReservedAccountKeys::empty_key_set(),
             );
+            let keys: Vec<Pubkey> = loaded_message.account_keys().iter().copied().collect();
+            let count = keys.len();
+            (
+                keys,
+                (0..count).map(|i| loaded_message.is_writable(i)).collect(),
+                (0..count).map(|i| loaded_message.is_signer(i)).collect(),
+            )
+        }
+    };
 
-            for (i, compiled_instruction) in v0.instructions.iter().enumerate() {
-                let program_id = *loaded_message
-                    .account_keys()
-                    .get(compiled_instruction.program_id_index as usize)
-                    .unwrap_or(&Pubkey::default());
+    let decompile = |compiled: &CompiledInstruction| Instruction {
+        program_id: account_keys
+            .get(compiled.program_id_index as usize)
+            .copied()
+            .unwrap_or_default(),
+        accounts: compiled
+            .accounts
+            .iter()
+            .map(|&account_index| {
+                let account_index = account_index as usize;
+                AccountMeta {
+                    pubkey: account_keys.get(account_index).copied().unwrap_or_default(),
+                    is_writable: writable.get(account_index).copied().unwrap_or(false),
+                    is_signer: signers.get(account_index).copied().unwrap_or(false),
+                }
+            })
+            .collect(),
+        data: compiled.data.clone(),
+    };
 
-                let accounts: Vec<AccountMeta> = compiled_instruction
-                    .accounts
-                    .iter()
-                    .filter_map(|account_index| {
-                        let account_pubkey =
-                            loaded_message.account_keys().get(*account_index as usize);
+    let inner_by_parent: HashMap<usize, _> = meta
+        .inner_instructions
+        .iter()
+        .flatten()
+        .map(|inner| (inner.index as usize, &inner.instructions))
+        .collect();
 
-                        return Some(AccountMeta {
-                            pubkey: account_pubkey.map(|acc| acc.clone()).unwrap_or_default(),
-                            is_writable: loaded_message.is_writable(*account_index as usize),
-                            is_signer: loaded_message.is_signer(*account_index as usize),
-                        });
-                    })
-                    .collect();
+    let mut instructions = Vec::<ParsedInstruction>::new();
 
-                instructions.push(solana_sdk::instruction::Instruction {
-                    program_id,
-                    accounts,
-                    data: compiled_instruction.data.clone(),
-                });
-            }
+    for (index, compiled_instruction) in message.instructions().iter().enumerate() {
+        instructions.push(ParsedInstruction {
+            instruction: decompile(compiled_instruction),
+            parent_index: index,
+            stack_height: 1,
+        });
+
+        for inner in inner_by_parent.get(&index).into_iter().flat_map(|inner| inner.iter()) {
+            instructions.push(ParsedInstruction {
+                instruction: decompile(&inner.instruction),
+                parent_index: index,
+                // Старые транзакции не содержат stack_height: считаем вызов прямым CPI
+                stack_height: inner.stack_height.unwrap_or(2),
+            });
         }
     }
 
     Ok(instructions)
-}
\ No newline at end of file
+}
+
+/// Разбор инструкций без meta: адреса из lookup tables берутся из [`AltResolver`]
+pub async fn extract_instructions_resolved(
+    resolver: &AltResolver,
+    transaction: VersionedTransaction,
+) -> GeyserResult<Vec<ParsedInstruction>> {
+    let loaded_addresses = resolver
+        .resolve(&transaction)
+        .await
+        .map_err(|err| Error::Custom(err.to_string()))?;
+    let meta = TransactionStatusMeta {
+        loaded_addresses,
+        ..Default::default()
+    };
+
+    extract_instructions(meta, transaction)
+}
@@ -0,0 +1,132 @@
+use crate::config::PingThingsArgs;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::compute_budget;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use tracing::{info, warn};
+
+/// Дискриминатор `ComputeBudgetInstruction::SetComputeUnitLimit`
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+/// Лимит для профилирующей симуляции, чтобы она не упёрлась в заниженное значение
+const PROFILE_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Форма транзакции: набор инструкций, от которого зависит расход compute units
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TxShape {
+    PumpFunBuy { create_ata: bool },
+    MeteoraSwap { create_ata: bool, wrap_sol: bool },
+    /// Число bin arrays определяет, сколько бинов может пройти свап
+    MeteoraDlmmSwap { bin_arrays: usize },
+    MeteoraDammV2Swap,
+    RaydiumAmmV4Swap,
+    RaydiumCpmmSwap,
+}
+
+/// Кэш расхода compute units по формам транзакций.
+/// Каждая форма симулируется один раз, дальше лимит берётся из кэша с запасом.
+#[derive(Default)]
+pub struct ComputeProfiler {
+    profiles: RwLock<HashMap<TxShape, u64>>,
+    in_flight: Mutex<HashSet<TxShape>>,
+}
+
+impl ComputeProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Лимит для формы: профиль плюс запас, иначе `compute_unit_limit` из конфигурации
+    pub fn limit_for(&self, shape: &TxShape, args: &PingThingsArgs) -> u32 {
+        if !args.compute_profile.enabled {
+            return args.compute_unit_limit;
+        }
+
+        match self.profiles.read().unwrap().get(shape) {
+            Some(units) => {
+                let limit = units * (100 + args.compute_profile.margin_percent as u64) / 100;
+                limit.min(PROFILE_COMPUTE_UNIT_LIMIT as u64) as u32
+            }
+            None => args.compute_unit_limit,
+        }
+    }
+
+    /// Фоновая симуляция готовой транзакции, если форма ещё не профилирована.
+    /// Не задерживает отправку: результат пригодится следующим сделкам.
+    pub fn profile_in_background(
+        self: &Arc<Self>,
+        args: &PingThingsArgs,
+        shape: TxShape,
+        tx: &VersionedTransaction,
+    ) {
+        if !args.compute_profile.enabled || self.profiles.read().unwrap().contains_key(&shape) {
+            return;
+        }
+        if !self.in_flight.lock().unwrap().insert(shape) {
+            return;
+        }
+
+        let Some(tx) = with_compute_unit_limit(tx, PROFILE_COMPUTE_UNIT_LIMIT) else {
+            warn!("Профилирование {:?} пропущено: нет инструкции лимита compute units", shape);
+            self.in_flight.lock().unwrap().remove(&shape);
+            return;
+        };
+
+        let profiler = self.clone();
+        let rpc = RpcClient::new(args.http_rpc.clone());
+        tokio::spawn(async move {
+            profiler.profile(&rpc, shape, &tx).await;
+            profiler.in_flight.lock().unwrap().remove(&shape);
+        });
+    }
+
+    pub async fn profile(&self, rpc: &RpcClient, shape: TxShape, tx: &VersionedTransaction) {
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(CommitmentConfig::processed()),
+            ..Default::default()
+        };
+
+        let result = match rpc.simulate_transaction_with_config(tx, config).await {
+            Ok(response) => response.value,
+            Err(e) => {
+                warn!("Симуляция для профиля {:?} не удалась: {:?}", shape, e);
+                return;
+            }
+        };
+
+        // Неуспешная симуляция занижает расход: такой профиль не сохраняем
+        if let Some(err) = result.err {
+            warn!("Симуляция для профиля {:?} завершилась ошибкой: {}", shape, err);
+            return;
+        }
+
+        if let Some(units) = result.units_consumed {
+            info!("Профиль compute units {:?}: {}", shape, units);
+            self.profiles.write().unwrap().insert(shape, units);
+        }
+    }
+}
+
+/// Копия транзакции с заменённым лимитом compute units (подпись становится недействительной)
+fn with_compute_unit_limit(tx: &VersionedTransaction, limit: u32) -> Option<VersionedTransaction> {
+    let mut tx = tx.clone();
+    let keys = tx.message.static_account_keys().to_vec();
+
+    let instruction = match &mut tx.message {
+        solana_sdk::message::VersionedMessage::Legacy(message) => &mut message.instructions,
+        solana_sdk::message::VersionedMessage::V0(message) => &mut message.instructions,
+    }
+    .iter_mut()
+    .find(|ix| {
+        keys.get(ix.program_id_index as usize) == Some(&compute_budget::id())
+            && ix.data.first() == Some(&SET_COMPUTE_UNIT_LIMIT)
+    })?;
+
+    instruction.data = [&[SET_COMPUTE_UNIT_LIMIT][..], &limit.to_le_bytes()].concat();
+    Some(tx)
+}
@@ -0,0 +1,171 @@
+pub mod compute;
+
+use crate::config::{MicroLamports, PingThingsArgs};
+use crate::metrics::PRIORITY_FEE_MICRO_LAMPORTS;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::compute_budget;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Дискриминатор `ComputeBudgetInstruction::SetComputeUnitPrice`
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+/// Максимум хранимых наблюдений из Geyser
+const MAX_OBSERVATIONS: usize = 1024;
+
+/// Оценка цены compute unit по недавним комиссиям сети.
+/// Источники: `getRecentPrioritizationFees` по записываемым аккаунтам сделки
+/// и цены из транзакций Meteora, увиденных в потоке Geyser.
+#[derive(Default)]
+pub struct FeeEstimator {
+    observed: Mutex<VecDeque<(Instant, u64)>>,
+}
+
+impl FeeEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Учёт цены compute unit из транзакции, пришедшей по Geyser
+    pub fn observe(&self, tx: &VersionedTransaction) {
+        let Some(price) = compute_unit_price(tx) else {
+            return;
+        };
+
+        let mut observed = self.observed.lock().unwrap();
+        if observed.len() == MAX_OBSERVATIONS {
+            observed.pop_front();
+        }
+        observed.push_back((Instant::now(), price));
+    }
+
+    /// Цена для очередной транзакции: перцентиль выборки, ограниченный сверху `priority_fee.max`.
+    /// В статическом режиме или без данных используется `compute_unit_price` из конфигурации.
+    pub async fn estimate(
+        &self,
+        rpc: &RpcClient,
+        args: &PingThingsArgs,
+        writable_accounts: &[Pubkey],
+    ) -> MicroLamports {
+        let config = &args.priority_fee;
+        if !config.dynamic {
+            return args.compute_unit_price;
+        }
+
+        let mut samples = self.recent_observations(Duration::from_secs(config.window_secs));
+
+        match rpc.get_recent_prioritization_fees(writable_accounts).await {
+            Ok(fees) => samples.extend(
+                fees.iter()
+                    .map(|fee| fee.prioritization_fee)
+                    .filter(|fee| *fee > 0),
+            ),
+            Err(e) => warn!("Не удалось получить недавние приоритетные комиссии: {:?}", e),
+        }
+
+        let price = match percentile(&mut samples, config.percentile) {
+            Some(price) => MicroLamports(price.min(config.max.0)),
+            None => args.compute_unit_price,
+        };
+
+        debug!("Оценка compute_unit_price: {} по {} наблюдениям", price, samples.len());
+        PRIORITY_FEE_MICRO_LAMPORTS.set(price.0 as i64);
+
+        price
+    }
+
+    fn recent_observations(&self, window: Duration) -> Vec<u64> {
+        let mut observed = self.observed.lock().unwrap();
+        while observed
+            .front()
+            .is_some_and(|(at, _)| at.elapsed() > window)
+        {
+            observed.pop_front();
+        }
+
+        observed.iter().map(|(_, price)| *price).collect()
+    }
+}
+
+fn compute_unit_price(tx: &VersionedTransaction) -> Option<u64> {
+    let keys = tx.message.static_account_keys();
+
+    tx.message.instructions().iter().find_map(|ix| {
+        let program_id = keys.get(ix.program_id_index as usize)?;
+        if *program_id != compute_budget::id() || ix.data.first() != Some(&SET_COMPUTE_UNIT_PRICE) {
+            return None;
+        }
+
+        Some(u64::from_le_bytes(ix.data.get(1..9)?.try_into().ok()?))
+    })
+}
+
+fn percentile(samples: &mut [u64], percentile: u8) -> Option<u64> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    samples.sort_unstable();
+    let rank = (samples.len() - 1) as f64 * percentile.min(100) as f64 / 100.0;
+    Some(samples[rank.round() as usize])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::compute_budget::ComputeBudgetInstruction;
+    use solana_sdk::instruction::Instruction;
+    use solana_sdk::transaction::Transaction;
+
+    fn transaction(instructions: &[Instruction]) -> VersionedTransaction {
+        Transaction::new_with_payer(instructions, Some(&Pubkey::new_unique())).into()
+    }
+
+    #[test]
+    fn percentile_of_samples() {
+        let cases: [(&[u64], u8, Option<u64>); 8] = [
+            (&[], 75, None),
+            (&[42], 0, Some(42)),
+            (&[42], 100, Some(42)),
+            (&[10, 1, 7, 3, 9, 2, 8, 4, 6, 5], 0, Some(1)),
+            // ранг 4.5 округляется вверх
+            (&[10, 1, 7, 3, 9, 2, 8, 4, 6, 5], 50, Some(6)),
+            (&[10, 1, 7, 3, 9, 2, 8, 4, 6, 5], 75, Some(8)),
+            (&[10, 1, 7, 3, 9, 2, 8, 4, 6, 5], 100, Some(10)),
+            (&[10, 1, 7, 3, 9, 2, 8, 4, 6, 5], 200, Some(10)),
+        ];
+        for (samples, p, expected) in cases {
+            assert_eq!(percentile(&mut samples.to_vec(), p), expected, "перцентиль {p} из {samples:?}");
+        }
+    }
+
+    #[test]
+    fn compute_unit_price_from_transaction() {
+        let priced = transaction(&[
+            ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+            ComputeBudgetInstruction::set_compute_unit_price(12_345),
+        ]);
+        assert_eq!(compute_unit_price(&priced), Some(12_345));
+
+        let unpriced = transaction(&[ComputeBudgetInstruction::set_compute_unit_limit(200_000)]);
+        assert_eq!(compute_unit_price(&unpriced), None);
+    }
+
+    #[test]
+    fn observations_are_capped() {
+        let estimator = FeeEstimator::new();
+        let tx = transaction(&[ComputeBudgetInstruction::set_compute_unit_price(1_000)]);
+        for _ in 0..MAX_OBSERVATIONS + 10 {
+            estimator.observe(&tx);
+        }
+        estimator.observe(&transaction(&[]));
+
+        let observed = estimator.recent_observations(Duration::from_secs(60));
+        assert_eq!(observed.len(), MAX_OBSERVATIONS);
+        assert!(observed.iter().all(|price| *price == 1_000));
+    }
+}
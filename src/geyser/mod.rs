@@ -1,22 +1,30 @@
+pub mod signatures;
+pub mod subscriptions;
+
 use async_trait::async_trait;
-use futures::StreamExt;
+use futures::{SinkExt, StreamExt};
 use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
 use yellowstone_grpc_proto::prelude::*;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 use yellowstone_grpc_client::GeyserGrpcClient;
 use yellowstone_grpc_proto::tonic::transport::ClientTlsConfig;
 use yellowstone_grpc_proto::geyser::{
     subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest,
-    SubscribeRequestFilterAccounts, SubscribeRequestFilterTransactions,
+    SubscribeRequestFilterAccounts, SubscribeRequestFilterSlots, SubscribeRequestFilterTransactions,
 };
 use thiserror::Error;
 use futures::future::BoxFuture;
 use yellowstone_grpc_proto::convert_from::create_tx_versioned;
+use crate::geyser::subscriptions::AccountSubscriptions;
+
+/// Обработчик account-обновлений: адрес аккаунта и его данные
+pub type AccountHandler = Arc<dyn Fn(Pubkey, Vec<u8>) + Send + Sync>;
+/// Обработчик статусов слотов: processed, confirmed и finalized
+pub type SlotHandler = Arc<dyn Fn(u64, CommitmentLevel) + Send + Sync>;
 
-#[derive(Debug)]
 pub struct YellowstoneGrpcGeyserClient {
     pub endpoint: String,
     pub x_token: Option<String>,
@@ -24,6 +32,9 @@ pub struct YellowstoneGrpcGeyserClient {
     pub account_filters: HashMap<String, SubscribeRequestFilterAccounts>,
     pub transaction_filters: HashMap<String, SubscribeRequestFilterTransactions>,
     pub account_deletions_tracked: Arc<RwLock<HashSet<Pubkey>>>,
+    pub account_handler: Option<AccountHandler>,
+    pub slot_handler: Option<SlotHandler>,
+    pub account_subscriptions: Option<Arc<AccountSubscriptions>>,
 }
 
 impl YellowstoneGrpcGeyserClient {
@@ -42,10 +53,84 @@ impl YellowstoneGrpcGeyserClient {
             account_filters,
             transaction_filters,
             account_deletions_tracked,
+            account_handler: None,
+            slot_handler: None,
+            account_subscriptions: None,
+        }
+    }
+
+    /// Обработка обновлений аккаунтов, попавших под `account_filters`
+    pub fn with_account_handler(mut self, handler: AccountHandler) -> Self {
+        self.account_handler = Some(handler);
+        self
+    }
+
+    /// Подписка на слоты: без обработчика они не запрашиваются
+    pub fn with_slot_handler(mut self, handler: SlotHandler) -> Self {
+        self.slot_handler = Some(handler);
+        self
+    }
+
+    /// Аккаунты, добавляемые к `account_filters` во время работы; обновления идут в `account_handler`
+    pub fn with_account_subscriptions(mut self, subscriptions: Arc<AccountSubscriptions>) -> Self {
+        self.account_subscriptions = Some(subscriptions);
+        self
+    }
+
+    /// Запрос подписки с текущим набором динамических аккаунтов.
+    /// Geyser заменяет фильтры целиком, поэтому статические фильтры входят в каждый запрос.
+    fn subscribe_request(&self) -> SubscribeRequest {
+        let mut slots = HashMap::new();
+        if self.slot_handler.is_some() {
+            slots.insert(
+                "slot_filter".to_string(),
+                // Все статусы, а не только commitment подписки: подтверждения нужны SignatureWatch
+                SubscribeRequestFilterSlots {
+                    filter_by_commitment: Some(false),
+                },
+            );
+        }
+
+        let mut accounts = self.account_filters.clone();
+        // Пустой фильтр совпал бы со всеми аккаунтами
+        let dynamic_accounts = self
+            .account_subscriptions
+            .as_ref()
+            .map(|subscriptions| subscriptions.accounts())
+            .unwrap_or_default();
+        if !dynamic_accounts.is_empty() {
+            accounts.insert(
+                "dynamic_account_filter".to_string(),
+                SubscribeRequestFilterAccounts {
+                    account: dynamic_accounts,
+                    ..Default::default()
+                },
+            );
+        }
+
+        SubscribeRequest {
+            slots,
+            accounts,
+            transactions: self.transaction_filters.clone(),
+            transactions_status: HashMap::new(),
+            entry: HashMap::new(),
+            blocks: HashMap::new(),
+            blocks_meta: HashMap::new(),
+            commitment: self.commitment.map(|x| x as i32),
+            accounts_data_slice: vec![],
+            ping: None,
         }
     }
 }
 
+/// Изменение набора динамических аккаунтов; без подписок ожидание бесконечно
+async fn subscriptions_changed(receiver: &mut Option<watch::Receiver<HashSet<Pubkey>>>) -> bool {
+    match receiver {
+        Some(receiver) => receiver.changed().await.is_ok(),
+        None => std::future::pending().await,
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Custom error: {0}")]
@@ -58,7 +143,7 @@ pub type GeyserResult<T> = Result<T, Error>;
 pub trait YellowstoneGrpcGeyser: Send + Sync {
     async fn consume<F>(&self, handler: F) -> GeyserResult<()>
     where
-        F: Fn(VersionedTransaction, TransactionStatusMeta) -> BoxFuture<'static, ()>
+        F: Fn(VersionedTransaction, TransactionStatusMeta, u64) -> BoxFuture<'static, ()>
             + Send
             + Sync
             + 'static;
@@ -68,7 +153,7 @@ pub trait YellowstoneGrpcGeyser: Send + Sync {
 impl YellowstoneGrpcGeyser for YellowstoneGrpcGeyserClient {
     async fn consume<F>(&self, handler: F) -> GeyserResult<()>
     where
-        F: Fn(VersionedTransaction, TransactionStatusMeta) -> BoxFuture<'static, ()>
+        F: Fn(VersionedTransaction, TransactionStatusMeta, u64) -> BoxFuture<'static, ()>
             + Send
             + Sync
             + 'static,
@@ -85,27 +170,28 @@ impl YellowstoneGrpcGeyser for YellowstoneGrpcGeyserClient {
             .await
             .map_err(|err| Error::Custom(err.to_string()))?;
 
-        let subscribe_request = SubscribeRequest {
-            slots: HashMap::new(),
-            accounts: self.account_filters.clone(),
-            transactions: self.transaction_filters.clone(),
-            transactions_status: HashMap::new(),
-            entry: HashMap::new(),
-            blocks: HashMap::new(),
-            blocks_meta: HashMap::new(),
-            commitment: self.commitment.map(|x| x as i32),
-            accounts_data_slice: vec![],
-            ping: None,
-        };
-
-        let (mut _subscribe_tx, mut stream) =
-            geyser_client.subscribe_with_request(Some(subscribe_request)).await
+        let mut subscriptions = self.account_subscriptions.as_ref().map(|subscriptions| subscriptions.watch());
+        let (mut subscribe_tx, mut stream) =
+            geyser_client.subscribe_with_request(Some(self.subscribe_request())).await
                 .map_err(|err| Error::Custom(err.to_string()))?;
 
-        while let Some(message) = stream.next().await {
+        loop {
+            let message = tokio::select! {
+                message = stream.next() => message,
+                true = subscriptions_changed(&mut subscriptions) => {
+                    if let Err(err) = subscribe_tx.send(self.subscribe_request()).await {
+                        log::warn!("Не удалось обновить подписку на аккаунты: {:?}", err);
+                    }
+                    continue;
+                }
+            };
+            let Some(message) = message else {
+                break;
+            };
+
             match message {
-                Ok(msg) => {
-                    if let Some(UpdateOneof::Transaction(tx_update)) = msg.update_oneof {
+                Ok(msg) => match msg.update_oneof {
+                    Some(UpdateOneof::Transaction(tx_update)) => {
                         if let Some(tx_info) = tx_update.transaction {
                             let Some(raw_tx) = tx_info.transaction else {
                                 log::warn!("Нет поля transaction");
@@ -121,10 +207,32 @@ impl YellowstoneGrpcGeyser for YellowstoneGrpcGeyserClient {
                                 continue;
                             };
 
-                            handler(versioned_tx, meta).await;
+                            handler(versioned_tx, meta, tx_update.slot).await;
                         }
                     }
-                }
+                    Some(UpdateOneof::Account(account_update)) => {
+                        let (Some(account_handler), Some(account)) =
+                            (&self.account_handler, account_update.account)
+                        else {
+                            continue;
+                        };
+                        let Ok(pubkey) = Pubkey::try_from(account.pubkey.as_slice()) else {
+                            log::warn!("Некорректный адрес в account-обновлении");
+                            continue;
+                        };
+
+                        account_handler(pubkey, account.data);
+                    }
+                    Some(UpdateOneof::Slot(slot_update)) => {
+                        let (Some(slot_handler), Ok(status)) =
+                            (&self.slot_handler, CommitmentLevel::try_from(slot_update.status))
+                        else {
+                            continue;
+                        };
+                        slot_handler(slot_update.slot, status);
+                    }
+                    _ => {}
+                },
                 Err(error) => {
                     log::error!("Geyser stream error: {:?}", error);
                     break;
@@ -0,0 +1,314 @@
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{TransactionError, VersionedTransaction};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Notify;
+use yellowstone_grpc_proto::geyser::CommitmentLevel;
+use yellowstone_grpc_proto::prelude::TransactionStatusMeta as YellowstoneMeta;
+
+/// Сколько последних подтверждённых слотов помнить: транзакция и статус её слота
+/// приходят разными сообщениями потока и могут поменяться местами
+const CONFIRMED_SLOTS_KEPT: usize = 512;
+/// Подтверждения дошли дальше слота транзакции на столько слотов, а её слот не подтверждён:
+/// слот ушёл в отброшенный форк, статус берётся из RPC
+const CONFIRMATION_SLOT_MARGIN: u64 = 32;
+/// Сколько последних увиденных своих транзакций хранить для учёта исполнения
+const OBSERVED_TRANSACTIONS_KEPT: usize = 256;
+
+/// Результат выполнения транзакции, увиденной в потоке Geyser
+#[derive(Debug, Clone)]
+pub enum ObservedStatus {
+    Succeeded,
+    Failed(String),
+}
+
+/// Транзакция из потока Geyser и подтверждение её слота
+#[derive(Debug, Clone)]
+pub struct Observation {
+    pub status: ObservedStatus,
+    /// Слот, в котором транзакция обработана (processed)
+    pub slot: u64,
+    /// Слот подтверждён (confirmed или finalized)
+    pub confirmed: bool,
+    /// Слот не подтвердился, хотя подтверждения ушли дальше: результату потока верить нельзя
+    pub orphaned: bool,
+}
+
+/// Реестр подписей, ожидающих появления в потоке Geyser.
+/// Поток подписан на транзакции наших кошельков и на статусы слотов, поэтому отправленная
+/// покупка видна здесь раньше, чем её вернёт RPC-опрос статусов, в том числе на confirmed.
+#[derive(Default)]
+pub struct SignatureWatch {
+    pending: Mutex<HashMap<Signature, Option<(ObservedStatus, u64)>>>,
+    confirmed_slots: Mutex<BTreeSet<u64>>,
+    /// Наибольший подтверждённый слот; 0 — статусы слотов не приходят
+    highest_confirmed: AtomicU64,
+    /// Транзакции и meta отслеживаемых подписей; переживают `unwatch`
+    observed: Mutex<VecDeque<(Signature, VersionedTransaction, YellowstoneMeta)>>,
+    notify: Notify,
+}
+
+impl SignatureWatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn watch(&self, signature: Signature) {
+        self.pending.lock().unwrap().entry(signature).or_insert(None);
+    }
+
+    pub fn unwatch(&self, signatures: &[Signature]) {
+        let mut pending = self.pending.lock().unwrap();
+        for signature in signatures {
+            pending.remove(signature);
+        }
+    }
+
+    pub fn status(&self, signature: &Signature) -> Option<Observation> {
+        let (status, slot) = self.pending.lock().unwrap().get(signature).cloned().flatten()?;
+        let confirmed = self.confirmed_slots.lock().unwrap().contains(&slot);
+        let highest_confirmed = self.highest_confirmed.load(Ordering::Relaxed);
+
+        Some(Observation {
+            status,
+            slot,
+            confirmed,
+            orphaned: !confirmed && highest_confirmed > slot + CONFIRMATION_SLOT_MARGIN,
+        })
+    }
+
+    /// Обработка транзакции из потока: `true`, если это одна из отслеживаемых подписей
+    pub fn observe(&self, tx: &VersionedTransaction, meta: &YellowstoneMeta, slot: u64) -> bool {
+        let Some(signature) = tx.signatures.first() else {
+            return false;
+        };
+
+        let mut pending = self.pending.lock().unwrap();
+        let Some(entry) = pending.get_mut(signature) else {
+            return false;
+        };
+
+        let status = match &meta.err {
+            None => ObservedStatus::Succeeded,
+            Some(err) => ObservedStatus::Failed(
+                bincode::deserialize::<TransactionError>(&err.err)
+                    .map(|err| err.to_string())
+                    .unwrap_or_else(|_| "ошибка выполнения".to_string()),
+            ),
+        };
+        // При смене форка транзакция приходит повторно с новым слотом
+        *entry = Some((status, slot));
+        drop(pending);
+
+        let mut observed = self.observed.lock().unwrap();
+        observed.retain(|(observed_signature, ..)| observed_signature != signature);
+        observed.push_back((*signature, tx.clone(), meta.clone()));
+        if observed.len() > OBSERVED_TRANSACTIONS_KEPT {
+            observed.pop_front();
+        }
+        drop(observed);
+
+        self.notify.notify_waiters();
+        true
+    }
+
+    /// Транзакция и meta из потока для отслеживавшейся подписи
+    pub fn transaction(&self, signature: &Signature) -> Option<(VersionedTransaction, YellowstoneMeta)> {
+        self.observed
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(observed_signature, ..)| observed_signature == signature)
+            .map(|(_, tx, meta)| (tx.clone(), meta.clone()))
+    }
+
+    /// Статусы слотов приходят: подтверждение можно ждать из потока без RPC
+    pub fn tracks_confirmations(&self) -> bool {
+        self.highest_confirmed.load(Ordering::Relaxed) > 0
+    }
+
+    /// Статус слота из потока Geyser
+    pub fn slot_status(&self, slot: u64, commitment: CommitmentLevel) {
+        if commitment == CommitmentLevel::Processed {
+            return;
+        }
+
+        {
+            let mut confirmed_slots = self.confirmed_slots.lock().unwrap();
+            if !confirmed_slots.insert(slot) {
+                return;
+            }
+            while confirmed_slots.len() > CONFIRMED_SLOTS_KEPT {
+                confirmed_slots.pop_first();
+            }
+        }
+        self.highest_confirmed.fetch_max(slot, Ordering::Relaxed);
+
+        let observed_in_slot = self
+            .pending
+            .lock()
+            .unwrap()
+            .values()
+            .any(|entry| matches!(entry, Some((_, observed)) if *observed == slot));
+        if observed_in_slot {
+            self.notify.notify_waiters();
+        }
+    }
+
+    /// Ожидание новой отслеженной подписи или подтверждения её слота, не дольше `timeout`
+    pub async fn changed(&self, timeout: Duration) {
+        let _ = tokio::time::timeout(timeout, self.notify.notified()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::transaction::Transaction;
+    use yellowstone_grpc_proto::prelude::TransactionError as YellowstoneError;
+
+    fn transaction() -> VersionedTransaction {
+        let mut tx: VersionedTransaction = Transaction::new_with_payer(&[], Some(&Pubkey::new_unique())).into();
+        tx.signatures[0] = Signature::new_unique();
+        tx
+    }
+
+    fn failed_meta(err: TransactionError) -> YellowstoneMeta {
+        YellowstoneMeta {
+            err: Some(YellowstoneError {
+                err: bincode::serialize(&err).unwrap(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn observes_only_watched_signatures() {
+        let watch = SignatureWatch::new();
+        let tx = transaction();
+        let meta = YellowstoneMeta::default();
+
+        assert!(!watch.observe(&tx, &meta, 100), "подпись не отслеживается");
+        assert!(watch.transaction(&tx.signatures[0]).is_none());
+
+        watch.watch(tx.signatures[0]);
+        assert!(watch.status(&tx.signatures[0]).is_none(), "транзакция ещё не пришла");
+        assert!(watch.observe(&tx, &meta, 100));
+
+        let observation = watch.status(&tx.signatures[0]).unwrap();
+        assert!(matches!(observation.status, ObservedStatus::Succeeded));
+        assert_eq!(observation.slot, 100);
+        assert!(!observation.confirmed);
+        assert!(!observation.orphaned);
+    }
+
+    #[test]
+    fn failed_transaction_keeps_error() {
+        let watch = SignatureWatch::new();
+        let tx = transaction();
+        watch.watch(tx.signatures[0]);
+        watch.observe(&tx, &failed_meta(TransactionError::InsufficientFundsForFee), 100);
+
+        match watch.status(&tx.signatures[0]).unwrap().status {
+            ObservedStatus::Failed(err) => {
+                assert_eq!(err, TransactionError::InsufficientFundsForFee.to_string())
+            }
+            ObservedStatus::Succeeded => panic!("ожидалась ошибка выполнения"),
+        }
+    }
+
+    #[test]
+    fn confirmation_comes_from_slot_status() {
+        let watch = SignatureWatch::new();
+        let tx = transaction();
+        watch.watch(tx.signatures[0]);
+        watch.observe(&tx, &YellowstoneMeta::default(), 100);
+        assert!(!watch.tracks_confirmations());
+
+        // Processed не подтверждает слот
+        watch.slot_status(100, CommitmentLevel::Processed);
+        assert!(!watch.status(&tx.signatures[0]).unwrap().confirmed);
+        assert!(!watch.tracks_confirmations());
+
+        watch.slot_status(100, CommitmentLevel::Confirmed);
+        assert!(watch.status(&tx.signatures[0]).unwrap().confirmed);
+        assert!(watch.tracks_confirmations());
+    }
+
+    #[test]
+    fn slot_status_may_arrive_before_transaction() {
+        let watch = SignatureWatch::new();
+        let tx = transaction();
+        watch.watch(tx.signatures[0]);
+        watch.slot_status(100, CommitmentLevel::Finalized);
+        watch.observe(&tx, &YellowstoneMeta::default(), 100);
+
+        assert!(watch.status(&tx.signatures[0]).unwrap().confirmed);
+    }
+
+    #[test]
+    fn orphaned_after_confirmation_margin() {
+        let cases = [
+            // (наибольший подтверждённый слот, ожидание)
+            (100, false),
+            (100 + CONFIRMATION_SLOT_MARGIN, false),
+            (100 + CONFIRMATION_SLOT_MARGIN + 1, true),
+        ];
+
+        for (highest, orphaned) in cases {
+            let watch = SignatureWatch::new();
+            let tx = transaction();
+            watch.watch(tx.signatures[0]);
+            watch.observe(&tx, &YellowstoneMeta::default(), 100);
+            watch.slot_status(highest, CommitmentLevel::Confirmed);
+
+            let observation = watch.status(&tx.signatures[0]).unwrap();
+            assert_eq!(observation.orphaned, orphaned, "подтверждён слот {highest}");
+        }
+    }
+
+    #[test]
+    fn fork_switch_moves_transaction_to_new_slot() {
+        let watch = SignatureWatch::new();
+        let tx = transaction();
+        watch.watch(tx.signatures[0]);
+        watch.observe(&tx, &YellowstoneMeta::default(), 100);
+        watch.slot_status(100 + CONFIRMATION_SLOT_MARGIN + 1, CommitmentLevel::Confirmed);
+        assert!(watch.status(&tx.signatures[0]).unwrap().orphaned);
+
+        // Транзакция пришла повторно в слоте другого форка
+        let slot = 100 + CONFIRMATION_SLOT_MARGIN + 2;
+        watch.observe(&tx, &YellowstoneMeta::default(), slot);
+        let observation = watch.status(&tx.signatures[0]).unwrap();
+        assert_eq!(observation.slot, slot);
+        assert!(!observation.orphaned);
+        assert!(!observation.confirmed);
+    }
+
+    #[test]
+    fn old_confirmed_slots_are_dropped() {
+        let watch = SignatureWatch::new();
+        for slot in 1..=(CONFIRMED_SLOTS_KEPT as u64 + 1) {
+            watch.slot_status(slot, CommitmentLevel::Confirmed);
+        }
+
+        let confirmed_slots = watch.confirmed_slots.lock().unwrap();
+        assert_eq!(confirmed_slots.len(), CONFIRMED_SLOTS_KEPT);
+        assert!(!confirmed_slots.contains(&1));
+    }
+
+    #[test]
+    fn transaction_survives_unwatch() {
+        let watch = SignatureWatch::new();
+        let tx = transaction();
+        watch.watch(tx.signatures[0]);
+        watch.observe(&tx, &YellowstoneMeta::default(), 100);
+        watch.unwatch(&tx.signatures);
+
+        assert!(watch.status(&tx.signatures[0]).is_none());
+        assert!(watch.transaction(&tx.signatures[0]).is_some(), "нужна для учёта исполнения");
+    }
+}
@@ -0,0 +1,43 @@
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use tokio::sync::watch;
+
+/// Аккаунты, на которые подписка оформляется во время работы (пулы, их хранилища и lookup tables).
+/// При изменении набора поток Geyser получает новый `SubscribeRequest` без переподключения.
+pub struct AccountSubscriptions {
+    accounts: watch::Sender<HashSet<Pubkey>>,
+}
+
+impl Default for AccountSubscriptions {
+    fn default() -> Self {
+        Self {
+            accounts: watch::Sender::new(HashSet::new()),
+        }
+    }
+}
+
+impl AccountSubscriptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(&self, accounts: &[Pubkey]) {
+        self.accounts.send_if_modified(|current| {
+            accounts.iter().fold(false, |changed, account| current.insert(*account) || changed)
+        });
+    }
+
+    pub fn unsubscribe(&self, accounts: &[Pubkey]) {
+        self.accounts.send_if_modified(|current| {
+            accounts.iter().fold(false, |changed, account| current.remove(account) || changed)
+        });
+    }
+
+    pub fn accounts(&self) -> Vec<String> {
+        self.accounts.borrow().iter().map(|account| account.to_string()).collect()
+    }
+
+    pub fn watch(&self) -> watch::Receiver<HashSet<Pubkey>> {
+        self.accounts.subscribe()
+    }
+}
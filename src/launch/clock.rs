@@ -0,0 +1,45 @@
+use tokio::sync::watch;
+
+/// Последний слот из потока Geyser. Покупки, ожидающие активации пула,
+/// просыпаются по обновлению слота без опроса RPC.
+pub struct SlotClock {
+    slot: watch::Sender<u64>,
+}
+
+impl Default for SlotClock {
+    fn default() -> Self {
+        Self {
+            slot: watch::Sender::new(0),
+        }
+    }
+}
+
+impl SlotClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Обновление слота; устаревшие и повторные значения игнорируются
+    pub fn update(&self, slot: u64) {
+        self.slot.send_if_modified(|current| {
+            if slot > *current {
+                *current = slot;
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    /// 0, пока из Geyser не пришло ни одного слота
+    pub fn current(&self) -> u64 {
+        *self.slot.borrow()
+    }
+
+    /// Ожидание слота `slot` (или более позднего)
+    pub async fn wait_for(&self, slot: u64) {
+        let mut receiver = self.slot.subscribe();
+        // Отправитель живёт вместе с часами, поэтому ошибка ожидания невозможна
+        let _ = receiver.wait_for(|current| *current >= slot).await;
+    }
+}
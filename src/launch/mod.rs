@@ -0,0 +1,184 @@
+pub mod clock;
+pub mod scheduler;
+
+use crate::core::accounts::unix_now;
+use crate::launch::clock::SlotClock;
+use crate::meteora::damm_v2::fetch_pool as fetch_damm_v2_pool;
+use crate::meteora::dlmm::fetch_lb_pair;
+use crate::meteora::fetch_pool::fetch_pool_accounts;
+use crate::raydium::amm_v4::fetch_amm;
+use crate::raydium::cpmm::fetch_pool as fetch_cpmm_pool;
+use crate::strategy::Venue;
+
+use anyhow::Result;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::time::Duration;
+
+/// Средняя длительность слота для оценки времени до активации
+const SLOT_DURATION: Duration = Duration::from_millis(400);
+
+/// Момент открытия торговли в пуле
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activation {
+    Slot(u64),
+    /// Unix-время, секунды
+    Timestamp(u64),
+}
+
+impl Activation {
+    /// `activation_type` пулов Meteora: 0 — слоты, 1 — секунды
+    pub fn from_meteora(activation_type: u8, activation_point: u64) -> Self {
+        match activation_type {
+            0 => Activation::Slot(activation_point),
+            _ => Activation::Timestamp(activation_point),
+        }
+    }
+}
+
+/// Расписание запуска пула из его состояния
+#[derive(Debug, Clone)]
+pub struct LaunchSchedule {
+    pub activation: Activation,
+    /// Alpha vault или другой адрес, которому разрешены свапы до активации
+    pub alpha_vault: Option<Pubkey>,
+}
+
+impl LaunchSchedule {
+    /// Торговля уже открыта. Пока слот из Geyser не известен, слотовая активация считается будущей.
+    pub fn is_open(&self, clock: &SlotClock) -> bool {
+        match self.activation {
+            Activation::Slot(slot) => clock.current() != 0 && clock.current() >= slot,
+            Activation::Timestamp(timestamp) => unix_now() >= timestamp,
+        }
+    }
+
+    /// Оценка времени до активации; для слотов — по средней длительности слота
+    pub fn eta(&self, clock: &SlotClock) -> Duration {
+        match self.activation {
+            Activation::Slot(slot) => SLOT_DURATION * slot.saturating_sub(clock.current()).min(u32::MAX as u64) as u32,
+            Activation::Timestamp(timestamp) => {
+                Duration::from_secs(timestamp.saturating_sub(unix_now()))
+            }
+        }
+    }
+}
+
+fn alpha_vault(address: Pubkey) -> Option<Pubkey> {
+    (address != Pubkey::default()).then_some(address)
+}
+
+/// Расписание из состояния пула
+pub async fn fetch_schedule(rpc: &RpcClient, venue: Venue, pool: &Pubkey) -> Result<LaunchSchedule> {
+    let schedule = match venue {
+        Venue::MeteoraAmm => {
+            let bootstrapping = fetch_pool_accounts(rpc, pool).await?.bootstrapping;
+            LaunchSchedule {
+                activation: Activation::from_meteora(bootstrapping.activation_type, bootstrapping.activation_point),
+                alpha_vault: alpha_vault(bootstrapping.whitelisted_vault),
+            }
+        }
+        Venue::MeteoraDlmm => {
+            let pair = fetch_lb_pair(rpc, pool).await?;
+            LaunchSchedule {
+                activation: Activation::from_meteora(pair.activation_type, pair.activation_point),
+                alpha_vault: alpha_vault(pair.pre_activation_swap_address),
+            }
+        }
+        Venue::MeteoraDammV2 => {
+            let state = fetch_damm_v2_pool(rpc, pool).await?;
+            LaunchSchedule {
+                activation: Activation::from_meteora(state.activation_type, state.activation_point),
+                alpha_vault: alpha_vault(state.whitelisted_vault),
+            }
+        }
+        Venue::RaydiumAmmV4 => LaunchSchedule {
+            activation: Activation::Timestamp(fetch_amm(rpc, pool).await?.state_data.pool_open_time),
+            alpha_vault: None,
+        },
+        Venue::RaydiumCpmm => LaunchSchedule {
+            activation: Activation::Timestamp(fetch_cpmm_pool(rpc, pool).await?.open_time),
+            alpha_vault: None,
+        },
+    };
+    Ok(schedule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(activation: Activation) -> LaunchSchedule {
+        LaunchSchedule { activation, alpha_vault: None }
+    }
+
+    #[test]
+    fn meteora_activation_type() {
+        assert_eq!(Activation::from_meteora(0, 42), Activation::Slot(42));
+        assert_eq!(Activation::from_meteora(1, 42), Activation::Timestamp(42));
+    }
+
+    #[test]
+    fn slot_activation_is_open() {
+        let clock = SlotClock::new();
+        let cases = [
+            // (текущий слот, слот активации, открыта)
+            (0, 0, false),
+            (0, 100, false),
+            (99, 100, false),
+            (100, 100, true),
+            (150, 100, true),
+        ];
+
+        for (current, slot, open) in cases {
+            clock.update(current);
+            assert_eq!(
+                schedule(Activation::Slot(slot)).is_open(&clock),
+                open,
+                "слот {current}, активация {slot}"
+            );
+        }
+    }
+
+    #[test]
+    fn timestamp_activation_is_open() {
+        let clock = SlotClock::new();
+        let now = unix_now();
+
+        assert!(schedule(Activation::Timestamp(now - 10)).is_open(&clock));
+        assert!(schedule(Activation::Timestamp(now)).is_open(&clock));
+        assert!(!schedule(Activation::Timestamp(now + 60)).is_open(&clock));
+    }
+
+    #[test]
+    fn slot_eta_uses_average_slot_duration() {
+        let clock = SlotClock::new();
+        clock.update(100);
+        let cases = [
+            // (слот активации, ожидание)
+            (110, Duration::from_secs(4)),
+            (101, SLOT_DURATION),
+            (100, Duration::ZERO),
+            (50, Duration::ZERO),
+        ];
+
+        for (slot, expected) in cases {
+            assert_eq!(schedule(Activation::Slot(slot)).eta(&clock), expected, "активация {slot}");
+        }
+        // Длительность не переполняется для далёких слотов
+        assert_eq!(
+            schedule(Activation::Slot(u64::MAX)).eta(&clock),
+            SLOT_DURATION * u32::MAX
+        );
+    }
+
+    #[test]
+    fn timestamp_eta() {
+        let clock = SlotClock::new();
+        let now = unix_now();
+
+        let eta = schedule(Activation::Timestamp(now + 100)).eta(&clock);
+        assert!(eta <= Duration::from_secs(100) && eta >= Duration::from_secs(99), "{eta:?}");
+        assert_eq!(schedule(Activation::Timestamp(now - 100)).eta(&clock), Duration::ZERO);
+    }
+}
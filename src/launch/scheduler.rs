@@ -0,0 +1,235 @@
+use crate::config::{Lamports, LaunchConfig};
+use crate::launch::clock::SlotClock;
+use crate::launch::{Activation, LaunchSchedule};
+use crate::metrics::SCHEDULED_LAUNCHES;
+use crate::strategy::Venue;
+
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Покупка, ожидающая активации пула
+#[derive(Debug, Clone)]
+pub struct ScheduledLaunch {
+    pub venue: Venue,
+    pub pool: Pubkey,
+    pub token_a: Pubkey,
+    pub token_b: Pubkey,
+    pub amount: Lamports,
+    /// Стратегия, принявшая решение о покупке
+    pub strategy: &'static str,
+    pub schedule: LaunchSchedule,
+    pub scheduled_at: SystemTime,
+}
+
+/// Запланированная покупка в ответе admin API
+#[derive(Debug, Serialize)]
+pub struct LaunchReport {
+    pub venue: String,
+    pub pool: String,
+    pub token_a: String,
+    pub token_b: String,
+    pub amount_lamports: u64,
+    pub strategy: &'static str,
+    /// `slot` или `timestamp`
+    pub activation_type: &'static str,
+    pub activation_point: u64,
+    pub alpha_vault: Option<String>,
+    /// Оценка времени до активации, секунды
+    pub eta_secs: u64,
+    /// Unix-время постановки в расписание
+    pub scheduled_at: u64,
+}
+
+/// Момент отправки запланированной покупки
+#[derive(Debug, PartialEq, Eq)]
+enum SendAt {
+    Slot(u64),
+    Time(SystemTime),
+}
+
+/// Слот активации минус `lead_slots` или время активации плюс `timestamp_delay_ms`
+fn send_at(config: &LaunchConfig, activation: Activation) -> SendAt {
+    match activation {
+        Activation::Slot(slot) => SendAt::Slot(slot.saturating_sub(config.lead_slots)),
+        Activation::Timestamp(timestamp) => SendAt::Time(
+            UNIX_EPOCH + Duration::from_secs(timestamp) + Duration::from_millis(config.timestamp_delay_ms),
+        ),
+    }
+}
+
+/// Расписание покупок в пулах, торговля в которых ещё не открыта.
+/// Покупка до активации (и во время фазы alpha vault) только сжигает комиссию,
+/// поэтому она отправляется в слоте активации по потоку слотов Geyser.
+pub struct LaunchScheduler {
+    clock: Arc<SlotClock>,
+    launches: RwLock<HashMap<Pubkey, ScheduledLaunch>>,
+}
+
+impl LaunchScheduler {
+    pub fn new(clock: Arc<SlotClock>) -> Self {
+        Self {
+            clock,
+            launches: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn clock(&self) -> &SlotClock {
+        &self.clock
+    }
+
+    /// Регистрация покупки; `false`, если пул уже ожидает активации
+    pub fn insert(&self, launch: ScheduledLaunch) -> bool {
+        let mut launches = self.launches.write().unwrap();
+        if launches.contains_key(&launch.pool) {
+            return false;
+        }
+        launches.insert(launch.pool, launch);
+        SCHEDULED_LAUNCHES.set(launches.len() as i64);
+        true
+    }
+
+    pub fn remove(&self, pool: &Pubkey) {
+        let mut launches = self.launches.write().unwrap();
+        launches.remove(pool);
+        SCHEDULED_LAUNCHES.set(launches.len() as i64);
+    }
+
+    /// Ожидание момента отправки, см. [`send_at`]
+    pub async fn wait(&self, config: &LaunchConfig, schedule: &LaunchSchedule) {
+        match send_at(config, schedule.activation) {
+            SendAt::Slot(slot) => self.clock.wait_for(slot).await,
+            SendAt::Time(open_at) => {
+                let delay = open_at.duration_since(SystemTime::now()).unwrap_or_default();
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+
+    /// Запланированные покупки, ближайшие первыми
+    pub fn reports(&self) -> Vec<LaunchReport> {
+        let launches = self.launches.read().unwrap();
+        let mut reports: Vec<(Duration, LaunchReport)> = launches
+            .values()
+            .map(|launch| {
+                let eta = launch.schedule.eta(&self.clock);
+                let (activation_type, activation_point) = match launch.schedule.activation {
+                    Activation::Slot(slot) => ("slot", slot),
+                    Activation::Timestamp(timestamp) => ("timestamp", timestamp),
+                };
+                let report = LaunchReport {
+                    venue: format!("{:?}", launch.venue),
+                    pool: launch.pool.to_string(),
+                    token_a: launch.token_a.to_string(),
+                    token_b: launch.token_b.to_string(),
+                    amount_lamports: launch.amount.0,
+                    strategy: launch.strategy,
+                    activation_type,
+                    activation_point,
+                    alpha_vault: launch.schedule.alpha_vault.map(|vault| vault.to_string()),
+                    eta_secs: eta.as_secs(),
+                    scheduled_at: launch
+                        .scheduled_at
+                        .duration_since(UNIX_EPOCH)
+                        .map(|since| since.as_secs())
+                        .unwrap_or_default(),
+                };
+                (eta, report)
+            })
+            .collect();
+
+        reports.sort_by_key(|(eta, _)| *eta);
+        reports.into_iter().map(|(_, report)| report).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn launch(pool: Pubkey, activation: Activation) -> ScheduledLaunch {
+        ScheduledLaunch {
+            venue: Venue::MeteoraDlmm,
+            pool,
+            token_a: Pubkey::new_unique(),
+            token_b: Pubkey::new_unique(),
+            amount: Lamports(1),
+            strategy: "test",
+            schedule: LaunchSchedule { activation, alpha_vault: None },
+            scheduled_at: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn send_point_accounts_for_lead_and_delay() {
+        let config = |lead_slots, timestamp_delay_ms| LaunchConfig {
+            lead_slots,
+            timestamp_delay_ms,
+            ..Default::default()
+        };
+        let cases = [
+            // (lead_slots, timestamp_delay_ms, активация, момент отправки)
+            (0, 0, Activation::Slot(1_000), SendAt::Slot(1_000)),
+            (2, 0, Activation::Slot(1_000), SendAt::Slot(998)),
+            (5, 0, Activation::Slot(3), SendAt::Slot(0)),
+            // Задержка не влияет на слотовую активацию
+            (0, 500, Activation::Slot(1_000), SendAt::Slot(1_000)),
+            (0, 0, Activation::Timestamp(1_700_000_000), SendAt::Time(UNIX_EPOCH + Duration::from_secs(1_700_000_000))),
+            (
+                // Упреждение в слотах не влияет на активацию по времени
+                2,
+                500,
+                Activation::Timestamp(1_700_000_000),
+                SendAt::Time(UNIX_EPOCH + Duration::from_millis(1_700_000_000_500)),
+            ),
+        ];
+
+        for (lead_slots, delay, activation, expected) in cases {
+            assert_eq!(
+                send_at(&config(lead_slots, delay), activation),
+                expected,
+                "lead_slots {lead_slots}, задержка {delay} мс, {activation:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn wait_returns_at_lead_slot() {
+        let clock = Arc::new(SlotClock::new());
+        let scheduler = LaunchScheduler::new(clock.clone());
+        let config = LaunchConfig { lead_slots: 2, ..Default::default() };
+        let schedule = LaunchSchedule { activation: Activation::Slot(100), alpha_vault: None };
+
+        clock.update(97);
+        let early = tokio::time::timeout(Duration::from_millis(50), scheduler.wait(&config, &schedule)).await;
+        assert!(early.is_err(), "слот 97 раньше момента отправки 98");
+
+        clock.update(98);
+        let woke = tokio::time::timeout(Duration::from_millis(50), scheduler.wait(&config, &schedule)).await;
+        assert!(woke.is_ok(), "слот 98 — момент отправки");
+    }
+
+    #[test]
+    fn reports_nearest_first_and_rejects_duplicates() {
+        let clock = Arc::new(SlotClock::new());
+        clock.update(100);
+        let scheduler = LaunchScheduler::new(clock);
+        let (near, far) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert!(scheduler.insert(launch(far, Activation::Slot(1_000))));
+        assert!(scheduler.insert(launch(near, Activation::Slot(110))));
+        assert!(!scheduler.insert(launch(near, Activation::Slot(120))), "пул уже ожидает активации");
+
+        let reports = scheduler.reports();
+        let pools: Vec<String> = reports.iter().map(|report| report.pool.clone()).collect();
+        assert_eq!(pools, [near.to_string(), far.to_string()]);
+        assert_eq!(reports[0].activation_type, "slot");
+        assert_eq!(reports[0].activation_point, 110);
+        assert_eq!(reports[0].eta_secs, 4);
+
+        scheduler.remove(&near);
+        assert_eq!(scheduler.reports().len(), 1);
+    }
+}
@@ -1,24 +1,62 @@
+mod bench;
+mod cli;
 mod config;
 mod geyser;
 mod core;
+mod fees;
+mod launch;
 mod tx_senders;
 mod meteora;
 mod metrics;
 mod metrics_server;
+mod nonce;
+mod pools;
+mod pump_fun;
+mod raydium;
+mod strategy;
+mod wallet;
 
-use crate::config::PingThingsArgs;
+use crate::bench::Bench;
+use crate::cli::{Cli, Command};
+use crate::config::reload::watch_config;
+use crate::config::{PingThingsArgs, SharedConfig, StrategyKind};
+use crate::core::alt::AltResolver;
+use crate::fees::compute::ComputeProfiler;
+use crate::fees::FeeEstimator;
+use crate::geyser::signatures::SignatureWatch;
+use crate::geyser::subscriptions::AccountSubscriptions;
 use crate::geyser::{YellowstoneGrpcGeyser, YellowstoneGrpcGeyserClient};
-use crate::meteora::controller::MeteoraController;
+use crate::launch::clock::SlotClock;
+use crate::launch::scheduler::LaunchScheduler;
+use crate::pools::controller::PoolController;
+use crate::pools::reserves::ReserveCache;
 use crate::metrics_server::start_metrics_server;
-use crate::tx_senders::constants::METEORA_PROGRAM_ID;
+use crate::nonce::NoncePool;
+use crate::pump_fun::bonding_curve::{fetch_global, BONDING_CURVE_DISCRIMINATOR};
+use crate::pump_fun::controller::PumpFunController;
+use crate::pump_fun::graduation::GraduationTracker;
+use crate::tx_senders::constants::{
+    METEORA_DAMM_V2_PROGRAM_ID, METEORA_DLMM_PROGRAM_ID, METEORA_PROGRAM_ID, PUMP_FUN_PROGRAM_ADDR,
+    RAYDIUM_AMM_V4_PROGRAM_ID, RAYDIUM_CPMM_PROGRAM_ID,
+};
+use crate::tx_senders::lookup_table::LookupTables;
+use crate::tx_senders::submission::SubmissionManager;
+use crate::wallet::pool::WalletPool;
 
-use solana_sdk::signature::Keypair;
+use clap::Parser;
+use solana_client::nonblocking::rpc_client::RpcClient;
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::{Arc};
 use tracing::{info, warn};
 use tokio::sync::RwLock;
 use tracing_subscriber::FmtSubscriber;
-use yellowstone_grpc_proto::geyser::{CommitmentLevel, SubscribeRequestFilterTransactions};
+use yellowstone_grpc_proto::geyser::{
+    subscribe_request_filter_accounts_filter::Filter as AccountsFilter,
+    subscribe_request_filter_accounts_filter_memcmp::Data as MemcmpData, CommitmentLevel,
+    SubscribeRequestFilterAccounts, SubscribeRequestFilterAccountsFilter,
+    SubscribeRequestFilterAccountsFilterMemcmp, SubscribeRequestFilterTransactions,
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -27,46 +65,235 @@ async fn main() -> anyhow::Result<()> {
         .with_max_level(tracing::Level::INFO)
         .init();
 
-    // Загрузка конфигурации
-    let args = PingThingsArgs::new();
-    let user = Arc::new(Keypair::from_base58_string(&args.private_key));
-    let meteora_controller = MeteoraController::new(args.clone(), user.clone());
+    let cli = Cli::parse();
+
+    match cli.command.unwrap_or(Command::Run) {
+        Command::Run => run(cli.config.clone(), PingThingsArgs::from_path(&cli.config)?).await,
+        Command::Bench { pool, repeats } => {
+            let bench = Bench::for_pool(PingThingsArgs::from_path(&cli.config)?, pool).await?;
+            bench.run(repeats).await;
+            Ok(())
+        }
+        Command::Decode { target } => {
+            cli::decode::run(&PingThingsArgs::from_path(&cli.config)?, &target).await
+        }
+        Command::Simulate { pool } => {
+            cli::simulate::run(&PingThingsArgs::from_path(&cli.config)?, &pool).await
+        }
+        Command::CheckConfig => cli::check_config::run(&cli.config),
+        Command::Keystore { action } => cli::keystore::run(action),
+        Command::Alt { action } => {
+            cli::alt::run(&PingThingsArgs::from_path(&cli.config)?, action).await
+        }
+        Command::Nonce { action } => {
+            cli::nonce::run(&PingThingsArgs::from_path(&cli.config)?, action).await
+        }
+        Command::PumpFun { action } => {
+            cli::pump_fun::run(&PingThingsArgs::from_path(&cli.config)?, action).await
+        }
+    }
+}
+
+/// Основной режим: подписка на Geyser и снайпинг новых пулов
+async fn run(config_path: PathBuf, args: PingThingsArgs) -> anyhow::Result<()> {
+    if args.geyser_url.is_empty() {
+        anyhow::bail!("geyser_url не задан (config.yaml или {}GEYSER_URL)", config::ENV_PREFIX);
+    }
+
+    let wallets = Arc::new(WalletPool::from_config(&args)?);
+    info!("Кошельков в ротации: {}", wallets.wallets().len());
+    let config = SharedConfig::new(args.clone());
+    let fees = Arc::new(FeeEstimator::new());
+    let compute = Arc::new(ComputeProfiler::new());
+    let rpc = Arc::new(RpcClient::new(args.http_rpc.clone()));
+    let nonces = if args.nonce.enabled {
+        let nonces = NoncePool::load(&rpc, &args.nonce.accounts).await?;
+        info!("Durable nonce аккаунтов: {}", nonces.len());
+        Arc::new(nonces)
+    } else {
+        Arc::new(NoncePool::default())
+    };
+    let lookup_tables = Arc::new(LookupTables::load(&rpc, &args.lookup_tables).await?);
+    // Миграции Pump.fun нужны и без снайпинга bonding curve, если включена стратегия graduation
+    let track_graduations = args.strategies.contains(&StrategyKind::Graduation);
+    let mut graduations = GraduationTracker::new(config.clone());
+    if track_graduations {
+        graduations = graduations.with_global(fetch_global(&rpc).await?);
+    }
+    let graduations = Arc::new(graduations);
+    let signature_watch = Arc::new(SignatureWatch::new());
+    let submissions = Arc::new(SubmissionManager::new(
+        rpc.clone(),
+        signature_watch.clone(),
+        nonces.clone(),
+    ));
+    let slot_clock = Arc::new(SlotClock::new());
+    let launches = Arc::new(LaunchScheduler::new(slot_clock.clone()));
+    let reserves = Arc::new(ReserveCache::new());
+    let account_subscriptions = Arc::new(AccountSubscriptions::new());
+    let alt_resolver =
+        Arc::new(AltResolver::new(rpc.clone()).with_subscriptions(account_subscriptions.clone()));
+    let pool_controller = PoolController::new(
+        config.clone(),
+        wallets.clone(),
+        fees.clone(),
+        compute,
+        lookup_tables,
+        submissions.clone(),
+        nonces.clone(),
+        alt_resolver.clone(),
+        graduations.clone(),
+        launches.clone(),
+        reserves.clone(),
+        account_subscriptions.clone(),
+    );
+
+    let pump_fun_controller = args
+        .pump_fun
+        .enabled
+        .then(|| PumpFunController::new(config.clone(), wallets.clone(), rpc.clone(), submissions.clone()));
+
+    // Перезагрузка параметров стратегии при изменении файла конфигурации
+    tokio::spawn(watch_config(config_path, config));
 
     // Запуск HTTP-сервера для метрик Prometheus
-    tokio::spawn(async {
-        start_metrics_server().await;
+    let admin_reserves = reserves.clone();
+    tokio::spawn(async move {
+        start_metrics_server(launches, admin_reserves).await;
     });
 
-    // Настройка фильтра транзакций для Meteora
+    // Настройка фильтра транзакций для Meteora (dynamic AMM, DLMM и DAMM v2)
     let mut transaction_filters = HashMap::new();
     let meteora_filter = SubscribeRequestFilterTransactions {
         vote: Some(false),
         failed: Some(false),
-        account_include: vec![METEORA_PROGRAM_ID.to_string()],
+        account_include: vec![
+            METEORA_PROGRAM_ID.to_string(),
+            METEORA_DLMM_PROGRAM_ID.to_string(),
+            METEORA_DAMM_V2_PROGRAM_ID.to_string(),
+        ],
         account_exclude: vec![],
         account_required: vec![],
         signature: None,
     };
     transaction_filters.insert("meteora_transaction_filter".to_string(), meteora_filter);
 
+    // Пулы Raydium обрабатываются тем же контроллером, что и Meteora
+    if args.raydium.enabled {
+        let raydium_filter = SubscribeRequestFilterTransactions {
+            vote: Some(false),
+            failed: Some(false),
+            account_include: vec![RAYDIUM_AMM_V4_PROGRAM_ID.to_string(), RAYDIUM_CPMM_PROGRAM_ID.to_string()],
+            account_exclude: vec![],
+            account_required: vec![],
+            signature: None,
+        };
+        transaction_filters.insert("raydium_transaction_filter".to_string(), raydium_filter);
+    }
+
+    // Транзакции Pump.fun: создание токенов и миграции для стратегии graduation
+    if pump_fun_controller.is_some() || track_graduations {
+        let pump_fun_filter = SubscribeRequestFilterTransactions {
+            vote: Some(false),
+            failed: Some(false),
+            account_include: vec![PUMP_FUN_PROGRAM_ADDR.to_string()],
+            account_exclude: vec![],
+            account_required: vec![],
+            signature: None,
+        };
+        transaction_filters.insert("pump_fun_transaction_filter".to_string(), pump_fun_filter);
+    }
+
+    // Транзакции наших кошельков (включая неуспешные) для подтверждения отправленных покупок
+    let wallet_filter = SubscribeRequestFilterTransactions {
+        vote: Some(false),
+        failed: None,
+        account_include: wallets.wallets().iter().map(|w| w.pubkey().to_string()).collect(),
+        account_exclude: vec![],
+        account_required: vec![],
+        signature: None,
+    };
+    transaction_filters.insert("wallet_transaction_filter".to_string(), wallet_filter);
+
+    // Lookup tables из кэша AltResolver подписываются через AccountSubscriptions
+    let mut account_filters = HashMap::new();
+
+    // Durable nonce аккаунты: значение для подписи берётся из кэша, а не запросом при отправке
+    if !nonces.is_empty() {
+        account_filters.insert(
+            "nonce_filter".to_string(),
+            SubscribeRequestFilterAccounts {
+                account: nonces.addresses().iter().map(|address| address.to_string()).collect(),
+                ..Default::default()
+            },
+        );
+    }
+
+    // Bonding curves Pump.fun для отслеживания их заполнения до миграции: только аккаунты
+    // с дискриминатором BondingCurve (размер не фильтруется — программа дописывает поля в конец)
+    if track_graduations {
+        account_filters.insert(
+            "pump_fun_curve_filter".to_string(),
+            SubscribeRequestFilterAccounts {
+                owner: vec![PUMP_FUN_PROGRAM_ADDR.to_string()],
+                filters: vec![SubscribeRequestFilterAccountsFilter {
+                    filter: Some(AccountsFilter::Memcmp(SubscribeRequestFilterAccountsFilterMemcmp {
+                        offset: 0,
+                        data: Some(MemcmpData::Bytes(BONDING_CURVE_DISCRIMINATOR.to_vec())),
+                    })),
+                }],
+                ..Default::default()
+            },
+        );
+    }
+
+    let slot_watch = signature_watch.clone();
     let geyser = YellowstoneGrpcGeyserClient::new(
         args.geyser_url.clone(),
         Some(args.geyser_x_token.clone()),
         Some(CommitmentLevel::Processed),
-        HashMap::new(),
+        account_filters,
         transaction_filters,
         Arc::new(RwLock::new(HashSet::new())),
-    );
+    )
+    .with_account_handler(Arc::new(move |pubkey, data| {
+        alt_resolver.update(&pubkey, &data);
+        graduations.update(&pubkey, &data);
+        reserves.update(&pubkey, &data);
+        nonces.update(&pubkey, &data);
+    }))
+    .with_account_subscriptions(account_subscriptions)
+    .with_slot_handler(Arc::new(move |slot, status| {
+        if status == CommitmentLevel::Processed {
+            slot_clock.update(slot);
+        }
+        slot_watch.slot_status(slot, status);
+    }));
 
     info!("Подключение к Geyser установлено");
 
     // Подписка на поток транзакций через Geyser
     geyser
-        .consume(move |tx, meta| {
-            let meteora_controller = meteora_controller.clone();
-            Box::pin(async move {
-                let _ = meteora_controller.transaction_handler(tx, meta).await;
-            })
+        .consume(move |tx, meta, slot| {
+            fees.observe(&tx);
+
+            // Свои транзакции только подтверждают отправленные покупки;
+            // остальные обрабатываются в отдельной задаче, чтобы сделка не блокировала поток
+            if !signature_watch.observe(&tx, &meta, slot) {
+                if let Some(pump_fun_controller) = &pump_fun_controller {
+                    let pump_fun_controller = pump_fun_controller.clone();
+                    let (tx, meta) = (tx.clone(), meta.clone());
+                    tokio::spawn(async move {
+                        let _ = pump_fun_controller.transaction_handler(tx, meta).await;
+                    });
+                }
+
+                let pool_controller = pool_controller.clone();
+                tokio::spawn(async move {
+                    let _ = pool_controller.transaction_handler(tx, meta).await;
+                });
+            }
+            Box::pin(async {})
         })
         .await?;
 
@@ -0,0 +1,443 @@
+use crate::launch::clock::SlotClock;
+use crate::tx_senders::constants::METEORA_DAMM_V2_PROGRAM_ID;
+
+use anyhow::{anyhow, bail, Context, Result};
+use borsh::BorshDeserialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Точность ставок комиссии (1e9 = 100%)
+const FEE_DENOMINATOR: u128 = 1_000_000_000;
+/// Программа ограничивает суммарную комиссию 50%
+const MAX_FEE_NUMERATOR: u128 = 500_000_000;
+const BASIS_POINT_MAX: u128 = 10_000;
+
+/// Базовая комиссия с планировщиком: снижается от `cliff_fee_numerator` по периодам после активации
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct BaseFeeStruct {
+    pub cliff_fee_numerator: u64,
+    /// 0 — линейное снижение, 1 — экспоненциальное
+    pub fee_scheduler_mode: u8,
+    _padding_0: [u8; 5],
+    pub number_of_period: u16,
+    pub period_frequency: u64,
+    pub reduction_factor: u64,
+    _padding_1: u64,
+}
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct DynamicFeeStruct {
+    pub initialized: u8,
+    _padding: [u8; 7],
+    _max_volatility_accumulator: u32,
+    pub variable_fee_control: u32,
+    pub bin_step: u16,
+    _filter_period: u16,
+    _decay_period: u16,
+    _reduction_factor: u16,
+    _last_update_timestamp: u64,
+    _bin_step_u128: u128,
+    _sqrt_price_reference: u128,
+    pub volatility_accumulator: u128,
+    _volatility_reference: u128,
+}
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct PoolFeesStruct {
+    pub base_fee: BaseFeeStruct,
+    _protocol_fee_percent: u8,
+    _partner_fee_percent: u8,
+    _referral_fee_percent: u8,
+    _padding_0: [u8; 5],
+    pub dynamic_fee: DynamicFeeStruct,
+    _padding_1: [u64; 2],
+}
+
+/// Аккаунт пула DAMM v2 (без дискриминатора); поля после `pool_type` не нужны
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct Pool {
+    pub pool_fees: PoolFeesStruct,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    /// Alpha vault, покупающий до активации
+    pub whitelisted_vault: Pubkey,
+    _partner: Pubkey,
+    /// Ликвидность, Q64.64
+    pub liquidity: u128,
+    _padding: u128,
+    _protocol_a_fee: u64,
+    _protocol_b_fee: u64,
+    _partner_a_fee: u64,
+    _partner_b_fee: u64,
+    pub sqrt_min_price: u128,
+    pub sqrt_max_price: u128,
+    /// Корень цены B за A, Q64.64
+    pub sqrt_price: u128,
+    /// С этого момента пул открыт для свапов
+    pub activation_point: u64,
+    /// 0 — activation_point в слотах, 1 — в секундах
+    pub activation_type: u8,
+    /// 0 — торговля включена
+    pub pool_status: u8,
+    /// 0 — SPL Token, 1 — Token-2022
+    pub token_a_flag: u8,
+    pub token_b_flag: u8,
+    /// 0 — комиссия в обоих токенах, 1 — только в токене B
+    pub collect_fee_mode: u8,
+    _pool_type: u8,
+}
+
+impl Pool {
+    pub fn is_enabled(&self) -> bool {
+        self.pool_status == 0
+    }
+
+    /// Базовая комиссия в момент `current_point`. До активации действует
+    /// минимальная ставка последнего периода, как для свапов alpha vault.
+    pub fn base_fee_numerator(&self, current_point: u64) -> u128 {
+        let base = &self.pool_fees.base_fee;
+        let cliff = base.cliff_fee_numerator as u128;
+        if base.period_frequency == 0 {
+            return cliff;
+        }
+
+        let max_period = base.number_of_period as u64;
+        let period = if current_point < self.activation_point {
+            max_period
+        } else {
+            ((current_point - self.activation_point) / base.period_frequency).min(max_period)
+        };
+
+        let reduction = base.reduction_factor as u128;
+        match base.fee_scheduler_mode {
+            0 => cliff.saturating_sub(period as u128 * reduction),
+            _ => {
+                let factor = BASIS_POINT_MAX.saturating_sub(reduction);
+                (0..period).fold(cliff, |fee, _| fee * factor / BASIS_POINT_MAX)
+            }
+        }
+    }
+
+    /// Переменная комиссия от накопленной волатильности (формула DLMM)
+    pub fn dynamic_fee_numerator(&self) -> u128 {
+        let dynamic = &self.pool_fees.dynamic_fee;
+        if dynamic.initialized == 0 || dynamic.variable_fee_control == 0 {
+            return 0;
+        }
+        dynamic
+            .volatility_accumulator
+            .checked_mul(dynamic.bin_step as u128)
+            .and_then(|volatility| volatility.checked_mul(volatility))
+            .and_then(|square| square.checked_mul(dynamic.variable_fee_control as u128))
+            .map(|fee| fee.div_ceil(100_000_000_000))
+            .unwrap_or(MAX_FEE_NUMERATOR)
+    }
+
+    pub fn total_fee_numerator(&self, current_point: u64) -> u128 {
+        (self.base_fee_numerator(current_point) + self.dynamic_fee_numerator()).min(MAX_FEE_NUMERATOR)
+    }
+
+    /// Комиссия берётся со входа только при сборе в токене B и входе B
+    fn fee_on_input(&self, a_to_b: bool) -> bool {
+        self.collect_fee_mode == 1 && !a_to_b
+    }
+}
+
+/// Котировка свапа с точным входом
+#[derive(Debug, Clone)]
+pub struct DammV2Quote {
+    pub amount_out: u64,
+    pub fee: u64,
+    /// Корень цены после свапа, Q64.64
+    pub next_sqrt_price: u128,
+    /// Ставка комиссии, точность `FEE_DENOMINATOR`
+    pub fee_numerator: u128,
+}
+
+/// Свап с точным входом по кривой концентрированной ликвидности пула.
+/// `a_to_b` — продажа A за B (цена идёт вниз); `current_point` — слот или время по `activation_type`.
+pub fn quote_exact_in(pool: &Pool, amount_in: u64, a_to_b: bool, current_point: u64) -> Result<DammV2Quote> {
+    if pool.liquidity == 0 {
+        bail!("в пуле нет ликвидности");
+    }
+
+    let fee_numerator = pool.total_fee_numerator(current_point);
+    let fee_on_input = pool.fee_on_input(a_to_b);
+
+    let (amount_in, input_fee) = if fee_on_input {
+        let fee = mul_div(amount_in as u128, fee_numerator, FEE_DENOMINATOR, true)
+            .ok_or_else(|| anyhow!("переполнение при расчёте комиссии"))?;
+        (amount_in as u128 - fee, fee)
+    } else {
+        (amount_in as u128, 0)
+    };
+
+    let (next_sqrt_price, amount_out) = if a_to_b {
+        let next = next_sqrt_price_from_a(pool.sqrt_price, pool.liquidity, amount_in)?;
+        if next < pool.sqrt_min_price {
+            bail!("свап выходит за нижнюю границу цены пула");
+        }
+        (next, amount_b_delta(pool.liquidity, next, pool.sqrt_price))
+    } else {
+        let next = next_sqrt_price_from_b(pool.sqrt_price, pool.liquidity, amount_in)?;
+        if next > pool.sqrt_max_price {
+            bail!("свап выходит за верхнюю границу цены пула");
+        }
+        (next, amount_a_delta(pool.liquidity, pool.sqrt_price, next)?)
+    };
+
+    let (amount_out, fee) = if fee_on_input {
+        (amount_out, input_fee)
+    } else {
+        let fee = mul_div(amount_out, fee_numerator, FEE_DENOMINATOR, true)
+            .ok_or_else(|| anyhow!("переполнение при расчёте комиссии"))?;
+        (amount_out - fee, fee)
+    };
+
+    Ok(DammV2Quote {
+        amount_out: u64::try_from(amount_out)?,
+        fee: u64::try_from(fee)?,
+        next_sqrt_price,
+        fee_numerator,
+    })
+}
+
+/// Вход A двигает цену вниз: `√P' = L·√P / (L + Δa·√P)`, с округлением вверх
+fn next_sqrt_price_from_a(sqrt_price: u128, liquidity: u128, amount: u128) -> Result<u128> {
+    let denominator = amount
+        .checked_mul(sqrt_price)
+        .and_then(|product| product.checked_add(liquidity))
+        .ok_or_else(|| anyhow!("переполнение при расчёте цены"))?;
+    mul_div(liquidity, sqrt_price, denominator, true).ok_or_else(|| anyhow!("переполнение при расчёте цены"))
+}
+
+/// Вход B двигает цену вверх: `√P' = √P + Δb / L`
+fn next_sqrt_price_from_b(sqrt_price: u128, liquidity: u128, amount: u128) -> Result<u128> {
+    let amount = u64::try_from(amount)? as u128;
+    let delta = mul_div(amount << 64, 1 << 64, liquidity, false)
+        .ok_or_else(|| anyhow!("переполнение при расчёте цены"))?;
+    sqrt_price.checked_add(delta).ok_or_else(|| anyhow!("переполнение при расчёте цены"))
+}
+
+/// Выход B: `L·(√P_upper − √P_lower) >> 128`
+fn amount_b_delta(liquidity: u128, lower: u128, upper: u128) -> u128 {
+    mul_full(liquidity, upper - lower).0
+}
+
+/// Выход A: `L·(√P_upper − √P_lower) / (√P_lower·√P_upper)`
+fn amount_a_delta(liquidity: u128, lower: u128, upper: u128) -> Result<u128> {
+    let scaled = mul_div(liquidity, upper - lower, upper, false)
+        .ok_or_else(|| anyhow!("переполнение при расчёте выхода"))?;
+    Ok(scaled / lower)
+}
+
+/// Полное 256-битное произведение: (старшие, младшие) 128 бит
+fn mul_full(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let middle = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let lo = (middle << 64) | (lo_lo & MASK);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (middle >> 64);
+    (hi, lo)
+}
+
+/// `a * b / denominator` с 256-битным промежуточным произведением; `None` при переполнении результата
+fn mul_div(a: u128, b: u128, denominator: u128, round_up: bool) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+    let (hi, lo) = mul_full(a, b);
+    if hi >= denominator {
+        return None;
+    }
+
+    // Деление столбиком: остаток всегда меньше делителя, поэтому частное помещается в u128
+    let mut quotient: u128 = 0;
+    let mut remainder = hi;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> bit) & 1);
+        if carry == 1 || remainder >= denominator {
+            remainder = remainder.wrapping_sub(denominator);
+            quotient |= 1 << bit;
+        }
+    }
+
+    if round_up && remainder > 0 {
+        quotient.checked_add(1)
+    } else {
+        Some(quotient)
+    }
+}
+
+/// PDA, владеющий хранилищами всех пулов программы
+pub fn pool_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"pool_authority"], &METEORA_DAMM_V2_PROGRAM_ID).0
+}
+
+/// Текущий момент в единицах `activation_type` пула. Слот берётся из потока Geyser (processed),
+/// как и у планировщика запусков; RPC — только пока поток не прислал ни одного слота.
+pub async fn current_point(rpc: &RpcClient, clock: &SlotClock, pool: &Pool) -> Result<u64> {
+    match pool.activation_type {
+        0 => match clock.current() {
+            0 => Ok(rpc.get_slot_with_commitment(CommitmentConfig::processed()).await?),
+            slot => Ok(slot),
+        },
+        _ => Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()),
+    }
+}
+
+pub async fn fetch_pool(rpc: &RpcClient, address: &Pubkey) -> Result<Pool> {
+    let account = rpc
+        .get_account(address)
+        .await
+        .with_context(|| format!("пул DAMM v2 {address} не найден"))?;
+    if account.owner != METEORA_DAMM_V2_PROGRAM_ID {
+        bail!("{address} не принадлежит программе DAMM v2");
+    }
+
+    let mut body = account
+        .data
+        .get(8..)
+        .ok_or_else(|| anyhow!("пул DAMM v2 {address} слишком короткий"))?;
+    Pool::deserialize(&mut body).map_err(|e| anyhow!("не удалось разобрать пул DAMM v2 {address}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: u128 = 1 << 64;
+    const ACTIVATION_POINT: u64 = 1_000;
+
+    /// Пул с ценой 1, ликвидностью 1e9 и комиссией 1% без планировщика
+    fn pool() -> Pool {
+        let mut pool = Pool::deserialize(&mut &[0u8; 2048][..]).unwrap();
+        pool.liquidity = 1_000_000_000 << 64;
+        pool.sqrt_price = ONE;
+        pool.sqrt_max_price = u128::MAX;
+        pool.activation_point = ACTIVATION_POINT;
+        pool.pool_fees.base_fee.cliff_fee_numerator = 10_000_000;
+        pool
+    }
+
+    /// Планировщик: 10% с шагом `reduction_factor` каждые 60 единиц, 10 периодов
+    fn scheduled_pool(fee_scheduler_mode: u8, reduction_factor: u64) -> Pool {
+        let mut pool = pool();
+        let base = &mut pool.pool_fees.base_fee;
+        base.cliff_fee_numerator = 100_000_000;
+        base.fee_scheduler_mode = fee_scheduler_mode;
+        base.number_of_period = 10;
+        base.period_frequency = 60;
+        base.reduction_factor = reduction_factor;
+        pool
+    }
+
+    #[test]
+    fn linear_fee_decay() {
+        let pool = scheduled_pool(0, 5_000_000);
+        let cases = [
+            // До активации — ставка последнего периода
+            (500, 50_000_000),
+            (1_000, 100_000_000),
+            (1_059, 100_000_000),
+            (1_060, 95_000_000),
+            (1_300, 75_000_000),
+            (100_000, 50_000_000),
+        ];
+        for (point, expected) in cases {
+            assert_eq!(pool.base_fee_numerator(point), expected, "момент {point}");
+        }
+    }
+
+    #[test]
+    fn exponential_fee_decay() {
+        let pool = scheduled_pool(1, 1_000);
+        let cases = [
+            (500, 34_867_843),
+            (1_000, 100_000_000),
+            (1_060, 90_000_000),
+            (1_120, 81_000_000),
+            (100_000, 34_867_843),
+        ];
+        for (point, expected) in cases {
+            assert_eq!(pool.base_fee_numerator(point), expected, "момент {point}");
+        }
+    }
+
+    #[test]
+    fn fee_without_scheduler_and_dynamic_fee() {
+        let mut pool = pool();
+        assert_eq!(pool.base_fee_numerator(0), 10_000_000);
+        assert_eq!(pool.dynamic_fee_numerator(), 0);
+
+        let dynamic = &mut pool.pool_fees.dynamic_fee;
+        dynamic.initialized = 1;
+        dynamic.variable_fee_control = 40_000;
+        dynamic.bin_step = 100;
+        dynamic.volatility_accumulator = 10_000;
+        assert_eq!(pool.dynamic_fee_numerator(), 400_000);
+        assert_eq!(pool.total_fee_numerator(ACTIVATION_POINT), 10_400_000);
+
+        // Сумма ограничена 50%
+        pool.pool_fees.base_fee.cliff_fee_numerator = 600_000_000;
+        assert_eq!(pool.total_fee_numerator(ACTIVATION_POINT), MAX_FEE_NUMERATOR);
+    }
+
+    #[test]
+    fn quote_exact_in_along_sqrt_price() {
+        // (collect_fee_mode, a_to_b) -> (выход, комиссия, корень цены после свапа)
+        let cases = [
+            // Продажа A: цена вниз, комиссия с выхода
+            (0, true, (989_010, 9_990, 18_428_315_757_951_600_016)),
+            // Продажа B: цена вверх, комиссия с выхода
+            (0, false, (989_010, 9_990, 18_465_190_817_783_261_167)),
+            // Сбор только в B: при входе B комиссия берётся со входа
+            (1, false, (989_020, 10_000, 18_465_006_350_342_524_072)),
+            (1, true, (989_010, 9_990, 18_428_315_757_951_600_016)),
+        ];
+        for (collect_fee_mode, a_to_b, (amount_out, fee, next_sqrt_price)) in cases {
+            let mut pool = pool();
+            pool.collect_fee_mode = collect_fee_mode;
+            let quote = quote_exact_in(&pool, 1_000_000, a_to_b, ACTIVATION_POINT).unwrap();
+            assert_eq!(
+                (quote.amount_out, quote.fee, quote.next_sqrt_price),
+                (amount_out, fee, next_sqrt_price),
+                "режим {collect_fee_mode}, a_to_b {a_to_b}"
+            );
+        }
+    }
+
+    #[test]
+    fn quote_exact_in_respects_price_range() {
+        let mut pool = pool();
+        pool.sqrt_min_price = ONE - 1;
+        assert!(quote_exact_in(&pool, 1_000_000, true, ACTIVATION_POINT).is_err());
+
+        pool.sqrt_max_price = ONE + 1;
+        assert!(quote_exact_in(&pool, 1_000_000, false, ACTIVATION_POINT).is_err());
+
+        pool.liquidity = 0;
+        assert!(quote_exact_in(&pool, 1_000_000, false, ACTIVATION_POINT).is_err());
+    }
+
+    #[test]
+    fn mul_div_rounding_and_overflow() {
+        assert_eq!(mul_div(10, 10, 3, false), Some(33));
+        assert_eq!(mul_div(10, 10, 3, true), Some(34));
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX, false), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 2, 1, false), None);
+        assert_eq!(mul_div(1, 1, 0, false), None);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::config::{Lamports, MicroLamports, PingThingsArgs};
+use crate::fees::compute::{ComputeProfiler, TxShape};
+use crate::fees::FeeEstimator;
+use crate::launch::clock::SlotClock;
+use crate::meteora::damm_v2::{current_point, fetch_pool, pool_authority, quote_exact_in};
+use crate::pools::reserves::ReserveCache;
+use crate::tx_senders::constants::WSOL_MINT;
+use crate::tx_senders::lookup_table::LookupTables;
+use crate::tx_senders::submission::SubmissionManager;
+use crate::tx_senders::transaction::{build_damm_v2_swap_transaction, DammV2SwapAccounts};
+
+use anyhow::{bail, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{address_lookup_table::AddressLookupTableAccount, hash::Hash, pubkey::Pubkey, signature::{Keypair, Signature}, transaction::VersionedTransaction};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Покупка за SOL в пуле DAMM v2: котировка по кривой пула и параметры compute budget
+pub struct DammV2Swap {
+    pub accounts: DammV2SwapAccounts,
+    pub input_mint: Pubkey,
+    pub amount_in: Lamports,
+    /// Котировка за вычетом `swap.slippage_bps`
+    pub min_amount_out: u64,
+    pub compute_unit_price: MicroLamports,
+    pub compute_unit_limit: u32,
+    pub lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl DammV2Swap {
+    pub async fn prepare(
+        args: &PingThingsArgs,
+        fees: &FeeEstimator,
+        compute: &ComputeProfiler,
+        lookup_tables: &LookupTables,
+        reserves: &ReserveCache,
+        clock: &SlotClock,
+        rpc: &RpcClient,
+        pool_address: &Pubkey,
+        amount_in: Lamports,
+    ) -> Result<Self> {
+        info!("Получение состояния пула DAMM v2...");
+        // Пул из кэша резервов, если он отслеживается, иначе по RPC
+        let pool = match reserves.damm_v2_pool(pool_address) {
+            Some(pool) => pool,
+            None => fetch_pool(rpc, pool_address).await?,
+        };
+        if !pool.is_enabled() {
+            bail!("пул DAMM v2 {pool_address} отключён");
+        }
+        if pool.token_a_flag != 0 || pool.token_b_flag != 0 {
+            bail!("пул DAMM v2 {pool_address} использует Token-2022, свап не поддерживается");
+        }
+
+        let point = current_point(rpc, clock, &pool).await?;
+        debug!("activation_point: {} (тип {}), сейчас: {}", pool.activation_point, pool.activation_type, point);
+        // Планировщик отправляет слотовые запуски за `launch.lead_slots` до активации
+        let lead = if pool.activation_type == 0 { args.launch.lead_slots } else { 0 };
+        if point.saturating_add(lead) < pool.activation_point {
+            bail!("пул DAMM v2 {pool_address} ещё не активирован");
+        }
+        // Транзакция исполнится не раньше активации: до неё котировка занизила бы комиссию
+        let point = point.max(pool.activation_point);
+
+        // a_to_b — продажа A за B; SOL может быть любой стороной пула
+        let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+        let a_to_b = if pool.token_a_mint == wsol {
+            true
+        } else if pool.token_b_mint == wsol {
+            false
+        } else {
+            bail!("в пуле DAMM v2 {pool_address} нет WSOL");
+        };
+
+        let quote = quote_exact_in(&pool, amount_in.0, a_to_b, point)?;
+        let slippage = args.swap.slippage_bps as u128;
+        let min_amount_out = (quote.amount_out as u128 * (10_000 - slippage) / 10_000) as u64;
+        info!(
+            "Котировка DAMM v2: {} -> {} токенов (комиссия {}, ставка {}/1e9), минимум {}",
+            amount_in, quote.amount_out, quote.fee, quote.fee_numerator, min_amount_out
+        );
+        debug!("sqrt_price: {} -> {}", pool.sqrt_price, quote.next_sqrt_price);
+
+        let writable_accounts = [*pool_address, pool.token_a_vault, pool.token_b_vault];
+        let compute_unit_price = fees.estimate(rpc, args, &writable_accounts).await;
+        let compute_unit_limit = compute.limit_for(&TxShape::MeteoraDammV2Swap, args);
+
+        Ok(Self {
+            accounts: DammV2SwapAccounts {
+                pool: *pool_address,
+                pool_authority: pool_authority(),
+                token_a_vault: pool.token_a_vault,
+                token_b_vault: pool.token_b_vault,
+                token_a_mint: pool.token_a_mint,
+                token_b_mint: pool.token_b_mint,
+            },
+            input_mint: wsol,
+            amount_in,
+            min_amount_out,
+            compute_unit_price,
+            compute_unit_limit,
+            lookup_tables: lookup_tables.tables().to_vec(),
+        })
+    }
+
+    pub fn build(
+        &self,
+        user: &Keypair,
+        recent_blockhash: Hash,
+        compute_unit_price: MicroLamports,
+        nonce_account: Option<Pubkey>,
+    ) -> Result<VersionedTransaction> {
+        build_damm_v2_swap_transaction(
+            user,
+            &self.accounts,
+            &self.input_mint,
+            self.amount_in.0,
+            self.min_amount_out,
+            compute_unit_price,
+            self.compute_unit_limit,
+            recent_blockhash,
+            nonce_account,
+            &self.lookup_tables,
+        )
+    }
+}
+
+pub async fn fetch_and_execute_damm_v2_swap(
+    args: &PingThingsArgs,
+    fees: &FeeEstimator,
+    compute: &Arc<ComputeProfiler>,
+    lookup_tables: &LookupTables,
+    reserves: &ReserveCache,
+    clock: &SlotClock,
+    submissions: &SubmissionManager,
+    user: &Keypair,
+    nonce_account: Option<Pubkey>,
+    pool: &Pubkey,
+    amount_in: Lamports,
+) -> Result<Signature> {
+    info!("Запуск свапа через Meteora DAMM v2, пул: {pool}");
+
+    let rpc = RpcClient::new(args.http_rpc.clone());
+    let swap = DammV2Swap::prepare(args, fees, compute, lookup_tables, reserves, clock, &rpc, pool, amount_in).await?;
+
+    info!("Отправка транзакции...");
+    let result = submissions
+        .submit(args, *pool, swap.compute_unit_price, nonce_account, |blockhash, price| {
+            let tx = swap.build(user, blockhash, price, nonce_account)?;
+            compute.profile_in_background(args, TxShape::MeteoraDammV2Swap, &tx);
+            Ok(tx)
+        })
+        .await;
+
+    match result {
+        Ok(sig) => {
+            info!("Транзакция DAMM v2 успешно отправлена! Signature: {}", sig);
+            Ok(sig)
+        }
+        Err(e) => {
+            warn!("Ошибка отправки транзакции DAMM v2: {:?}", e);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,479 @@
+use crate::tx_senders::constants::METEORA_DLMM_PROGRAM_ID;
+
+use anyhow::{anyhow, bail, Context, Result};
+use borsh::BorshDeserialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+/// Бинов в одном аккаунте BinArray
+pub const MAX_BIN_PER_ARRAY: i64 = 70;
+/// Сколько bin arrays в сторону свапа загружается для котировки и передаётся в инструкцию
+pub const SWAP_BIN_ARRAYS: i64 = 3;
+
+const SCALE_OFFSET: u32 = 64;
+const ONE: u128 = 1 << SCALE_OFFSET;
+const BASIS_POINT_MAX: u128 = 10_000;
+/// Точность ставок комиссии (1e9 = 100%)
+const FEE_PRECISION: u128 = 1_000_000_000;
+const MAX_FEE_RATE: u128 = 100_000_000;
+/// Показатель степени, начиная с которого цена бина не представима в Q64.64
+const MAX_EXPONENTIAL: u32 = 0x80000;
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct StaticParameters {
+    pub base_factor: u16,
+    _filter_period: u16,
+    _decay_period: u16,
+    _reduction_factor: u16,
+    pub variable_fee_control: u32,
+    _max_volatility_accumulator: u32,
+    pub min_bin_id: i32,
+    pub max_bin_id: i32,
+    _protocol_share: u16,
+    pub base_fee_power_factor: u8,
+    _padding: [u8; 5],
+}
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct VariableParameters {
+    pub volatility_accumulator: u32,
+    _volatility_reference: u32,
+    _index_reference: i32,
+    _padding: [u8; 4],
+    _last_update_timestamp: i64,
+    _padding1: [u8; 8],
+}
+
+/// Аккаунт пары DLMM (без дискриминатора); поля после `activation_point` не нужны
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct LbPair {
+    pub parameters: StaticParameters,
+    pub v_parameters: VariableParameters,
+    _bump_seed: [u8; 1],
+    _bin_step_seed: [u8; 2],
+    _pair_type: u8,
+    /// Бин, в котором сейчас находится цена
+    pub active_id: i32,
+    pub bin_step: u16,
+    /// 0 — торговля включена
+    pub status: u8,
+    _require_base_factor_seed: u8,
+    _base_factor_seed: [u8; 2],
+    /// 0 — activation_point в слотах, 1 — в секундах
+    pub activation_type: u8,
+    _creator_pool_on_off_control: u8,
+    pub token_x_mint: Pubkey,
+    pub token_y_mint: Pubkey,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+    _protocol_fee: [u64; 2],
+    _padding1: [u8; 32],
+    _reward_infos: [u8; 288],
+    pub oracle: Pubkey,
+    _bin_array_bitmap: [u64; 16],
+    _last_updated_at: i64,
+    _padding2: [u8; 32],
+    /// Адрес, которому разрешены свапы до активации (обычно alpha vault)
+    pub pre_activation_swap_address: Pubkey,
+    _base_key: Pubkey,
+    /// С этого момента пара открыта для свапов
+    pub activation_point: u64,
+}
+
+impl LbPair {
+    pub fn is_enabled(&self) -> bool {
+        self.status == 0
+    }
+
+    /// Базовая комиссия, точность `FEE_PRECISION`
+    pub fn base_fee_rate(&self) -> u128 {
+        self.parameters.base_factor as u128
+            * self.bin_step as u128
+            * 10
+            * 10u128.pow(self.parameters.base_fee_power_factor as u32)
+    }
+
+    /// Переменная комиссия от накопленной волатильности
+    pub fn variable_fee_rate(&self) -> u128 {
+        if self.parameters.variable_fee_control == 0 {
+            return 0;
+        }
+        let volatility = self.v_parameters.volatility_accumulator as u128 * self.bin_step as u128;
+        let fee = volatility * volatility * self.parameters.variable_fee_control as u128;
+        (fee + 99_999_999_999) / 100_000_000_000
+    }
+
+    pub fn total_fee_rate(&self) -> u128 {
+        (self.base_fee_rate() + self.variable_fee_rate()).min(MAX_FEE_RATE)
+    }
+}
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct Bin {
+    pub amount_x: u64,
+    pub amount_y: u64,
+    /// Цена Y за X в Q64.64; у ещё не инициализированных бинов равна нулю
+    pub price: u128,
+    _liquidity_supply: u128,
+    _reward_per_token_stored: [u128; 2],
+    _fee_amount_x_per_token_stored: u128,
+    _fee_amount_y_per_token_stored: u128,
+    _amount_x_in: u128,
+    _amount_y_in: u128,
+}
+
+#[derive(Debug, Clone, BorshDeserialize)]
+struct BinArrayHeader {
+    index: i64,
+    _version: u8,
+    _padding: [u8; 7],
+    lb_pair: Pubkey,
+}
+
+/// Аккаунт BinArray: 70 подряд идущих бинов начиная с `index * 70`
+#[derive(Debug, Clone)]
+pub struct BinArray {
+    pub address: Pubkey,
+    pub index: i64,
+    pub lb_pair: Pubkey,
+    pub bins: Vec<Bin>,
+}
+
+impl BinArray {
+    pub fn decode(address: Pubkey, data: &[u8]) -> Result<Self> {
+        let mut body = data
+            .get(8..)
+            .ok_or_else(|| anyhow!("bin array {address} слишком короткий"))?;
+        let header = BinArrayHeader::deserialize(&mut body)
+            .map_err(|e| anyhow!("не удалось разобрать bin array {address}: {e}"))?;
+        let bins = (0..MAX_BIN_PER_ARRAY)
+            .map(|_| Bin::deserialize(&mut body))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow!("не удалось разобрать бины {address}: {e}"))?;
+
+        Ok(Self {
+            address,
+            index: header.index,
+            lb_pair: header.lb_pair,
+            bins,
+        })
+    }
+
+    fn bin(&self, bin_id: i32) -> Option<&Bin> {
+        let offset = bin_id as i64 - self.index * MAX_BIN_PER_ARRAY;
+        self.bins.get(usize::try_from(offset).ok()?)
+    }
+}
+
+/// Индекс bin array, содержащего бин (округление вниз и для отрицательных id)
+pub fn bin_array_index(bin_id: i32) -> i64 {
+    (bin_id as i64).div_euclid(MAX_BIN_PER_ARRAY)
+}
+
+pub fn bin_array_address(lb_pair: &Pubkey, index: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"bin_array", lb_pair.as_ref(), &index.to_le_bytes()],
+        &METEORA_DLMM_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Цена бина `(1 + bin_step / 10000) ^ bin_id` в Q64.64, как её считает программа
+pub fn bin_price(bin_id: i32, bin_step: u16) -> Option<u128> {
+    let base = ONE + ((bin_step as u128) << SCALE_OFFSET) / BASIS_POINT_MAX;
+    pow(base, bin_id)
+}
+
+fn pow(base: u128, exp: i32) -> Option<u128> {
+    if exp == 0 {
+        return Some(ONE);
+    }
+
+    let mut invert = exp.is_negative();
+    let exp = exp.unsigned_abs();
+    if exp >= MAX_EXPONENTIAL {
+        return None;
+    }
+
+    // Возведение в степень основания меньше единицы, чтобы произведения помещались в u128
+    let mut squared_base = base;
+    if squared_base >= ONE {
+        squared_base = u128::MAX / squared_base;
+        invert = !invert;
+    }
+
+    let mut result = ONE;
+    for bit in 0..MAX_EXPONENTIAL.trailing_zeros() {
+        if exp & (1 << bit) != 0 {
+            result = (result * squared_base) >> SCALE_OFFSET;
+        }
+        squared_base = (squared_base * squared_base) >> SCALE_OFFSET;
+    }
+
+    if result == 0 {
+        return None;
+    }
+    Some(if invert { u128::MAX / result } else { result })
+}
+
+/// `amount * price >> 64` без переполнения u128
+fn mul_shr(amount: u64, price: u128) -> u128 {
+    let amount = amount as u128;
+    amount * (price >> SCALE_OFFSET) + ((amount * (price & (ONE - 1))) >> SCALE_OFFSET)
+}
+
+/// `ceil((amount << 64) / price)`
+fn shl_div_ceil(amount: u64, price: u128) -> u128 {
+    ((amount as u128) << SCALE_OFFSET).div_ceil(price)
+}
+
+/// Комиссия сверху суммы: `amount` доходит до бина целиком
+fn fee_on_top(amount: u128, fee_rate: u128) -> u128 {
+    (amount * fee_rate).div_ceil(FEE_PRECISION - fee_rate)
+}
+
+/// Комиссия, уже включённая в `amount_with_fees`
+fn fee_included(amount_with_fees: u128, fee_rate: u128) -> u128 {
+    (amount_with_fees * fee_rate).div_ceil(FEE_PRECISION)
+}
+
+/// Котировка свапа с точным входом
+#[derive(Debug, Clone)]
+pub struct DlmmQuote {
+    pub amount_out: u64,
+    pub fee: u64,
+    /// Бины, через которые прошёл свап
+    pub bins_crossed: u32,
+    /// Bin arrays, затронутые свапом, в порядке прохода
+    pub bin_arrays: Vec<Pubkey>,
+}
+
+/// Проход свапа по бинам от активного; `swap_for_y` — продажа X за Y (цена идёт вниз).
+/// Волатильность внутри свапа не пересчитывается, поэтому комиссия на дальних бинах занижена.
+pub fn quote_exact_in(
+    pair: &LbPair,
+    bin_arrays: &[BinArray],
+    amount_in: u64,
+    swap_for_y: bool,
+) -> Result<DlmmQuote> {
+    let fee_rate = pair.total_fee_rate();
+    let mut remaining = amount_in as u128;
+    let mut amount_out: u128 = 0;
+    let mut total_fee: u128 = 0;
+    let mut bins_crossed = 0;
+    let mut used_arrays: Vec<Pubkey> = Vec::new();
+    let mut bin_id = pair.active_id;
+
+    while remaining > 0 {
+        if bin_id < pair.parameters.min_bin_id || bin_id > pair.parameters.max_bin_id {
+            bail!("свап вышел за границы бинов пары");
+        }
+        let Some(array) = bin_arrays.iter().find(|array| array.index == bin_array_index(bin_id)) else {
+            bail!("недостаточно ликвидности в загруженных bin arrays (бин {bin_id})");
+        };
+        let bin = array.bin(bin_id).ok_or_else(|| anyhow!("бин {bin_id} вне bin array"))?;
+
+        let max_out = if swap_for_y { bin.amount_y } else { bin.amount_x };
+        if max_out > 0 {
+            let price = match bin.price {
+                0 => bin_price(bin_id, pair.bin_step).ok_or_else(|| anyhow!("цена бина {bin_id} не представима"))?,
+                price => price,
+            };
+            if !used_arrays.contains(&array.address) {
+                used_arrays.push(array.address);
+            }
+
+            // Вход, нужный для выкупа всего бина: X за Y по цене, Y за X по обратной
+            let max_in = if swap_for_y {
+                shl_div_ceil(max_out, price)
+            } else {
+                mul_shr(max_out, price) + 1
+            };
+            let max_fee = fee_on_top(max_in, fee_rate);
+
+            if remaining >= max_in + max_fee {
+                remaining -= max_in + max_fee;
+                amount_out += max_out as u128;
+                total_fee += max_fee;
+            } else {
+                let fee = fee_included(remaining, fee_rate);
+                let amount_in_bin = u64::try_from(remaining - fee)?;
+                let out = if swap_for_y {
+                    mul_shr(amount_in_bin, price)
+                } else {
+                    ((amount_in_bin as u128) << SCALE_OFFSET) / price
+                };
+                amount_out += out.min(max_out as u128);
+                total_fee += fee;
+                remaining = 0;
+            }
+            bins_crossed += 1;
+        }
+
+        bin_id = if swap_for_y { bin_id - 1 } else { bin_id + 1 };
+    }
+
+    Ok(DlmmQuote {
+        amount_out: u64::try_from(amount_out)?,
+        fee: u64::try_from(total_fee)?,
+        bins_crossed,
+        bin_arrays: used_arrays,
+    })
+}
+
+pub async fn fetch_lb_pair(rpc: &RpcClient, address: &Pubkey) -> Result<LbPair> {
+    let account = rpc
+        .get_account(address)
+        .await
+        .with_context(|| format!("пара DLMM {address} не найдена"))?;
+    if account.owner != METEORA_DLMM_PROGRAM_ID {
+        bail!("{address} не принадлежит программе DLMM");
+    }
+
+    let mut body = account
+        .data
+        .get(8..)
+        .ok_or_else(|| anyhow!("пара DLMM {address} слишком короткая"))?;
+    LbPair::deserialize(&mut body).map_err(|e| anyhow!("не удалось разобрать пару DLMM {address}: {e}"))
+}
+
+/// Активный bin array и следующие за ним в сторону свапа; несозданные пропускаются
+pub async fn fetch_swap_bin_arrays(
+    rpc: &RpcClient,
+    lb_pair: &Pubkey,
+    pair: &LbPair,
+    swap_for_y: bool,
+) -> Result<Vec<BinArray>> {
+    let active = bin_array_index(pair.active_id);
+    let step = if swap_for_y { -1 } else { 1 };
+    let addresses: Vec<Pubkey> = (0..SWAP_BIN_ARRAYS)
+        .map(|offset| bin_array_address(lb_pair, active + offset * step))
+        .collect();
+
+    let accounts = rpc.get_multiple_accounts(&addresses).await?;
+    addresses
+        .into_iter()
+        .zip(accounts)
+        .filter_map(|(address, account)| account.map(|account| (address, account)))
+        .map(|(address, account)| {
+            let array = BinArray::decode(address, &account.data)?;
+            if array.lb_pair != *lb_pair {
+                bail!("bin array {address} принадлежит другой паре");
+            }
+            Ok(array)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Аккаунт из нулевых байт: в тестах задаются только нужные поля
+    fn zeroed<T: BorshDeserialize>() -> T {
+        T::deserialize(&mut &[0u8; 2048][..]).unwrap()
+    }
+
+    /// Пара с шагом 100 б.п. и базовой комиссией 1%
+    fn pair() -> LbPair {
+        let mut pair: LbPair = zeroed();
+        pair.bin_step = 100;
+        pair.parameters.base_factor = 10_000;
+        pair.parameters.min_bin_id = -1_000;
+        pair.parameters.max_bin_id = 1_000;
+        pair
+    }
+
+    /// Bin arrays с заданными бинами: (id, amount_x, amount_y, цена; 0 — по формуле)
+    fn bin_arrays(bins: &[(i32, u64, u64, u128)]) -> Vec<BinArray> {
+        let mut arrays: Vec<BinArray> = Vec::new();
+        for &(bin_id, amount_x, amount_y, price) in bins {
+            let index = bin_array_index(bin_id);
+            if !arrays.iter().any(|array| array.index == index) {
+                arrays.push(BinArray {
+                    address: Pubkey::new_unique(),
+                    index,
+                    lb_pair: Pubkey::default(),
+                    bins: (0..MAX_BIN_PER_ARRAY).map(|_| zeroed()).collect(),
+                });
+            }
+            let array = arrays.iter_mut().find(|array| array.index == index).unwrap();
+            let bin = &mut array.bins[(bin_id as i64 - index * MAX_BIN_PER_ARRAY) as usize];
+            bin.amount_x = amount_x;
+            bin.amount_y = amount_y;
+            bin.price = price;
+        }
+        arrays
+    }
+
+    #[test]
+    fn bin_price_matches_program() {
+        let cases: &[(i32, u16, Option<u128>)] = &[
+            (0, 10, Some(ONE)),
+            (1, 100, Some(18_631_211_514_446_647_132)),
+            (-1, 100, Some(18_264_103_043_276_783_778)),
+            (100, 25, Some(23_678_699_809_202_413_098)),
+            (5_000, 10, Some(2_730_908_310_638_343_582_694)),
+            (-5_000, 10, Some(124_604_098_056_077_993)),
+            (MAX_EXPONENTIAL as i32, 1, None),
+        ];
+        for &(bin_id, bin_step, expected) in cases {
+            assert_eq!(bin_price(bin_id, bin_step), expected, "бин {bin_id}, шаг {bin_step}");
+        }
+    }
+
+    #[test]
+    fn bin_array_index_rounds_down() {
+        assert_eq!(bin_array_index(0), 0);
+        assert_eq!(bin_array_index(69), 0);
+        assert_eq!(bin_array_index(70), 1);
+        assert_eq!(bin_array_index(-1), -1);
+        assert_eq!(bin_array_index(-70), -1);
+        assert_eq!(bin_array_index(-71), -2);
+    }
+
+    #[test]
+    fn fee_rates() {
+        let mut pair = pair();
+        assert_eq!(pair.base_fee_rate(), 10_000_000);
+        assert_eq!(pair.variable_fee_rate(), 0);
+
+        pair.parameters.variable_fee_control = 40_000;
+        pair.v_parameters.volatility_accumulator = 10_000;
+        assert_eq!(pair.variable_fee_rate(), 400_000);
+        assert_eq!(pair.total_fee_rate(), 10_400_000);
+
+        // Сумма ограничена MAX_FEE_RATE (10%)
+        pair.parameters.base_fee_power_factor = 1;
+        assert_eq!(pair.total_fee_rate(), MAX_FEE_RATE);
+    }
+
+    #[test]
+    fn quote_exact_in_across_bins() {
+        // (бины, вход, swap_for_y) -> (выход, комиссия, пройдено бинов)
+        let cases: &[(&[(i32, u64, u64, u128)], u64, bool, (u64, u64, u32))] = &[
+            // Весь вход в активном бине
+            (&[(0, 0, 1_000_000, ONE)], 100_000, true, (99_000, 1_000, 1)),
+            // Активный бин выкупается целиком с комиссией сверху, остаток — в следующем ниже
+            (&[(0, 0, 50_000, ONE), (-1, 0, 1_000_000, 0)], 100_000, true, (98_513, 1_001, 2)),
+            // Покупка X за Y: цена идёт вверх
+            (&[(0, 50_000, 0, ONE), (1, 1_000_000, 0, 0)], 100_000, false, (98_512, 1_001, 2)),
+            // Пустой бин пропускается и не считается пройденным
+            (&[(0, 0, 0, ONE), (-1, 0, 1_000_000, 0)], 100_000, true, (98_019, 1_000, 1)),
+        ];
+        for (index, &(bins, amount_in, swap_for_y, (amount_out, fee, bins_crossed))) in cases.iter().enumerate() {
+            let quote = quote_exact_in(&pair(), &bin_arrays(bins), amount_in, swap_for_y).unwrap();
+            assert_eq!(
+                (quote.amount_out, quote.fee, quote.bins_crossed),
+                (amount_out, fee, bins_crossed),
+                "случай {index}"
+            );
+        }
+    }
+
+    #[test]
+    fn quote_exact_in_fails_without_liquidity() {
+        let arrays = bin_arrays(&[(0, 0, 1_000, ONE)]);
+        assert!(quote_exact_in(&pair(), &arrays, 100_000, true).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::config::{Lamports, MicroLamports, PingThingsArgs};
+use crate::fees::compute::{ComputeProfiler, TxShape};
+use crate::fees::FeeEstimator;
+use crate::meteora::dlmm::{fetch_lb_pair, fetch_swap_bin_arrays, quote_exact_in};
+use crate::pools::reserves::ReserveCache;
+use crate::tx_senders::constants::{TOKEN_PROGRAM_ADDR, WSOL_MINT};
+use crate::tx_senders::lookup_table::LookupTables;
+use crate::tx_senders::submission::SubmissionManager;
+use crate::tx_senders::transaction::{build_dlmm_swap_transaction, DlmmSwapAccounts};
+
+use anyhow::{bail, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{address_lookup_table::AddressLookupTableAccount, hash::Hash, pubkey::Pubkey, signature::{Keypair, Signature}, transaction::VersionedTransaction};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Покупка за SOL в паре DLMM: котировка по бинам и параметры compute budget
+pub struct DlmmSwap {
+    pub accounts: DlmmSwapAccounts,
+    pub input_mint: Pubkey,
+    pub amount_in: Lamports,
+    /// Котировка за вычетом `swap.slippage_bps`
+    pub min_amount_out: u64,
+    pub shape: TxShape,
+    pub compute_unit_price: MicroLamports,
+    pub compute_unit_limit: u32,
+    pub lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl DlmmSwap {
+    pub async fn prepare(
+        args: &PingThingsArgs,
+        fees: &FeeEstimator,
+        compute: &ComputeProfiler,
+        lookup_tables: &LookupTables,
+        reserves: &ReserveCache,
+        rpc: &RpcClient,
+        lb_pair: &Pubkey,
+        amount_in: Lamports,
+    ) -> Result<Self> {
+        info!("Получение состояния пары DLMM...");
+        // Пара из кэша резервов, если она отслеживается; bin arrays всегда читаются по RPC
+        let pair = match reserves.lb_pair(lb_pair) {
+            Some(pair) => pair,
+            None => fetch_lb_pair(rpc, lb_pair).await?,
+        };
+        if !pair.is_enabled() {
+            bail!("пара DLMM {lb_pair} отключена");
+        }
+        debug!("Активный бин: {}, bin_step: {}, activation_point: {} (тип {})",
+            pair.active_id, pair.bin_step, pair.activation_point, pair.activation_type);
+
+        // swap_for_y — продажа X за Y; SOL может быть любой стороной пары
+        let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+        let swap_for_y = if pair.token_x_mint == wsol {
+            true
+        } else if pair.token_y_mint == wsol {
+            false
+        } else {
+            bail!("в паре DLMM {lb_pair} нет WSOL");
+        };
+
+        // Инструкция собирается под SPL Token: mint'ы Token-2022 не поддерживаются
+        let token_program_id = Pubkey::from_str(TOKEN_PROGRAM_ADDR).unwrap();
+        let mints = rpc.get_multiple_accounts(&[pair.token_x_mint, pair.token_y_mint]).await?;
+        if !mints.iter().all(|mint| mint.as_ref().is_some_and(|mint| mint.owner == token_program_id)) {
+            bail!("пара DLMM {lb_pair} использует Token-2022, свап не поддерживается");
+        }
+
+        let bin_arrays = fetch_swap_bin_arrays(rpc, lb_pair, &pair, swap_for_y).await?;
+        let quote = quote_exact_in(&pair, &bin_arrays, amount_in.0, swap_for_y)?;
+        let slippage = args.swap.slippage_bps as u128;
+        let min_amount_out = (quote.amount_out as u128 * (10_000 - slippage) / 10_000) as u64;
+        info!(
+            "Котировка DLMM: {} -> {} токенов (комиссия {}, бинов {}), минимум {}",
+            amount_in, quote.amount_out, quote.fee, quote.bins_crossed, min_amount_out
+        );
+
+        let writable_accounts = [*lb_pair, pair.reserve_x, pair.reserve_y, pair.oracle];
+        let compute_unit_price = fees.estimate(rpc, args, &writable_accounts).await;
+
+        // Передаются все загруженные bin arrays: цена может сдвинуться до исполнения
+        let shape = TxShape::MeteoraDlmmSwap {
+            bin_arrays: bin_arrays.len(),
+        };
+        let compute_unit_limit = compute.limit_for(&shape, args);
+
+        Ok(Self {
+            accounts: DlmmSwapAccounts {
+                lb_pair: *lb_pair,
+                reserve_x: pair.reserve_x,
+                reserve_y: pair.reserve_y,
+                token_x_mint: pair.token_x_mint,
+                token_y_mint: pair.token_y_mint,
+                oracle: pair.oracle,
+                bin_arrays: bin_arrays.iter().map(|array| array.address).collect(),
+            },
+            input_mint: wsol,
+            amount_in,
+            min_amount_out,
+            shape,
+            compute_unit_price,
+            compute_unit_limit,
+            lookup_tables: lookup_tables.tables().to_vec(),
+        })
+    }
+
+    pub fn build(
+        &self,
+        user: &Keypair,
+        recent_blockhash: Hash,
+        compute_unit_price: MicroLamports,
+        nonce_account: Option<Pubkey>,
+    ) -> Result<VersionedTransaction> {
+        build_dlmm_swap_transaction(
+            user,
+            &self.accounts,
+            &self.input_mint,
+            self.amount_in.0,
+            self.min_amount_out,
+            compute_unit_price,
+            self.compute_unit_limit,
+            recent_blockhash,
+            nonce_account,
+            &self.lookup_tables,
+        )
+    }
+}
+
+pub async fn fetch_and_execute_dlmm_swap(
+    args: &PingThingsArgs,
+    fees: &FeeEstimator,
+    compute: &Arc<ComputeProfiler>,
+    lookup_tables: &LookupTables,
+    reserves: &ReserveCache,
+    submissions: &SubmissionManager,
+    user: &Keypair,
+    nonce_account: Option<Pubkey>,
+    lb_pair: &Pubkey,
+    amount_in: Lamports,
+) -> Result<Signature> {
+    info!("Запуск свапа через Meteora DLMM, пара: {lb_pair}");
+
+    let rpc = RpcClient::new(args.http_rpc.clone());
+    let swap = DlmmSwap::prepare(args, fees, compute, lookup_tables, reserves, &rpc, lb_pair, amount_in).await?;
+
+    info!("Отправка транзакции...");
+    let result = submissions
+        .submit(args, *lb_pair, swap.compute_unit_price, nonce_account, |blockhash, price| {
+            let tx = swap.build(user, blockhash, price, nonce_account)?;
+            compute.profile_in_background(args, swap.shape, &tx);
+            Ok(tx)
+        })
+        .await;
+
+    match result {
+        Ok(sig) => {
+            info!("Транзакция DLMM успешно отправлена! Signature: {}", sig);
+            Ok(sig)
+        }
+        Err(e) => {
+            warn!("Ошибка отправки транзакции DLMM: {:?}", e);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::core::logs::{anchor_discriminator, program_data, split_event};
+use crate::tx_senders::constants::METEORA_PROGRAM_ID;
+
+use borsh::BorshDeserialize;
+use lazy_static::lazy_static;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct PoolCreated {
+    pub lp_mint: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    /// 0 — permissioned, 1 — permissionless
+    pub pool_type: u8,
+    pub pool: Pubkey,
+}
+
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct Swap {
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub trade_fee: u64,
+    pub protocol_fee: u64,
+    pub host_fee: u64,
+}
+
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct AddLiquidity {
+    pub lp_mint_amount: u64,
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+}
+
+/// События Anchor программы Meteora AMM из логов транзакции.
+/// В Swap и AddLiquidity нет адреса пула: он берётся из инструкции, выполненной
+/// в том же порядке, а событие даёт фактические суммы.
+#[derive(Debug, Clone)]
+pub enum MeteoraEvent {
+    PoolCreated(PoolCreated),
+    Swap(Swap),
+    AddLiquidity(AddLiquidity),
+}
+
+lazy_static! {
+    static ref POOL_CREATED: [u8; 8] = anchor_discriminator("event", "PoolCreated");
+    static ref SWAP: [u8; 8] = anchor_discriminator("event", "Swap");
+    static ref ADD_LIQUIDITY: [u8; 8] = anchor_discriminator("event", "AddLiquidity");
+}
+
+/// Разбор событий Meteora; прочие программы и неизвестные события пропускаются
+pub fn parse_events(logs: &[String]) -> Vec<MeteoraEvent> {
+    program_data(logs)
+        .into_iter()
+        .filter(|entry| entry.program_id == METEORA_PROGRAM_ID)
+        .filter_map(|entry| decode_event(&entry.data))
+        .collect()
+}
+
+fn decode_event(data: &[u8]) -> Option<MeteoraEvent> {
+    let (discriminator, mut body) = split_event(data)?;
+
+    // deserialize, а не try_from_slice: новые версии программы могут дописывать поля в конец
+    if discriminator == *POOL_CREATED {
+        PoolCreated::deserialize(&mut body).ok().map(MeteoraEvent::PoolCreated)
+    } else if discriminator == *SWAP {
+        Swap::deserialize(&mut body).ok().map(MeteoraEvent::Swap)
+    } else if discriminator == *ADD_LIQUIDITY {
+        AddLiquidity::deserialize(&mut body).ok().map(MeteoraEvent::AddLiquidity)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+
+    fn event(discriminator: &[u8; 8], fields: &[u64]) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        for field in fields {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data
+    }
+
+    fn logs(program_id: &Pubkey, data: &[u8]) -> Vec<String> {
+        vec![
+            format!("Program {program_id} invoke [1]"),
+            format!("Program data: {}", base64::engine::general_purpose::STANDARD.encode(data)),
+            format!("Program {program_id} success"),
+        ]
+    }
+
+    #[test]
+    fn decodes_swap() {
+        let data = event(&SWAP, &[1_000, 2_000, 3, 2, 1]);
+
+        let Some(MeteoraEvent::Swap(swap)) = decode_event(&data) else {
+            panic!("ожидалось событие Swap");
+        };
+        assert_eq!(swap.in_amount, 1_000);
+        assert_eq!(swap.out_amount, 2_000);
+        assert_eq!(swap.trade_fee, 3);
+        assert_eq!(swap.protocol_fee, 2);
+        assert_eq!(swap.host_fee, 1);
+    }
+
+    #[test]
+    fn decodes_add_liquidity() {
+        let data = event(&ADD_LIQUIDITY, &[500, 1_000, 2_000]);
+
+        let Some(MeteoraEvent::AddLiquidity(added)) = decode_event(&data) else {
+            panic!("ожидалось событие AddLiquidity");
+        };
+        assert_eq!(added.lp_mint_amount, 500);
+        assert_eq!(added.token_a_amount, 1_000);
+        assert_eq!(added.token_b_amount, 2_000);
+    }
+
+    #[test]
+    fn decodes_pool_created() {
+        let (lp_mint, token_a, token_b, pool) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = POOL_CREATED.to_vec();
+        data.extend_from_slice(lp_mint.as_ref());
+        data.extend_from_slice(token_a.as_ref());
+        data.extend_from_slice(token_b.as_ref());
+        data.push(1);
+        data.extend_from_slice(pool.as_ref());
+
+        let Some(MeteoraEvent::PoolCreated(created)) = decode_event(&data) else {
+            panic!("ожидалось событие PoolCreated");
+        };
+        assert_eq!(created.lp_mint, lp_mint);
+        assert_eq!(created.token_a_mint, token_a);
+        assert_eq!(created.token_b_mint, token_b);
+        assert_eq!(created.pool_type, 1);
+        assert_eq!(created.pool, pool);
+    }
+
+    #[test]
+    fn skips_unknown_and_truncated_events() {
+        let cases: [(&str, Vec<u8>); 3] = [
+            ("неизвестный дискриминатор", event(&anchor_discriminator("event", "RemoveLiquidity"), &[1, 2, 3])),
+            ("обрезанное тело", event(&SWAP, &[1, 2])),
+            ("нет дискриминатора", vec![1, 2, 3]),
+        ];
+
+        for (name, data) in cases {
+            assert!(decode_event(&data).is_none(), "{name}");
+        }
+    }
+
+    #[test]
+    fn appended_fields_are_ignored() {
+        let data = event(&ADD_LIQUIDITY, &[500, 1_000, 2_000, 42]);
+        assert!(matches!(decode_event(&data), Some(MeteoraEvent::AddLiquidity(_))));
+    }
+
+    #[test]
+    fn parses_only_meteora_program() {
+        let data = event(&SWAP, &[1_000, 2_000, 3, 2, 1]);
+
+        assert_eq!(parse_events(&logs(&METEORA_PROGRAM_ID, &data)).len(), 1);
+        assert!(parse_events(&logs(&Pubkey::new_unique(), &data)).is_empty());
+    }
+}
@@ -1,77 +1,247 @@
-use crate::meteora::fetch_pool::fetch_pool_accounts;
-use crate::config::PingThingsArgs;
+use crate::meteora::fetch_pool::{fetch_pool_accounts, fetch_pool_reserves, fetch_vaults, PoolAccountData, Vault};
+use crate::config::{Lamports, MicroLamports, PingThingsArgs};
+use crate::core::accounts::unix_now;
+use crate::fees::compute::{ComputeProfiler, TxShape};
+use crate::fees::FeeEstimator;
+use crate::pools::reserves::ReserveCache;
 use crate::tx_senders::constants::VAULT_PROGRAM_ID;
+use crate::tx_senders::lookup_table::LookupTables;
+use crate::tx_senders::submission::SubmissionManager;
 use crate::tx_senders::transaction::build_swap_transaction;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use solana_sdk::{address_lookup_table::AddressLookupTableAccount, hash::Hash, pubkey::Pubkey, signature::{Keypair, Signature}, transaction::VersionedTransaction};
+use std::sync::Arc;
 use tracing::{info, warn, debug};
 
+/// Сумма свапа, собираемого без отправки (simulate): прежнее фиксированное значение, 0.1 SOL
+const BUILD_AMOUNT_IN: Lamports = Lamports(100_000_000);
+
+/// Всё, что нужно для подписи свапа: состояние пула и параметры compute budget
+pub struct PoolSwap {
+    pub pool: Pubkey,
+    pub token_a: Pubkey,
+    pub token_b: Pubkey,
+    pub info: PoolAccountData,
+    /// Хранилища A и B: их токен-аккаунты и LP mint
+    pub vaults: [Vault; 2],
+    pub shape: TxShape,
+    pub compute_unit_price: MicroLamports,
+    pub compute_unit_limit: u32,
+    /// Сумма покупки
+    pub amount_in: Lamports,
+    /// Котировка за вычетом `swap.slippage_bps`
+    pub min_amount_out: u64,
+    pub lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl PoolSwap {
+    pub async fn prepare(
+        args: &PingThingsArgs,
+        fees: &FeeEstimator,
+        compute: &ComputeProfiler,
+        lookup_tables: &LookupTables,
+        reserves: &ReserveCache,
+        rpc: &RpcClient,
+        pool: &Pubkey,
+        token_a: &Pubkey,
+        token_b: &Pubkey,
+        amount_in: Lamports,
+    ) -> Result<Self> {
+        info!("Получение информации о пуле...");
+        let info = match fetch_pool_accounts(rpc, pool).await {
+            Ok(info) => {
+                info!("Информация о пуле получена");
+                info
+            }
+            Err(e) => {
+                warn!("Не удалось получить информацию о пуле: {:?}", e);
+                return Err(e);
+            }
+        };
+
+        // Хранилища и резервы из кэша резервов, если пул отслеживается, иначе по RPC
+        let (vaults, pool_reserves) = match reserves.meteora_amm(pool) {
+            Some(cached) => cached,
+            None => {
+                let vaults = fetch_vaults(rpc, &info).await?;
+                let pool_reserves = fetch_pool_reserves(rpc, &info, &vaults, unix_now()).await?;
+                (vaults, pool_reserves)
+            }
+        };
+
+        // Покупается token_a: для него создаётся ATA
+        let (reserve_in, reserve_out) = if info.token_a_mint == *token_a {
+            (pool_reserves[1], pool_reserves[0])
+        } else if info.token_b_mint == *token_a {
+            (pool_reserves[0], pool_reserves[1])
+        } else {
+            bail!("mint {token_a} не относится к пулу {pool}");
+        };
+        debug!("Резервы Dynamic AMM: {} / {}", reserve_in, reserve_out);
+
+        let amount_out = info.quote_exact_in(reserve_in, reserve_out, amount_in.0)?;
+        let slippage = args.swap.slippage_bps as u128;
+        let min_amount_out = (amount_out as u128 * (10_000 - slippage) / 10_000) as u64;
+        info!(
+            "Котировка Meteora: {} -> {} токенов (комиссия {}/{}), минимум {}",
+            amount_in, amount_out, info.fees.trade_fee_numerator, info.fees.trade_fee_denominator, min_amount_out
+        );
+
+        let writable_accounts = [*pool, info.a_vault, info.b_vault, info.a_vault_lp, info.b_vault_lp];
+        let compute_unit_price = fees.estimate(rpc, args, &writable_accounts).await;
+
+        // build_swap_transaction всегда создаёт ATA и не оборачивает SOL
+        let shape = TxShape::MeteoraSwap {
+            create_ata: true,
+            wrap_sol: false,
+        };
+        let compute_unit_limit = compute.limit_for(&shape, args);
+
+        Ok(Self {
+            pool: *pool,
+            token_a: *token_a,
+            token_b: *token_b,
+            info,
+            vaults,
+            shape,
+            compute_unit_price,
+            compute_unit_limit,
+            amount_in,
+            min_amount_out,
+            lookup_tables: lookup_tables.tables().to_vec(),
+        })
+    }
+
+    pub fn build(
+        &self,
+        user: &Keypair,
+        recent_blockhash: Hash,
+        compute_unit_price: MicroLamports,
+        nonce_account: Option<Pubkey>,
+    ) -> Result<VersionedTransaction> {
+        info!("Сборка транзакции swap...");
+        match build_swap_transaction(
+            user,
+            &self.pool,
+            &self.token_a,
+            &self.token_b,
+            &self.info.a_vault,
+            &self.info.b_vault,
+            &self.vaults[0].token_vault,
+            &self.vaults[1].token_vault,
+            &self.vaults[0].lp_mint,
+            &self.vaults[1].lp_mint,
+            &self.info.a_vault_lp,
+            &self.info.b_vault_lp,
+            &self.info.protocol_token_a_fee,
+            &VAULT_PROGRAM_ID,
+            self.amount_in.0,
+            self.min_amount_out,
+            compute_unit_price,
+            self.compute_unit_limit,
+            recent_blockhash,
+            nonce_account,
+            &self.lookup_tables,
+        ) {
+            Ok(tx) => {
+                info!("Транзакция успешно собрана");
+                Ok(tx)
+            }
+            Err(e) => {
+                warn!("Ошибка сборки транзакции: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+}
+
 pub async fn fetch_and_execute_swap(
     args: &PingThingsArgs,
+    fees: &FeeEstimator,
+    compute: &Arc<ComputeProfiler>,
+    lookup_tables: &LookupTables,
+    reserves: &ReserveCache,
+    submissions: &SubmissionManager,
     user: &Keypair,
+    nonce_account: Option<Pubkey>,
     pool_account: &Pubkey,
     token_a: &Pubkey,
     token_b: &Pubkey,
-) -> Result<()> {
+    amount_in: Lamports,
+) -> Result<Signature> {
     info!("Запуск свапа через Meteora");
     debug!("Адрес пула: {pool_account}, Token A: {token_a}, Token B: {token_b}");
 
     let rpc = RpcClient::new(args.http_rpc.clone());
-
-    info!("Получение информации о пуле...");
-    let pool_info = match fetch_pool_accounts(&rpc, pool_account).await {
-        Ok(info) => {
-            info!("Информация о пуле получена");
-            info
-        }
-        Err(e) => {
-            warn!("Не удалось получить информацию о пуле: {:?}", e);
-            return Err(e);
-        }
-    };
-
-    info!("Сборка транзакции swap...");
-    let tx = match build_swap_transaction(
+    let swap = PoolSwap::prepare(
         args,
-        user,
+        fees,
+        compute,
+        lookup_tables,
+        reserves,
+        &rpc,
         pool_account,
         token_a,
         token_b,
-        &pool_info.a_vault,
-        &pool_info.b_vault,
-        &pool_info.token_a_mint,
-        &pool_info.token_b_mint,
-        &pool_info.lp_mint,
-        &pool_info.lp_mint,
-        &pool_info.a_vault_lp,
-        &pool_info.b_vault_lp,
-        &pool_info.protocol_token_a_fee,
-        &VAULT_PROGRAM_ID,
-        100_000_000,
-        1,
+        amount_in,
     )
-    .await {
-        Ok(tx) => {
-            info!("Транзакция успешно собрана");
-            tx
-        }
-        Err(e) => {
-            warn!("Ошибка сборки транзакции: {:?}", e);
-            return Err(e);
-        }
-    };
+    .await?;
 
     info!("Отправка транзакции...");
-    match rpc.send_and_confirm_transaction(&tx).await {
+    let result = submissions
+        .submit(args, *pool_account, swap.compute_unit_price, nonce_account, |blockhash, price| {
+            let tx = swap.build(user, blockhash, price, nonce_account)?;
+            compute.profile_in_background(args, swap.shape, &tx);
+            Ok(tx)
+        })
+        .await;
+
+    match result {
         Ok(sig) => {
             info!("Транзакция успешно отправлена! Signature: {}", sig);
-            Ok(())
+            Ok(sig)
         }
         Err(e) => {
-            warn!("Ошибка сборки транзакции: {:?}", e);
-            return Err(e.into());
+            warn!("Ошибка отправки транзакции: {:?}", e);
+            Err(e)
         }
     }
 }
+
+/// Сборка транзакции свапа по адресу пула (без отправки)
+pub async fn build_pool_swap(
+    args: &PingThingsArgs,
+    fees: &FeeEstimator,
+    compute: &Arc<ComputeProfiler>,
+    lookup_tables: &LookupTables,
+    reserves: &ReserveCache,
+    rpc: &RpcClient,
+    user: &Keypair,
+    pool_account: &Pubkey,
+    token_a: &Pubkey,
+    token_b: &Pubkey,
+) -> Result<VersionedTransaction> {
+    let swap = PoolSwap::prepare(
+        args,
+        fees,
+        compute,
+        lookup_tables,
+        reserves,
+        rpc,
+        pool_account,
+        token_a,
+        token_b,
+        BUILD_AMOUNT_IN,
+    )
+    .await?;
+
+    info!("Получение blockhash...");
+    let blockhash = rpc.get_latest_blockhash().await?;
+    let tx = swap.build(user, blockhash, swap.compute_unit_price, None)?;
+
+    compute.profile_in_background(args, swap.shape, &tx);
+
+    Ok(tx)
+}
@@ -1,8 +1,35 @@
+use crate::core::accounts::{read_u64, token_amount, MINT_SUPPLY_OFFSET};
+use crate::raydium::reserves::{constant_product_out, fee_ceil};
+use crate::tx_senders::constants::VAULT_PROGRAM_ID;
+
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use borsh::{BorshDeserialize};
 
+/// Знаменатель скорости разблокировки прибыли хранилища
+const LOCKED_PROFIT_DEGRADATION_DENOMINATOR: u128 = 1_000_000_000_000;
+
+/// Запуск пула: до `activation_point` свапы разрешены только `whitelisted_vault`
+#[derive(BorshDeserialize, Debug)]
+pub struct Bootstrapping {
+    pub activation_point: u64,
+    pub whitelisted_vault: Pubkey,
+    _pool_creator: Pubkey,
+    /// 0 — слоты, 1 — секунды
+    pub activation_type: u8,
+}
+
+/// Комиссия свапа и доля протокола в ней
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct PoolFees {
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+    pub protocol_trade_fee_numerator: u64,
+    pub protocol_trade_fee_denominator: u64,
+}
+
+/// Аккаунт пула Dynamic AMM (без дискриминатора); поля после `bootstrapping` не нужны
 #[derive(BorshDeserialize, Debug)]
 pub struct PoolAccountData {
     pub lp_mint: Pubkey,
@@ -16,10 +43,226 @@ pub struct PoolAccountData {
     pub enabled: bool,
     pub protocol_token_a_fee: Pubkey,
     pub protocol_token_b_fee: Pubkey,
+    _fee_last_updated_at: u64,
+    _padding0: [u8; 24],
+    pub fees: PoolFees,
+    _pool_type: u8,
+    _stake: Pubkey,
+    _total_locked_lp: u64,
+    pub bootstrapping: Bootstrapping,
+}
+
+impl PoolAccountData {
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let mut body = data.get(8..).ok_or_else(|| anyhow!("аккаунт пула короче дискриминатора"))?;
+        Self::deserialize(&mut body).map_err(|e| anyhow!("не удалось разобрать пул: {e}"))
+    }
+
+    /// Выход свапа по формуле `x * y = k`; для stable-пулов занижен, что для минимума допустимо
+    pub fn quote_exact_in(&self, reserve_in: u64, reserve_out: u64, amount_in: u64) -> Result<u64> {
+        let fee = fee_ceil(amount_in, self.fees.trade_fee_numerator, self.fees.trade_fee_denominator)
+            .ok_or_else(|| anyhow!("некорректная комиссия пула"))?;
+        Ok(constant_product_out(reserve_in, reserve_out, amount_in.saturating_sub(fee)))
+    }
+}
+
+/// Прибыль хранилища, разблокируемая линейно после отчёта стратегии
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct LockedProfitTracker {
+    pub last_updated_locked_profit: u64,
+    pub last_report: u64,
+    pub locked_profit_degradation: u64,
+}
+
+/// Хранилище vault program (без дискриминатора), из которого пулы Dynamic AMM берут ликвидность
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct Vault {
+    pub enabled: u8,
+    _bumps: [u8; 2],
+    /// Токены хранилища, включая ещё заблокированную прибыль
+    pub total_amount: u64,
+    /// Токен-аккаунт хранилища
+    pub token_vault: Pubkey,
+    _fee_vault: Pubkey,
+    pub token_mint: Pubkey,
+    /// LP-токены хранилища (не путать с LP-токенами пула)
+    pub lp_mint: Pubkey,
+    _strategies: [Pubkey; 30],
+    _base: Pubkey,
+    _admin: Pubkey,
+    _operator: Pubkey,
+    pub locked_profit_tracker: LockedProfitTracker,
+}
+
+impl Vault {
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let mut body = data.get(8..).ok_or_else(|| anyhow!("аккаунт хранилища короче дискриминатора"))?;
+        Self::deserialize(&mut body).map_err(|e| anyhow!("не удалось разобрать хранилище: {e}"))
+    }
+
+    /// Доступные токены на момент `now`, как их считает vault program при выводе
+    pub fn unlocked_amount(&self, now: u64) -> u64 {
+        let tracker = &self.locked_profit_tracker;
+        let ratio = (now.saturating_sub(tracker.last_report) as u128)
+            .saturating_mul(tracker.locked_profit_degradation as u128);
+        let locked = if ratio > LOCKED_PROFIT_DEGRADATION_DENOMINATOR {
+            0
+        } else {
+            (tracker.last_updated_locked_profit as u128 * (LOCKED_PROFIT_DEGRADATION_DENOMINATOR - ratio)
+                / LOCKED_PROFIT_DEGRADATION_DENOMINATOR) as u64
+        };
+        self.total_amount.saturating_sub(locked)
+    }
+}
+
+/// Доля пула в хранилище: его LP-токены к эмиссии LP хранилища
+pub fn pool_share(vault: &Vault, pool_lp: u64, lp_supply: u64, now: u64) -> Option<u64> {
+    if lp_supply == 0 {
+        return None;
+    }
+    u64::try_from(vault.unlocked_amount(now) as u128 * pool_lp as u128 / lp_supply as u128).ok()
 }
 
 pub async fn fetch_pool_accounts(rpc: &RpcClient, pool_pubkey: &Pubkey) -> Result<PoolAccountData> {
     let account = rpc.get_account(pool_pubkey).await?;
-    let data = PoolAccountData::try_from_slice(&account.data)?;
-    Ok(data)
-}
\ No newline at end of file
+    PoolAccountData::decode(&account.data)
+}
+
+/// Хранилища A и B пула одним запросом
+pub async fn fetch_vaults(rpc: &RpcClient, info: &PoolAccountData) -> Result<[Vault; 2]> {
+    let accounts = rpc.get_multiple_accounts(&[info.a_vault, info.b_vault]).await?;
+    let mut vaults = Vec::with_capacity(2);
+    for (address, account) in [info.a_vault, info.b_vault].iter().zip(accounts) {
+        let account = account.ok_or_else(|| anyhow!("хранилище {address} не найдено"))?;
+        if account.owner != VAULT_PROGRAM_ID {
+            bail!("{address} не принадлежит vault program");
+        }
+        vaults.push(Vault::decode(&account.data)?);
+    }
+    let [a, b]: [Vault; 2] = vaults.try_into().map_err(|_| anyhow!("ожидалось два хранилища"))?;
+    Ok([a, b])
+}
+
+/// Резервы A и B пула по RPC: LP-токены пула в хранилищах и эмиссия LP хранилищ
+pub async fn fetch_pool_reserves(rpc: &RpcClient, info: &PoolAccountData, vaults: &[Vault; 2], now: u64) -> Result<[u64; 2]> {
+    let addresses = [info.a_vault_lp, info.b_vault_lp, vaults[0].lp_mint, vaults[1].lp_mint];
+    let accounts = rpc.get_multiple_accounts(&addresses).await?;
+    let mut data = Vec::with_capacity(addresses.len());
+    for (address, account) in addresses.iter().zip(accounts) {
+        data.push(account.ok_or_else(|| anyhow!("аккаунт {address} не найден"))?.data);
+    }
+
+    let mut reserves = [0; 2];
+    for side in 0..2 {
+        let pool_lp = token_amount(&data[side])?;
+        let lp_supply = read_u64(&data[side + 2], MINT_SUPPLY_OFFSET)?;
+        reserves[side] = pool_share(&vaults[side], pool_lp, lp_supply, now)
+            .ok_or_else(|| anyhow!("пустое хранилище {}", [info.a_vault, info.b_vault][side]))?;
+    }
+    Ok(reserves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> PoolAccountData {
+        PoolAccountData {
+            lp_mint: Pubkey::default(),
+            token_a_mint: Pubkey::default(),
+            token_b_mint: Pubkey::default(),
+            a_vault: Pubkey::default(),
+            b_vault: Pubkey::default(),
+            a_vault_lp: Pubkey::default(),
+            b_vault_lp: Pubkey::default(),
+            a_vault_lp_bump: 0,
+            enabled: true,
+            protocol_token_a_fee: Pubkey::default(),
+            protocol_token_b_fee: Pubkey::default(),
+            _fee_last_updated_at: 0,
+            _padding0: [0; 24],
+            fees: PoolFees {
+                trade_fee_numerator: 25,
+                trade_fee_denominator: 10_000,
+                protocol_trade_fee_numerator: 20,
+                protocol_trade_fee_denominator: 100,
+            },
+            _pool_type: 0,
+            _stake: Pubkey::default(),
+            _total_locked_lp: 0,
+            bootstrapping: Bootstrapping {
+                activation_point: 0,
+                whitelisted_vault: Pubkey::default(),
+                _pool_creator: Pubkey::default(),
+                activation_type: 0,
+            },
+        }
+    }
+
+    fn vault(total_amount: u64, locked_profit: u64, last_report: u64) -> Vault {
+        Vault {
+            enabled: 1,
+            _bumps: [0; 2],
+            total_amount,
+            token_vault: Pubkey::default(),
+            _fee_vault: Pubkey::default(),
+            token_mint: Pubkey::default(),
+            lp_mint: Pubkey::default(),
+            _strategies: [Pubkey::default(); 30],
+            _base: Pubkey::default(),
+            _admin: Pubkey::default(),
+            _operator: Pubkey::default(),
+            locked_profit_tracker: LockedProfitTracker {
+                last_updated_locked_profit: locked_profit,
+                last_report,
+                locked_profit_degradation: 1_000_000_000,
+            },
+        }
+    }
+
+    #[test]
+    fn quote_exact_in_charges_trade_fee_from_input() {
+        let pool = pool();
+        let cases = [
+            // (amount_in, ожидаемый выход): резервы 10 SOL / 1 000 000 токенов
+            (1_000_000_000, 90_702_432_370),
+            (401, 39_899),
+            (1, 0),
+            (0, 0),
+        ];
+        for (amount_in, expected) in cases {
+            assert_eq!(
+                pool.quote_exact_in(10_000_000_000, 1_000_000_000_000, amount_in).unwrap(),
+                expected,
+                "выход для входа {amount_in}"
+            );
+        }
+    }
+
+    #[test]
+    fn quote_exact_in_rejects_zero_fee_denominator() {
+        let mut pool = pool();
+        pool.fees.trade_fee_denominator = 0;
+        assert!(pool.quote_exact_in(10_000_000_000, 1_000_000_000_000, 1_000).is_err());
+    }
+
+    #[test]
+    fn pool_share_excludes_locked_profit() {
+        let cases = [
+            // (хранилище, LP пула, эмиссия LP, ожидаемая доля)
+            (vault(1_000, 0, 0), 250, 1_000, Some(250)),
+            (vault(1_000, 400, 100), 250, 1_000, Some(150)),
+            // За 100 секунд после отчёта разблокирована десятая часть прибыли
+            (vault(1_000, 400, 0), 250, 1_000, Some(160)),
+            (vault(1_000, 0, 0), 250, 0, None),
+        ];
+        for (vault, pool_lp, lp_supply, expected) in cases {
+            assert_eq!(
+                pool_share(&vault, pool_lp, lp_supply, 100),
+                expected,
+                "доля {pool_lp}/{lp_supply} при заблокированной прибыли {}",
+                vault.locked_profit_tracker.last_updated_locked_profit
+            );
+        }
+    }
+}
@@ -1,3 +1,7 @@
-pub mod controller;
+pub mod damm_v2;
+pub mod damm_v2_swap;
+pub mod dlmm;
+pub mod dlmm_swap;
+pub mod events;
 pub mod fetch_and_swap;
-pub mod fetch_pool;
\ No newline at end of file
+pub mod fetch_pool;
@@ -1,4 +1,4 @@
-use prometheus::{IntCounter, register_int_counter, Encoder, TextEncoder, gather};
+use prometheus::{IntCounter, IntGauge, IntGaugeVec, register_int_counter, register_int_gauge, register_int_gauge_vec, Encoder, TextEncoder, gather};
 use lazy_static::lazy_static;
 
 lazy_static! {
@@ -13,6 +13,62 @@ lazy_static! {
     pub static ref METEORA_POOL_DETECTED: IntCounter = register_int_counter!(
         "meteora_pools_detected_total", "Обнаруженные пулы Meteora"
     ).unwrap();
+
+    pub static ref RAYDIUM_SWAP_SUCCESS: IntCounter = register_int_counter!(
+        "raydium_swap_success_total", "Успешные свапы Raydium"
+    ).unwrap();
+
+    pub static ref RAYDIUM_SWAP_FAILURE: IntCounter = register_int_counter!(
+        "raydium_swap_failure_total", "Неудачные свапы Raydium"
+    ).unwrap();
+
+    pub static ref RAYDIUM_POOL_DETECTED: IntCounter = register_int_counter!(
+        "raydium_pools_detected_total", "Обнаруженные пулы Raydium"
+    ).unwrap();
+
+    pub static ref PUMP_FUN_TOKEN_DETECTED: IntCounter = register_int_counter!(
+        "pump_fun_tokens_detected_total", "Обнаруженные токены Pump.fun"
+    ).unwrap();
+
+    pub static ref PUMP_FUN_BUY_SUCCESS: IntCounter = register_int_counter!(
+        "pump_fun_buy_success_total", "Покупки Pump.fun, принятые хотя бы одним отправщиком"
+    ).unwrap();
+
+    pub static ref PUMP_FUN_BUY_FAILURE: IntCounter = register_int_counter!(
+        "pump_fun_buy_failure_total", "Покупки Pump.fun, не принятые ни одним отправщиком"
+    ).unwrap();
+
+    pub static ref PUMP_FUN_CURVES_COMPLETED: IntCounter = register_int_counter!(
+        "pump_fun_curves_completed_total", "Заполненные bonding curve Pump.fun"
+    ).unwrap();
+
+    pub static ref PUMP_FUN_GRADUATIONS: IntCounter = register_int_counter!(
+        "pump_fun_graduations_total", "Миграции токенов Pump.fun с bonding curve"
+    ).unwrap();
+
+    pub static ref SUBMISSION_REBROADCASTS: IntCounter = register_int_counter!(
+        "submission_rebroadcasts_total", "Повторные отправки подписанной транзакции"
+    ).unwrap();
+
+    pub static ref SUBMISSION_RESIGNS: IntCounter = register_int_counter!(
+        "submission_resigns_total", "Повторные подписи после истечения blockhash"
+    ).unwrap();
+
+    pub static ref SCHEDULED_LAUNCHES: IntGauge = register_int_gauge!(
+        "scheduled_launches", "Покупки, ожидающие активации пула"
+    ).unwrap();
+
+    pub static ref RESERVE_TRACKED_POOLS: IntGauge = register_int_gauge!(
+        "reserve_tracked_pools", "Пулы, резервы которых отслеживаются по подписке Geyser"
+    ).unwrap();
+
+    pub static ref PRIORITY_FEE_MICRO_LAMPORTS: IntGauge = register_int_gauge!(
+        "priority_fee_micro_lamports", "Выбранная цена compute unit для последней сделки"
+    ).unwrap();
+
+    pub static ref WALLET_EXPOSURE_LAMPORTS: IntGaugeVec = register_int_gauge_vec!(
+        "wallet_exposure_lamports", "Экспозиция кошелька (резерв + открытые позиции)", &["wallet"]
+    ).unwrap();
 }
 
 pub fn encode_metrics() -> Vec<u8> {
@@ -1,22 +1,62 @@
-use hyper::{Body, Request, Response, Server};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
 use hyper::service::{make_service_fn, service_fn};
+use crate::launch::scheduler::LaunchScheduler;
+use crate::pools::reserves::ReserveCache;
 use crate::metrics::encode_metrics;
+use std::sync::Arc;
 
-async fn metrics_handler(_req: Request<Body>) -> Result<Response<Body>, hyper::Error> {
-    Ok(Response::builder()
+fn metrics_response() -> Response<Body> {
+    Response::builder()
         .status(200)
         .header("Content-Type", "text/plain")
         .body(Body::from(encode_metrics()))
-        .unwrap())
+        .unwrap()
 }
 
-pub async fn start_metrics_server() {
+fn json_response<T: serde::Serialize>(value: &T) -> Response<Body> {
+    match serde_json::to_vec(value) {
+        Ok(body) => Response::builder()
+            .status(200)
+            .header("Content-Type", "application/json")
+            .body(Body::from(body))
+            .unwrap(),
+        Err(e) => Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(Body::from(e.to_string()))
+            .unwrap(),
+    }
+}
+
+/// Admin API: запланированные покупки и резервы отслеживаемых пулов
+async fn handler(
+    req: Request<Body>,
+    launches: Arc<LaunchScheduler>,
+    reserves: Arc<ReserveCache>,
+) -> Result<Response<Body>, hyper::Error> {
+    let response = match (req.method(), req.uri().path()) {
+        (&Method::GET, "/admin/launches") => json_response(&launches.reports()),
+        (&Method::GET, "/admin/reserves") => json_response(&reserves.reports()),
+        (_, path) if path.starts_with("/admin") => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .unwrap(),
+        // Prometheus по любому другому пути, как и раньше
+        _ => metrics_response(),
+    };
+    Ok(response)
+}
+
+/// HTTP-сервер метрик Prometheus и admin API
+pub async fn start_metrics_server(launches: Arc<LaunchScheduler>, reserves: Arc<ReserveCache>) {
     let addr = ([0, 0, 0, 0], 9898).into();
-    let make_svc = make_service_fn(|_conn| async {
-        Ok::<_, hyper::Error>(service_fn(metrics_handler))
+    let make_svc = make_service_fn(move |_conn| {
+        let (launches, reserves) = (launches.clone(), reserves.clone());
+        async move {
+            Ok::<_, hyper::Error>(service_fn(move |req| handler(req, launches.clone(), reserves.clone())))
+        }
     });
 
     if let Err(e) = Server::bind(&addr).serve(make_svc).await {
         eprintln!("Prometheus server error: {}", e);
     }
-}
\ No newline at end of file
+}
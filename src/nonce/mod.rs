@@ -0,0 +1,139 @@
+use anyhow::{anyhow, bail, Context, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::nonce::state::{State, Versions};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+/// Состояние durable nonce аккаунта
+#[derive(Debug, Clone)]
+pub struct NonceAccount {
+    pub address: Pubkey,
+    pub authority: Pubkey,
+    /// Значение nonce, используемое вместо recent blockhash
+    pub nonce: Hash,
+    pub lamports_per_signature: u64,
+}
+
+pub async fn fetch_nonce_account(rpc: &RpcClient, address: &Pubkey) -> Result<NonceAccount> {
+    let account = rpc
+        .get_account(address)
+        .await
+        .with_context(|| format!("nonce аккаунт {address} не найден"))?;
+
+    if account.owner != system_program::id() {
+        bail!("{address} не принадлежит System Program");
+    }
+
+    decode_nonce_account(address, &account.data)
+}
+
+fn decode_nonce_account(address: &Pubkey, data: &[u8]) -> Result<NonceAccount> {
+    let versions: Versions =
+        bincode::deserialize(data).map_err(|_| anyhow!("{address} не является nonce аккаунтом"))?;
+
+    match versions.state() {
+        State::Initialized(data) => Ok(NonceAccount {
+            address: *address,
+            authority: data.authority,
+            nonce: data.blockhash(),
+            lamports_per_signature: data.fee_calculator.lamports_per_signature,
+        }),
+        State::Uninitialized => bail!("nonce аккаунт {address} не инициализирован"),
+    }
+}
+
+/// Nonce аккаунты из конфигурации, сгруппированные по authority (кошельку).
+/// Один аккаунт одновременно используется только одной сделкой.
+///
+/// Текущие значения nonce кэшируются и обновляются подпиской Geyser на аккаунты,
+/// поэтому транзакция подписывается без RPC-запроса.
+#[derive(Default)]
+pub struct NoncePool {
+    accounts: Vec<NonceAccount>,
+    in_use: Mutex<HashSet<Pubkey>>,
+    values: Mutex<HashMap<Pubkey, Hash>>,
+}
+
+impl NoncePool {
+    pub async fn load(rpc: &RpcClient, addresses: &[String]) -> Result<Self> {
+        let mut accounts = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let address = Pubkey::from_str(address)
+                .map_err(|_| anyhow!("некорректный адрес nonce аккаунта {address}"))?;
+            let account = fetch_nonce_account(rpc, &address).await?;
+            info!("Nonce аккаунт {} (authority {})", account.address, account.authority);
+            accounts.push(account);
+        }
+
+        let values = accounts.iter().map(|account| (account.address, account.nonce)).collect();
+        Ok(Self {
+            accounts,
+            in_use: Mutex::new(HashSet::new()),
+            values: Mutex::new(values),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Адреса аккаунтов для подписки Geyser
+    pub fn addresses(&self) -> Vec<Pubkey> {
+        self.accounts.iter().map(|account| account.address).collect()
+    }
+
+    /// Закэшированное значение nonce; `None`, если оно сдвинуто и новое ещё не пришло
+    pub fn value(&self, address: &Pubkey) -> Option<Hash> {
+        self.values.lock().unwrap().get(address).copied()
+    }
+
+    pub fn set_value(&self, address: &Pubkey, value: Hash) {
+        self.values.lock().unwrap().insert(*address, value);
+    }
+
+    /// Обновление аккаунта из потока Geyser
+    pub fn update(&self, address: &Pubkey, data: &[u8]) {
+        if !self.accounts.iter().any(|account| account.address == *address) {
+            return;
+        }
+
+        match decode_nonce_account(address, data) {
+            Ok(account) => self.set_value(address, account.nonce),
+            Err(e) => warn!("Обновление nonce {}: {}", address, e),
+        }
+    }
+
+    /// Транзакция с `value` обработана и сдвинула nonce: значение больше не годится,
+    /// если подписка ещё не прислала новое
+    pub fn mark_used(&self, address: &Pubkey, value: &Hash) {
+        let mut values = self.values.lock().unwrap();
+        if values.get(address) == Some(value) {
+            values.remove(address);
+        }
+    }
+
+    /// Свободный nonce аккаунт, подписываемый кошельком `authority`
+    pub fn acquire(&self, authority: &Pubkey) -> Option<Pubkey> {
+        let mut in_use = self.in_use.lock().unwrap();
+        let account = self
+            .accounts
+            .iter()
+            .find(|account| account.authority == *authority && !in_use.contains(&account.address))?;
+
+        in_use.insert(account.address);
+        Some(account.address)
+    }
+
+    pub fn release(&self, address: &Pubkey) {
+        self.in_use.lock().unwrap().remove(address);
+    }
+}
@@ -0,0 +1,756 @@
+use crate::config::{Lamports, PingThingsArgs, SharedConfig};
+use crate::core::alt::AltResolver;
+use crate::core::decoder::meteora::{MeteoraAmmInstruction, MeteoraDammV2Instruction, MeteoraDlmmInstruction};
+use crate::core::decoder::pump_fun::PumpFunInstruction;
+use crate::core::decoder::raydium::{RaydiumAmmV4Instruction, RaydiumCpmmInstruction};
+use crate::core::decoder::{DecodedInstruction, DecoderRegistry};
+use crate::core::{extract_instructions, extract_instructions_resolved, ParsedInstruction};
+use crate::geyser::subscriptions::AccountSubscriptions;
+use crate::geyser::GeyserResult;
+use crate::launch::scheduler::{LaunchScheduler, ScheduledLaunch};
+use crate::launch::{fetch_schedule, Activation, LaunchSchedule};
+use crate::fees::compute::ComputeProfiler;
+use crate::fees::FeeEstimator;
+use crate::nonce::NoncePool;
+use crate::strategy::{self, Action, MarketEvent, Strategy, Venue};
+use crate::meteora::events::{parse_events, MeteoraEvent};
+use crate::meteora::damm_v2_swap::fetch_and_execute_damm_v2_swap;
+use crate::meteora::dlmm_swap::fetch_and_execute_dlmm_swap;
+use crate::meteora::fetch_and_swap::fetch_and_execute_swap;
+use crate::metrics::{
+    METEORA_POOL_DETECTED, METEORA_SWAP_FAILURE, METEORA_SWAP_SUCCESS, RAYDIUM_POOL_DETECTED,
+    RAYDIUM_SWAP_FAILURE, RAYDIUM_SWAP_SUCCESS,
+};
+use crate::pools::reserves::ReserveCache;
+use crate::pump_fun::graduation::GraduationTracker;
+use crate::pump_fun::sell::sell_on_curve;
+use crate::raydium::amm_v4_swap::fetch_and_execute_raydium_amm_v4_swap;
+use crate::raydium::cpmm_swap::fetch_and_execute_raydium_cpmm_swap;
+use crate::tx_senders::constants::WSOL_MINT;
+use crate::tx_senders::lookup_table::LookupTables;
+use crate::tx_senders::submission::SubmissionManager;
+use crate::wallet::pool::WalletPool;
+
+use anyhow::Result;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, warn};
+use yellowstone_grpc_proto::prelude::TransactionStatusMeta as YellowstoneMeta;
+use yellowstone_grpc_proto::convert_from::create_tx_meta;
+
+/// Попытки прочитать точку активации пула, ещё не видимого через RPC
+const SCHEDULE_FETCH_ATTEMPTS: u32 = 5;
+const SCHEDULE_FETCH_DELAY: Duration = Duration::from_millis(400);
+
+/// Контроллер новых пулов AMM: Meteora (Dynamic AMM, DLMM, DAMM v2) и Raydium (AMM v4, CPMM)
+#[derive(Clone)]
+pub struct PoolController {
+    pub args: SharedConfig,
+    pub wallets: Arc<WalletPool>,
+    pub fees: Arc<FeeEstimator>,
+    pub compute: Arc<ComputeProfiler>,
+    pub lookup_tables: Arc<LookupTables>,
+    pub submissions: Arc<SubmissionManager>,
+    pub nonces: Arc<NoncePool>,
+    pub alt_resolver: Arc<AltResolver>,
+    pub graduations: Arc<GraduationTracker>,
+    pub launches: Arc<LaunchScheduler>,
+    pub reserves: Arc<ReserveCache>,
+    pub account_subscriptions: Arc<AccountSubscriptions>,
+    pub decoders: Arc<DecoderRegistry>,
+    pub strategies: Arc<Vec<Box<dyn Strategy>>>,
+    pub seen_pools: Arc<RwLock<HashSet<String>>>,
+}
+
+impl PoolController {
+    pub fn new(
+        args: SharedConfig,
+        wallets: Arc<WalletPool>,
+        fees: Arc<FeeEstimator>,
+        compute: Arc<ComputeProfiler>,
+        lookup_tables: Arc<LookupTables>,
+        submissions: Arc<SubmissionManager>,
+        nonces: Arc<NoncePool>,
+        alt_resolver: Arc<AltResolver>,
+        graduations: Arc<GraduationTracker>,
+        launches: Arc<LaunchScheduler>,
+        reserves: Arc<ReserveCache>,
+        account_subscriptions: Arc<AccountSubscriptions>,
+    ) -> Self {
+        Self {
+            args,
+            wallets,
+            fees,
+            compute,
+            lookup_tables,
+            submissions,
+            nonces,
+            alt_resolver,
+            graduations,
+            launches,
+            reserves,
+            account_subscriptions,
+            decoders: Arc::new(DecoderRegistry::default()),
+            strategies: Arc::new(strategy::from_config(&args.load())),
+            seen_pools: Arc::new(RwLock::new(HashSet::new())),
+        }
+    }
+
+    pub async fn transaction_handler(
+        &self,
+        tx: VersionedTransaction,
+        meta: YellowstoneMeta,
+    ) -> Result<()> {
+        if meta.err.is_some() {
+            return Ok(()); // Пропускаем failed-транзакции
+        }
+
+        // Без пригодной meta адреса из lookup tables разрешаются через кэш таблиц
+        let (extracted, events) = match create_tx_meta(meta.clone()) {
+            Ok(parsed_meta) => {
+                let events = parsed_meta
+                    .log_messages
+                    .as_deref()
+                    .map(parse_events)
+                    .unwrap_or_default();
+                (extract_instructions(parsed_meta, tx.clone()), events)
+            }
+            Err(e) => {
+                warn!("Не удалось сконвертировать meta: {:?}", e);
+                (extract_instructions_resolved(&self.alt_resolver, tx.clone()).await, Vec::new())
+            }
+        };
+
+        let events = self.market_events(extracted, &events);
+
+        for event in events {
+            if let MarketEvent::NewPool { venue, pool, token_a, token_b, origin, graduation, .. } = &event {
+                // Проверка и вставка под одной блокировкой: обработчики транзакций работают параллельно
+                if !self.seen_pools.write().unwrap().insert(pool.to_string()) {
+                    continue;
+                }
+
+                info!("Обнаружен новый пул: {} | Token A: {} | Token B: {}{}",
+                    pool, token_a, token_b, origin);
+                if venue.is_raydium() {
+                    RAYDIUM_POOL_DETECTED.inc();
+                } else {
+                    METEORA_POOL_DETECTED.inc();
+                }
+                if let Some(graduation) = graduation {
+                    info!(
+                        "Пул {} создан миграцией {} с Pump.fun: собрано {} lamports, обновлений кривой {}, \
+                         отслеживание {:?}, от заполнения до пула {:?}",
+                        pool,
+                        graduation.mint,
+                        graduation.real_sol_reserves,
+                        graduation.updates,
+                        graduation.tracked_since.map(|since| since.elapsed()),
+                        graduation.completed_at.map(|at| at.elapsed()),
+                    );
+                }
+            }
+
+            // Снимок параметров на время сделки: перезагрузка конфигурации её не затронет
+            let args = self.args.load();
+            let activation = match &event {
+                MarketEvent::NewPool { activation, .. } => *activation,
+                _ => None,
+            };
+            for strategy in self.strategies.iter() {
+                match strategy.on_event(&event, &args) {
+                    Action::Buy { venue, pool, token_a, token_b, amount } => {
+                        info!("Стратегия {}: покупка в пуле {} ({:?})", strategy.name(), pool, venue);
+                        // Не задерживает покупку: её котировка без кэша читает пул по RPC
+                        let controller = self.clone();
+                        tokio::spawn(async move {
+                            controller.track_reserves(venue, pool, token_a, token_b).await;
+                        });
+                        match self.pending_launch(&args, venue, &pool, activation).await {
+                            Ok(Some(schedule)) => {
+                                let launch = ScheduledLaunch {
+                                    venue,
+                                    pool,
+                                    token_a,
+                                    token_b,
+                                    amount,
+                                    strategy: strategy.name(),
+                                    schedule,
+                                    scheduled_at: SystemTime::now(),
+                                };
+                                self.schedule_buy(&args, launch);
+                            }
+                            Ok(None) => self.buy(&args, venue, pool, token_a, token_b, amount).await,
+                            Err(e) => warn!("Покупка в пуле {} пропущена: {:?}", pool, e),
+                        }
+                    }
+                    Action::Sell { pool } => self.sell(&args, strategy.name(), pool).await,
+                    Action::Ignore { reason } => {
+                        // Свапы и ликвидность — частые события, их пропуски не логируются
+                        if matches!(event, MarketEvent::NewPool { .. }) {
+                            info!("Стратегия {}: пропуск пула {}: {}", strategy.name(), event.pool(), reason);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// События рынка из транзакции: новые пулы, добавление ликвидности, свапы.
+    /// Событие PoolCreated надёжнее разбора инструкций, поэтому имеет приоритет.
+    /// Миграции Pump.fun (в этой же или более ранней транзакции) привязываются к новым пулам по mint.
+    fn market_events(
+        &self,
+        extracted: GeyserResult<Vec<ParsedInstruction>>,
+        events: &[MeteoraEvent],
+    ) -> Vec<MarketEvent> {
+        let mut market_events: Vec<MarketEvent> = events
+            .iter()
+            .filter_map(|event| match event {
+                MeteoraEvent::PoolCreated(created) => Some(MarketEvent::NewPool {
+                    venue: Venue::MeteoraAmm,
+                    pool: created.pool,
+                    token_a: created.token_a_mint,
+                    token_b: created.token_b_mint,
+                    origin: " (событие PoolCreated)".to_string(),
+                    graduation: None,
+                    activation: None,
+                }),
+                MeteoraEvent::Swap(_) | MeteoraEvent::AddLiquidity(_) => None,
+            })
+            .collect();
+        let from_logs = !market_events.is_empty();
+        // Позиции свапов и добавлений ликвидности Meteora AMM для сумм из событий
+        let mut amm_swaps = Vec::new();
+        let mut amm_deposits = Vec::new();
+
+        let Ok(instructions) = extracted else {
+            warn!("Не удалось извлечь инструкции");
+            return self.attach_graduations(market_events);
+        };
+
+        // Включая CPI: пулы, созданные через роутеры, лаунчпады и мультисиги
+        for parsed in instructions {
+            let cpi = if parsed.is_inner() {
+                format!(", CPI из инструкции #{}", parsed.parent_index)
+            } else {
+                String::new()
+            };
+
+            match self.decoders.decode(&parsed.instruction) {
+                Some(DecodedInstruction::MeteoraAmm(MeteoraAmmInstruction::InitializePool {
+                    kind, pool, token_a_mint, token_b_mint, ..
+                })) if !from_logs => {
+                    market_events.push(MarketEvent::NewPool {
+                        venue: Venue::MeteoraAmm,
+                        pool,
+                        token_a: token_a_mint,
+                        token_b: token_b_mint,
+                        origin: format!(" ({kind}{cpi})"),
+                        graduation: None,
+                        activation: None,
+                    });
+                }
+                Some(DecodedInstruction::MeteoraAmm(MeteoraAmmInstruction::AddLiquidity {
+                    pool, user, pool_token_amount,
+                })) => {
+                    amm_deposits.push(market_events.len());
+                    market_events.push(MarketEvent::LiquidityAdded { pool, user, pool_token_amount });
+                }
+                Some(DecodedInstruction::MeteoraAmm(MeteoraAmmInstruction::RemoveLiquidity {
+                    pool, user, pool_token_amount,
+                })) => {
+                    market_events.push(MarketEvent::LiquidityRemoved { pool, user, pool_token_amount });
+                }
+                Some(DecodedInstruction::MeteoraAmm(MeteoraAmmInstruction::Swap { pool, user, in_amount, .. })) => {
+                    amm_swaps.push(market_events.len());
+                    market_events.push(MarketEvent::Swap { pool, user, in_amount });
+                }
+                Some(DecodedInstruction::MeteoraDlmm(MeteoraDlmmInstruction::InitializeLbPair {
+                    kind, lb_pair, token_x_mint, token_y_mint, active_id, bin_step, ..
+                })) => {
+                    market_events.push(MarketEvent::NewPool {
+                        venue: Venue::MeteoraDlmm,
+                        pool: lb_pair,
+                        token_a: token_x_mint,
+                        token_b: token_y_mint,
+                        origin: format!(" (DLMM {kind}, bin_step {bin_step}, активный бин {active_id}{cpi})"),
+                        graduation: None,
+                        activation: None,
+                    });
+                }
+                Some(DecodedInstruction::MeteoraDlmm(MeteoraDlmmInstruction::Swap {
+                    lb_pair, user, amount_in, ..
+                })) => {
+                    market_events.push(MarketEvent::Swap { pool: lb_pair, user, in_amount: amount_in });
+                }
+                Some(DecodedInstruction::MeteoraDammV2(MeteoraDammV2Instruction::InitializePool {
+                    kind, pool, token_a_mint, token_b_mint, position_nft_mint, ..
+                })) => {
+                    market_events.push(MarketEvent::NewPool {
+                        venue: Venue::MeteoraDammV2,
+                        pool,
+                        token_a: token_a_mint,
+                        token_b: token_b_mint,
+                        origin: format!(" (DAMM v2 {kind}, NFT позиции {position_nft_mint}{cpi})"),
+                        graduation: None,
+                        activation: None,
+                    });
+                }
+                Some(DecodedInstruction::MeteoraDammV2(MeteoraDammV2Instruction::Swap {
+                    pool, user, amount_in, ..
+                })) => {
+                    market_events.push(MarketEvent::Swap { pool, user, in_amount: amount_in });
+                }
+                Some(DecodedInstruction::RaydiumAmmV4(RaydiumAmmV4Instruction::Initialize2 {
+                    amm, coin_mint, pc_mint, market, open_time, ..
+                })) => {
+                    market_events.push(MarketEvent::NewPool {
+                        venue: Venue::RaydiumAmmV4,
+                        pool: amm,
+                        token_a: coin_mint,
+                        token_b: pc_mint,
+                        origin: format!(" (Raydium AMM v4 initialize2, рынок {market}, открытие {open_time}{cpi})"),
+                        graduation: None,
+                        activation: Some(Activation::Timestamp(open_time)),
+                    });
+                }
+                Some(DecodedInstruction::RaydiumAmmV4(RaydiumAmmV4Instruction::SwapBaseIn {
+                    amm, user, amount_in, ..
+                })) => {
+                    market_events.push(MarketEvent::Swap { pool: amm, user, in_amount: amount_in });
+                }
+                Some(DecodedInstruction::RaydiumCpmm(RaydiumCpmmInstruction::Initialize {
+                    pool, token_0_mint, token_1_mint, open_time, ..
+                })) => {
+                    market_events.push(MarketEvent::NewPool {
+                        venue: Venue::RaydiumCpmm,
+                        pool,
+                        token_a: token_0_mint,
+                        token_b: token_1_mint,
+                        origin: format!(" (Raydium CPMM initialize, открытие {open_time}{cpi})"),
+                        graduation: None,
+                        activation: Some(Activation::Timestamp(open_time)),
+                    });
+                }
+                Some(DecodedInstruction::RaydiumCpmm(RaydiumCpmmInstruction::SwapBaseInput {
+                    pool, user, amount_in, ..
+                })) => {
+                    market_events.push(MarketEvent::Swap { pool, user, in_amount: amount_in });
+                }
+                Some(DecodedInstruction::PumpFun(PumpFunInstruction::Withdraw { mint, bonding_curve, .. })) => {
+                    self.graduations.migrated(mint, bonding_curve);
+                }
+                _ => {}
+            }
+        }
+
+        apply_event_amounts(&mut market_events, events, &amm_swaps, &amm_deposits);
+        self.attach_graduations(market_events)
+    }
+
+    fn attach_graduations(&self, mut market_events: Vec<MarketEvent>) -> Vec<MarketEvent> {
+        for event in &mut market_events {
+            if let MarketEvent::NewPool { token_a, token_b, graduation, .. } = event {
+                *graduation = self.graduations.take(token_a, token_b);
+            }
+        }
+        market_events
+    }
+
+    /// Подписка на аккаунты пула и его хранилищ для кэша резервов после решения о покупке:
+    /// по кэшу котируются отложенный запуск и сопровождение позиции.
+    /// Сверх `max_pools` снимаются самые старые пулы без открытых позиций.
+    async fn track_reserves(&self, venue: Venue, pool: Pubkey, token_a: Pubkey, token_b: Pubkey) {
+        let args = self.args.load();
+        if !args.reserves.enabled {
+            return;
+        }
+
+        let rpc = RpcClient::new(args.http_rpc.clone());
+        match self.reserves.track(&rpc, venue, pool, token_a, token_b).await {
+            Ok(accounts) => {
+                self.account_subscriptions.subscribe(&accounts);
+                debug!("Резервы пула {} отслеживаются по {} аккаунтам", pool, accounts.len());
+            }
+            Err(e) => {
+                warn!("Не удалось подписаться на резервы пула {}: {:?}", pool, e);
+                self.reserves.untrack(&pool);
+                return;
+            }
+        }
+
+        let open: HashSet<Pubkey> = self
+            .wallets
+            .positions()
+            .into_iter()
+            .map(|(_, position)| position.pool)
+            .collect();
+        for evicted in self.reserves.excess(args.reserves.max_pools, &open) {
+            let released = self.reserves.untrack(&evicted);
+            self.account_subscriptions.unsubscribe(&released);
+        }
+    }
+
+    /// Расписание пула, торговля в котором ещё не открыта; `None` — покупать сразу.
+    /// Момент открытия берётся из инструкции создания, иначе из состояния пула с повторами.
+    /// Если его не удалось узнать, покупка пропускается: до активации она только сжигает комиссию.
+    async fn pending_launch(
+        &self,
+        args: &PingThingsArgs,
+        venue: Venue,
+        pool: &Pubkey,
+        activation: Option<Activation>,
+    ) -> Result<Option<LaunchSchedule>> {
+        if !args.launch.schedule {
+            return Ok(None);
+        }
+
+        let schedule = match activation {
+            Some(activation) => LaunchSchedule { activation, alpha_vault: None },
+            None => {
+                let rpc = RpcClient::new(args.http_rpc.clone());
+                let mut attempt = 1;
+                loop {
+                    match fetch_schedule(&rpc, venue, pool).await {
+                        Ok(schedule) => break schedule,
+                        Err(e) if attempt < SCHEDULE_FETCH_ATTEMPTS => {
+                            debug!("Не удалось прочитать активацию пула {} (попытка {}): {:?}", pool, attempt, e);
+                            attempt += 1;
+                            tokio::time::sleep(SCHEDULE_FETCH_DELAY).await;
+                        }
+                        Err(e) => {
+                            return Err(e.context(format!(
+                                "активация пула {pool} не прочитана за {SCHEDULE_FETCH_ATTEMPTS} попыток"
+                            )))
+                        }
+                    }
+                }
+            }
+        };
+        Ok((!schedule.is_open(self.launches.clock())).then_some(schedule))
+    }
+
+    /// Отложенная покупка: отправляется в момент активации пула
+    fn schedule_buy(&self, args: &PingThingsArgs, launch: ScheduledLaunch) {
+        let eta = launch.schedule.eta(self.launches.clock());
+        if eta > Duration::from_secs(args.launch.max_wait_secs) {
+            info!(
+                "Пул {} активируется через {:?} ({:?}), дольше max_wait_secs: покупка пропущена",
+                launch.pool, eta, launch.schedule.activation
+            );
+            return;
+        }
+
+        let pool = launch.pool;
+        let schedule = launch.schedule.clone();
+        let (venue, token_a, token_b, amount) = (launch.venue, launch.token_a, launch.token_b, launch.amount);
+        if !self.launches.insert(launch) {
+            return;
+        }
+        info!(
+            "Покупка в пуле {} запланирована на активацию {:?} (через {:?}){}",
+            pool,
+            schedule.activation,
+            eta,
+            schedule
+                .alpha_vault
+                .map(|vault| format!(", до неё свапы только через alpha vault {vault}"))
+                .unwrap_or_default(),
+        );
+
+        let controller = self.clone();
+        tokio::spawn(async move {
+            controller.launches.wait(&controller.args.load().launch, &schedule).await;
+            controller.launches.remove(&pool);
+
+            // Параметры на момент активации, а не постановки в расписание
+            let args = controller.args.load();
+            info!("Активация пула {}: отправка покупки", pool);
+            controller.buy(&args, venue, pool, token_a, token_b, amount).await;
+        });
+    }
+
+    async fn buy(
+        &self,
+        args: &PingThingsArgs,
+        venue: Venue,
+        pool: Pubkey,
+        token_a: Pubkey,
+        token_b: Pubkey,
+        amount: Lamports,
+    ) {
+        let Some(lease) = self.wallets.acquire(&pool, amount) else {
+            warn!("Нет кошелька с доступным бюджетом для пула: {}", pool);
+            return;
+        };
+        info!("Кошелёк для сделки: {} ({})", lease.wallet.name, lease.wallet.pubkey());
+        if let Some(reserves) = self.reserves.get(&pool) {
+            info!(
+                "Резервы пула {} по подписке: A {:?}, B {:?}, цена {:?}",
+                pool, reserves.reserve_a, reserves.reserve_b, reserves.price
+            );
+        }
+
+        // Без свободного nonce аккаунта сделка идёт с обычным recent blockhash
+        let nonce_account = if args.nonce.enabled {
+            self.nonces.acquire(&lease.wallet.pubkey())
+        } else {
+            None
+        };
+
+        let result = match venue {
+            Venue::MeteoraAmm => {
+                fetch_and_execute_swap(
+                    args,
+                    &self.fees,
+                    &self.compute,
+                    &self.lookup_tables,
+                    &self.reserves,
+                    &self.submissions,
+                    &lease.wallet.keypair,
+                    nonce_account,
+                    &pool,
+                    &token_a,
+                    &token_b,
+                    amount,
+                )
+                .await
+            }
+            Venue::MeteoraDlmm => {
+                fetch_and_execute_dlmm_swap(
+                    args,
+                    &self.fees,
+                    &self.compute,
+                    &self.lookup_tables,
+                    &self.reserves,
+                    &self.submissions,
+                    &lease.wallet.keypair,
+                    nonce_account,
+                    &pool,
+                    amount,
+                )
+                .await
+            }
+            Venue::MeteoraDammV2 => {
+                fetch_and_execute_damm_v2_swap(
+                    args,
+                    &self.fees,
+                    &self.compute,
+                    &self.lookup_tables,
+                    &self.reserves,
+                    self.launches.clock(),
+                    &self.submissions,
+                    &lease.wallet.keypair,
+                    nonce_account,
+                    &pool,
+                    amount,
+                )
+                .await
+            }
+            Venue::RaydiumAmmV4 => {
+                fetch_and_execute_raydium_amm_v4_swap(
+                    args,
+                    &self.fees,
+                    &self.compute,
+                    &self.lookup_tables,
+                    &self.reserves,
+                    &self.submissions,
+                    &lease.wallet.keypair,
+                    nonce_account,
+                    &pool,
+                    amount,
+                )
+                .await
+            }
+            Venue::RaydiumCpmm => {
+                fetch_and_execute_raydium_cpmm_swap(
+                    args,
+                    &self.fees,
+                    &self.compute,
+                    &self.lookup_tables,
+                    &self.reserves,
+                    &self.submissions,
+                    &lease.wallet.keypair,
+                    nonce_account,
+                    &pool,
+                    amount,
+                )
+                .await
+            }
+        };
+        if let Some(nonce_account) = &nonce_account {
+            self.nonces.release(nonce_account);
+        }
+
+        match result {
+            Ok(signature) => {
+                info!("Swap выполнен для пула: {}", pool);
+                if venue.is_raydium() {
+                    RAYDIUM_SWAP_SUCCESS.inc();
+                } else {
+                    METEORA_SWAP_SUCCESS.inc();
+                }
+                self.wallets.open_position(&lease, pool, Some(venue), Some(signature));
+
+                let controller = self.clone();
+                let wallet = lease.wallet.pubkey();
+                let mint = if token_a.to_string() == WSOL_MINT { token_b } else { token_a };
+                tokio::spawn(async move {
+                    controller.record_fill(signature, wallet, pool, mint).await;
+                });
+            }
+            Err(e) => {
+                warn!("Ошибка swap: {:?}", e);
+                if venue.is_raydium() {
+                    RAYDIUM_SWAP_FAILURE.inc();
+                } else {
+                    METEORA_SWAP_FAILURE.inc();
+                }
+                self.wallets.release(&lease);
+            }
+        }
+    }
+
+    /// Продажа позиций всех кошельков по пулу; проданная позиция закрывается.
+    /// Продавать умеет только Pump.fun, позиции в пулах AMM остаются открытыми.
+    async fn sell(&self, args: &PingThingsArgs, strategy: &str, pool: Pubkey) {
+        let holders = self.wallets.holders(&pool);
+        if holders.is_empty() {
+            debug!("Стратегия {}: нет открытых позиций по {}", strategy, pool);
+            return;
+        }
+
+        let rpc = RpcClient::new(args.http_rpc.clone());
+        for (wallet, position) in holders {
+            if let Some(venue) = position.venue {
+                warn!(
+                    "Стратегия {}: продажа в {:?} не поддерживается, позиция {} по пулу {} остаётся открытой",
+                    strategy, venue, wallet.name, pool
+                );
+                continue;
+            }
+            let Some(fill) = position.fill else {
+                warn!("Стратегия {}: исполнение покупки {} ещё не известно, продажа отложена", strategy, pool);
+                continue;
+            };
+
+            info!("Стратегия {}: продажа {} токенов {} с кошелька {}", strategy, fill.tokens_received, pool, wallet.name);
+            match sell_on_curve(args, &rpc, &self.submissions, &wallet, pool, fill.tokens_received).await {
+                Ok(signature) => {
+                    info!("Продажа {} подтверждена: {}", pool, signature);
+                    self.wallets.close_position(&wallet.pubkey(), &pool);
+                }
+                Err(e) => warn!("Продажа {} с кошелька {} не прошла: {:?}", pool, wallet.name, e),
+            }
+        }
+    }
+
+    /// Фактический результат покупки учитывается в позиции кошелька
+    async fn record_fill(&self, signature: Signature, wallet: Pubkey, pool: Pubkey, mint: Pubkey) {
+        let fill = self.submissions.fill(&signature, &wallet, &mint).await;
+        if !self.wallets.record_fill(&wallet, &pool, fill) {
+            warn!("Покупка {} в пуле {} не исполнена: позиция закрыта", signature, pool);
+        }
+    }
+}
+
+/// Фактические суммы из событий Meteora AMM вместо заявленных в инструкциях.
+/// Адреса пула в событиях нет: события и инструкции сопоставляются по порядку выполнения,
+/// и только если их число совпадает (логи не обрезаны).
+fn apply_event_amounts(
+    market_events: &mut [MarketEvent],
+    events: &[MeteoraEvent],
+    amm_swaps: &[usize],
+    amm_deposits: &[usize],
+) {
+    let swaps: Vec<u64> = events
+        .iter()
+        .filter_map(|event| match event {
+            MeteoraEvent::Swap(swap) => Some(swap.in_amount),
+            _ => None,
+        })
+        .collect();
+    if swaps.len() == amm_swaps.len() {
+        for (&index, amount) in amm_swaps.iter().zip(swaps) {
+            if let MarketEvent::Swap { in_amount, .. } = &mut market_events[index] {
+                *in_amount = amount;
+            }
+        }
+    }
+
+    let deposits: Vec<u64> = events
+        .iter()
+        .filter_map(|event| match event {
+            MeteoraEvent::AddLiquidity(added) => Some(added.lp_mint_amount),
+            _ => None,
+        })
+        .collect();
+    if deposits.len() == amm_deposits.len() {
+        for (&index, amount) in amm_deposits.iter().zip(deposits) {
+            if let MarketEvent::LiquidityAdded { pool_token_amount, .. } = &mut market_events[index] {
+                *pool_token_amount = amount;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meteora::events::{AddLiquidity, Swap};
+
+    fn swap_event(in_amount: u64) -> MeteoraEvent {
+        MeteoraEvent::Swap(Swap { in_amount, out_amount: 0, trade_fee: 0, protocol_fee: 0, host_fee: 0 })
+    }
+
+    fn in_amounts(market_events: &[MarketEvent]) -> Vec<u64> {
+        market_events
+            .iter()
+            .filter_map(|event| match event {
+                MarketEvent::Swap { in_amount, .. } => Some(*in_amount),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn event_amounts_replace_instruction_amounts() {
+        let (pool, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut market_events = vec![
+            MarketEvent::Swap { pool, user, in_amount: 100 },
+            MarketEvent::LiquidityAdded { pool, user, pool_token_amount: 1 },
+            MarketEvent::Swap { pool, user, in_amount: 200 },
+        ];
+        let events = [
+            swap_event(90),
+            MeteoraEvent::AddLiquidity(AddLiquidity { lp_mint_amount: 7, token_a_amount: 0, token_b_amount: 0 }),
+            swap_event(180),
+        ];
+
+        apply_event_amounts(&mut market_events, &events, &[0, 2], &[1]);
+
+        assert_eq!(in_amounts(&market_events), [90, 180]);
+        assert!(matches!(market_events[1], MarketEvent::LiquidityAdded { pool_token_amount: 7, .. }));
+    }
+
+    #[test]
+    fn truncated_logs_keep_instruction_amounts() {
+        let (pool, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut market_events = vec![
+            MarketEvent::Swap { pool, user, in_amount: 100 },
+            MarketEvent::Swap { pool, user, in_amount: 200 },
+        ];
+
+        apply_event_amounts(&mut market_events, &[swap_event(90)], &[0, 1], &[]);
+
+        assert_eq!(in_amounts(&market_events), [100, 200], "события не сопоставимы с инструкциями");
+    }
+}
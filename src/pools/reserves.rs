@@ -0,0 +1,617 @@
+use crate::core::accounts::{read_u64, token_amount, unix_now, MINT_SUPPLY_OFFSET};
+use crate::meteora::damm_v2::Pool as DammV2Pool;
+use crate::meteora::dlmm::{bin_price, LbPair};
+use crate::meteora::fetch_pool::{fetch_vaults, pool_share, PoolAccountData, Vault};
+use crate::raydium::amm_v4::{fetch_amm, AmmInfo};
+use crate::raydium::cpmm::{fetch_pool as fetch_cpmm_pool, PoolState as CpmmPool};
+use crate::strategy::Venue;
+use crate::metrics::RESERVE_TRACKED_POOLS;
+
+use anyhow::{anyhow, Result};
+use borsh::BorshDeserialize;
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+use std::time::Instant;
+use tracing::warn;
+
+/// Q64.64 -> f64
+const Q64: f64 = 18_446_744_073_709_551_616.0;
+
+/// Роль аккаунта в расчёте резервов; индекс — сторона пула (0 — A/X, 1 — B/Y)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    DlmmPair,
+    DammV2Pool,
+    RaydiumAmmV4Pool,
+    RaydiumCpmmPool,
+    /// Токен-аккаунт, в котором лежит резерв стороны
+    TokenReserve(usize),
+    /// Хранилище vault program
+    Vault(usize),
+    /// LP-токены хранилища, принадлежащие пулу Dynamic AMM
+    VaultLp(usize),
+    /// Mint LP-токенов хранилища
+    VaultLpMint(usize),
+}
+
+/// Последние значения из аккаунтов пула
+#[derive(Debug, Default)]
+struct PoolState {
+    token_reserves: [Option<u64>; 2],
+    vaults: [Option<Vault>; 2],
+    vault_lp: [Option<u64>; 2],
+    vault_lp_supply: [Option<u64>; 2],
+    lb_pair: Option<LbPair>,
+    damm_v2: Option<DammV2Pool>,
+    amm_v4: Option<AmmInfo>,
+    cpmm: Option<CpmmPool>,
+}
+
+impl PoolState {
+    fn reserve(&self, venue: Venue, side: usize, now: u64) -> Option<u64> {
+        match venue {
+            // Доля пула в хранилище: его LP-токены к эмиссии LP хранилища
+            Venue::MeteoraAmm => {
+                pool_share(self.vaults[side].as_ref()?, self.vault_lp[side]?, self.vault_lp_supply[side]?, now)
+            }
+            // Балансы хранилищ без PnL и накопленных комиссий, как при котировке
+            Venue::RaydiumAmmV4 => {
+                let (coin, pc) = self.amm_v4.as_ref()?.reserves(self.token_reserves[0]?, self.token_reserves[1]?);
+                Some([coin, pc][side])
+            }
+            Venue::RaydiumCpmm => {
+                let (reserve_0, reserve_1) =
+                    self.cpmm.as_ref()?.reserves(self.token_reserves[0]?, self.token_reserves[1]?);
+                Some([reserve_0, reserve_1][side])
+            }
+            _ => self.token_reserves[side],
+        }
+    }
+
+    /// Цена токена A в токенах B без учёта decimals
+    fn price(&self, venue: Venue, reserves: [Option<u64>; 2]) -> Option<f64> {
+        match venue {
+            Venue::MeteoraDlmm => {
+                let pair = self.lb_pair.as_ref()?;
+                Some(bin_price(pair.active_id, pair.bin_step)? as f64 / Q64)
+            }
+            Venue::MeteoraDammV2 => {
+                let sqrt_price = self.damm_v2.as_ref()?.sqrt_price as f64 / Q64;
+                Some(sqrt_price * sqrt_price)
+            }
+            // Оценка по формуле x * y = k; для stable-пулов неточна
+            _ => {
+                let (reserve_a, reserve_b) = (reserves[0]?, reserves[1]?);
+                (reserve_a > 0).then(|| reserve_b as f64 / reserve_a as f64)
+            }
+        }
+    }
+}
+
+struct TrackedPool {
+    venue: Venue,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    accounts: Vec<(Pubkey, Role)>,
+    state: PoolState,
+    tracked_at: Instant,
+    updated_at: Option<Instant>,
+}
+
+/// Резервы и цена пула из кэша
+#[derive(Debug, Clone)]
+pub struct PoolReserves {
+    pub venue: Venue,
+    pub pool: Pubkey,
+    pub token_a: Pubkey,
+    pub token_b: Pubkey,
+    pub reserve_a: Option<u64>,
+    pub reserve_b: Option<u64>,
+    /// Цена токена A в токенах B без учёта decimals
+    pub price: Option<f64>,
+    /// Время последнего обновления любого из аккаунтов пула
+    pub updated_at: Option<Instant>,
+}
+
+/// Резервы пула в ответе admin API
+#[derive(Debug, Serialize)]
+pub struct ReserveReport {
+    pub venue: String,
+    pub pool: String,
+    pub token_a: String,
+    pub token_b: String,
+    pub reserve_a: Option<u64>,
+    pub reserve_b: Option<u64>,
+    pub price: Option<f64>,
+    /// Сколько миллисекунд назад пришло последнее обновление
+    pub age_ms: Option<u64>,
+}
+
+/// Кэш резервов пулов Meteora и Raydium. Аккаунты пула и хранилищ читаются по RPC один раз
+/// при постановке на учёт, дальше обновляются из account-потока Geyser,
+/// поэтому котировки покупок и сопровождение позиций не ходят в RPC, пока пул отслеживается.
+#[derive(Default)]
+pub struct ReserveCache {
+    pools: RwLock<HashMap<Pubkey, TrackedPool>>,
+    /// Аккаунт -> пулы, которые его используют (хранилища Dynamic AMM общие для пулов)
+    accounts: RwLock<HashMap<Pubkey, Vec<(Pubkey, Role)>>>,
+}
+
+impl ReserveCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_tracked(&self, pool: &Pubkey) -> bool {
+        self.pools.read().unwrap().contains_key(pool)
+    }
+
+    /// Постановка пула на учёт: адреса аккаунтов из состояния пула и начальные значения по RPC.
+    /// Возвращает аккаунты для подписки.
+    pub async fn track(
+        &self,
+        rpc: &RpcClient,
+        venue: Venue,
+        pool: Pubkey,
+        token_a: Pubkey,
+        token_b: Pubkey,
+    ) -> Result<Vec<Pubkey>> {
+        if self.is_tracked(&pool) {
+            return Ok(Vec::new());
+        }
+
+        let accounts = match venue {
+            Venue::MeteoraAmm => dynamic_amm_accounts(rpc, &pool).await?,
+            Venue::MeteoraDlmm => {
+                let pair = decode_lb_pair(&rpc.get_account_data(&pool).await?)?;
+                vec![
+                    (pool, Role::DlmmPair),
+                    (pair.reserve_x, Role::TokenReserve(0)),
+                    (pair.reserve_y, Role::TokenReserve(1)),
+                ]
+            }
+            Venue::MeteoraDammV2 => {
+                let state = decode_damm_v2_pool(&rpc.get_account_data(&pool).await?)?;
+                vec![
+                    (pool, Role::DammV2Pool),
+                    (state.token_a_vault, Role::TokenReserve(0)),
+                    (state.token_b_vault, Role::TokenReserve(1)),
+                ]
+            }
+            Venue::RaydiumAmmV4 => {
+                let amm = fetch_amm(rpc, &pool).await?;
+                vec![
+                    (pool, Role::RaydiumAmmV4Pool),
+                    (amm.coin_vault, Role::TokenReserve(0)),
+                    (amm.pc_vault, Role::TokenReserve(1)),
+                ]
+            }
+            Venue::RaydiumCpmm => {
+                let state = fetch_cpmm_pool(rpc, &pool).await?;
+                vec![
+                    (pool, Role::RaydiumCpmmPool),
+                    (state.token_0_vault, Role::TokenReserve(0)),
+                    (state.token_1_vault, Role::TokenReserve(1)),
+                ]
+            }
+        };
+
+        if !self.insert(venue, pool, token_a, token_b, &accounts) {
+            return Ok(Vec::new());
+        }
+
+        // Начальные значения: Geyser присылает только изменения
+        let addresses: Vec<Pubkey> = accounts.iter().map(|(account, _)| *account).collect();
+        for (address, account) in addresses.iter().zip(rpc.get_multiple_accounts(&addresses).await?) {
+            if let Some(account) = account {
+                self.update(address, &account.data);
+            }
+        }
+
+        Ok(addresses)
+    }
+
+    /// Постановка на учёт без начальных значений; `false` — пул уже отслеживается.
+    /// Повторная проверка под блокировкой: постановки на учёт идут из фоновых задач параллельно.
+    fn insert(&self, venue: Venue, pool: Pubkey, token_a: Pubkey, token_b: Pubkey, accounts: &[(Pubkey, Role)]) -> bool {
+        let mut pools = self.pools.write().unwrap();
+        if pools.contains_key(&pool) {
+            return false;
+        }
+        let mut index = self.accounts.write().unwrap();
+        for (account, role) in accounts {
+            index.entry(*account).or_default().push((pool, *role));
+        }
+        pools.insert(
+            pool,
+            TrackedPool {
+                venue,
+                token_a,
+                token_b,
+                accounts: accounts.to_vec(),
+                state: PoolState::default(),
+                tracked_at: Instant::now(),
+                updated_at: None,
+            },
+        );
+        RESERVE_TRACKED_POOLS.set(pools.len() as i64);
+        true
+    }
+
+    /// Снятие пула с учёта; возвращает аккаунты, которые больше не нужны ни одному пулу
+    pub fn untrack(&self, pool: &Pubkey) -> Vec<Pubkey> {
+        let tracked = {
+            let mut pools = self.pools.write().unwrap();
+            let Some(tracked) = pools.remove(pool) else {
+                return Vec::new();
+            };
+            RESERVE_TRACKED_POOLS.set(pools.len() as i64);
+            tracked
+        };
+
+        let mut index = self.accounts.write().unwrap();
+        let mut released = Vec::new();
+        for (account, _) in tracked.accounts {
+            if let Some(users) = index.get_mut(&account) {
+                users.retain(|(user, _)| user != pool);
+                if users.is_empty() {
+                    index.remove(&account);
+                    released.push(account);
+                }
+            }
+        }
+        released
+    }
+
+    /// Самые давно отслеживаемые пулы сверх лимита `max_pools`; пулы из `keep` не вытесняются
+    pub fn excess(&self, max_pools: usize, keep: &HashSet<Pubkey>) -> Vec<Pubkey> {
+        let pools = self.pools.read().unwrap();
+        let mut by_age: Vec<(&Pubkey, Instant)> = pools
+            .iter()
+            .filter(|(pool, _)| !keep.contains(*pool))
+            .map(|(pool, tracked)| (pool, tracked.tracked_at))
+            .collect();
+        by_age.sort_by_key(|(_, tracked_at)| *tracked_at);
+        by_age
+            .into_iter()
+            .take(pools.len().saturating_sub(max_pools))
+            .map(|(pool, _)| *pool)
+            .collect()
+    }
+
+    /// Account-обновление из Geyser; чужие аккаунты игнорируются
+    pub fn update(&self, account: &Pubkey, data: &[u8]) {
+        let Some(users) = self.accounts.read().unwrap().get(account).cloned() else {
+            return;
+        };
+
+        let mut pools = self.pools.write().unwrap();
+        for (pool, role) in users {
+            let Some(tracked) = pools.get_mut(&pool) else {
+                continue;
+            };
+            let state = &mut tracked.state;
+            let applied = match role {
+                Role::DlmmPair => decode_lb_pair(data).map(|pair| state.lb_pair = Some(pair)),
+                Role::DammV2Pool => decode_damm_v2_pool(data).map(|damm| state.damm_v2 = Some(damm)),
+                Role::RaydiumAmmV4Pool => decode_amm_v4(data).map(|amm| state.amm_v4 = Some(amm)),
+                Role::RaydiumCpmmPool => decode_cpmm_pool(data).map(|cpmm| state.cpmm = Some(cpmm)),
+                Role::TokenReserve(side) => token_amount(data).map(|amount| state.token_reserves[side] = Some(amount)),
+                Role::Vault(side) => Vault::decode(data).map(|vault| state.vaults[side] = Some(vault)),
+                Role::VaultLp(side) => token_amount(data).map(|amount| state.vault_lp[side] = Some(amount)),
+                Role::VaultLpMint(side) => {
+                    read_u64(data, MINT_SUPPLY_OFFSET).map(|supply| state.vault_lp_supply[side] = Some(supply))
+                }
+            };
+
+            match applied {
+                Ok(()) => tracked.updated_at = Some(Instant::now()),
+                Err(e) => warn!("Не удалось разобрать аккаунт {} пула {}: {:?}", account, pool, e),
+            }
+        }
+    }
+
+    pub fn get(&self, pool: &Pubkey) -> Option<PoolReserves> {
+        let pools = self.pools.read().unwrap();
+        pools.get(pool).map(|tracked| reserves(pool, tracked, unix_now()))
+    }
+
+    /// Пара DLMM по подписке; `None` — пара не отслеживается или аккаунт ещё не пришёл
+    pub fn lb_pair(&self, pool: &Pubkey) -> Option<LbPair> {
+        self.state(pool, |state| state.lb_pair.clone())
+    }
+
+    pub fn damm_v2_pool(&self, pool: &Pubkey) -> Option<DammV2Pool> {
+        self.state(pool, |state| state.damm_v2.clone())
+    }
+
+    /// Хранилища Dynamic AMM и резервы пула в них
+    pub fn meteora_amm(&self, pool: &Pubkey) -> Option<([Vault; 2], [u64; 2])> {
+        let now = unix_now();
+        self.state(pool, |state| {
+            let vaults = [state.vaults[0].clone()?, state.vaults[1].clone()?];
+            let reserves = [state.reserve(Venue::MeteoraAmm, 0, now)?, state.reserve(Venue::MeteoraAmm, 1, now)?];
+            Some((vaults, reserves))
+        })
+    }
+
+    /// Пул AMM v4 и балансы хранилищ coin и pc
+    pub fn raydium_amm_v4(&self, pool: &Pubkey) -> Option<(AmmInfo, [u64; 2])> {
+        self.state(pool, |state| {
+            Some((state.amm_v4.clone()?, [state.token_reserves[0]?, state.token_reserves[1]?]))
+        })
+    }
+
+    /// Пул CPMM и балансы хранилищ token_0 и token_1
+    pub fn raydium_cpmm(&self, pool: &Pubkey) -> Option<(CpmmPool, [u64; 2])> {
+        self.state(pool, |state| {
+            Some((state.cpmm.clone()?, [state.token_reserves[0]?, state.token_reserves[1]?]))
+        })
+    }
+
+    fn state<T>(&self, pool: &Pubkey, read: impl FnOnce(&PoolState) -> Option<T>) -> Option<T> {
+        self.pools.read().unwrap().get(pool).and_then(|tracked| read(&tracked.state))
+    }
+
+    /// Все отслеживаемые пулы, последние обновлённые первыми
+    pub fn reports(&self) -> Vec<ReserveReport> {
+        let now = unix_now();
+        let pools = self.pools.read().unwrap();
+        let mut reserves: Vec<PoolReserves> = pools.iter().map(|(pool, tracked)| reserves(pool, tracked, now)).collect();
+        reserves.sort_by_key(|reserves| std::cmp::Reverse(reserves.updated_at));
+
+        reserves
+            .into_iter()
+            .map(|reserves| ReserveReport {
+                venue: format!("{:?}", reserves.venue),
+                pool: reserves.pool.to_string(),
+                token_a: reserves.token_a.to_string(),
+                token_b: reserves.token_b.to_string(),
+                reserve_a: reserves.reserve_a,
+                reserve_b: reserves.reserve_b,
+                price: reserves.price,
+                age_ms: reserves.updated_at.map(|at| at.elapsed().as_millis() as u64),
+            })
+            .collect()
+    }
+}
+
+fn reserves(pool: &Pubkey, tracked: &TrackedPool, now: u64) -> PoolReserves {
+    let state = &tracked.state;
+    let reserves = [state.reserve(tracked.venue, 0, now), state.reserve(tracked.venue, 1, now)];
+    PoolReserves {
+        venue: tracked.venue,
+        pool: *pool,
+        token_a: tracked.token_a,
+        token_b: tracked.token_b,
+        reserve_a: reserves[0],
+        reserve_b: reserves[1],
+        price: state.price(tracked.venue, reserves),
+        updated_at: tracked.updated_at,
+    }
+}
+
+/// Аккаунты Dynamic AMM: хранилища, LP-токены пула в них и LP mint хранилищ.
+/// Сам аккаунт пула резервов не содержит и не отслеживается.
+async fn dynamic_amm_accounts(rpc: &RpcClient, pool: &Pubkey) -> Result<Vec<(Pubkey, Role)>> {
+    let info = PoolAccountData::decode(&rpc.get_account_data(pool).await?)?;
+    let [vault_a, vault_b] = fetch_vaults(rpc, &info).await?;
+
+    Ok(vec![
+        (info.a_vault, Role::Vault(0)),
+        (info.b_vault, Role::Vault(1)),
+        (info.a_vault_lp, Role::VaultLp(0)),
+        (info.b_vault_lp, Role::VaultLp(1)),
+        (vault_a.lp_mint, Role::VaultLpMint(0)),
+        (vault_b.lp_mint, Role::VaultLpMint(1)),
+    ])
+}
+
+fn anchor_body(data: &[u8]) -> Result<&[u8]> {
+    data.get(8..).ok_or_else(|| anyhow!("аккаунт короче дискриминатора"))
+}
+
+fn decode_lb_pair(data: &[u8]) -> Result<LbPair> {
+    LbPair::deserialize(&mut anchor_body(data)?).map_err(|e| anyhow!("пара DLMM: {e}"))
+}
+
+fn decode_damm_v2_pool(data: &[u8]) -> Result<DammV2Pool> {
+    DammV2Pool::deserialize(&mut anchor_body(data)?).map_err(|e| anyhow!("пул DAMM v2: {e}"))
+}
+
+fn decode_amm_v4(data: &[u8]) -> Result<AmmInfo> {
+    AmmInfo::deserialize(&mut &data[..]).map_err(|e| anyhow!("пул AMM v4: {e}"))
+}
+
+fn decode_cpmm_pool(data: &[u8]) -> Result<CpmmPool> {
+    CpmmPool::deserialize(&mut anchor_body(data)?).map_err(|e| anyhow!("пул CPMM: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::accounts::TOKEN_AMOUNT_OFFSET;
+    use std::time::Duration;
+
+    fn zeroed<T: BorshDeserialize>() -> T {
+        T::deserialize(&mut &[0u8; 2048][..]).unwrap()
+    }
+
+    fn token_account(amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 165];
+        data[TOKEN_AMOUNT_OFFSET..TOKEN_AMOUNT_OFFSET + 8].copy_from_slice(&amount.to_le_bytes());
+        data
+    }
+
+    fn mint(supply: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 82];
+        data[MINT_SUPPLY_OFFSET..MINT_SUPPLY_OFFSET + 8].copy_from_slice(&supply.to_le_bytes());
+        data
+    }
+
+    /// Хранилище без заблокированной прибыли: `total_amount` после дискриминатора, enabled и bumps
+    fn vault(total_amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 2048];
+        data[11..19].copy_from_slice(&total_amount.to_le_bytes());
+        data
+    }
+
+    fn insert(cache: &ReserveCache, venue: Venue, accounts: &[(Pubkey, Role)]) -> Pubkey {
+        let pool = Pubkey::new_unique();
+        assert!(cache.insert(venue, pool, Pubkey::new_unique(), Pubkey::new_unique(), accounts));
+        pool
+    }
+
+    fn dynamic_amm_accounts() -> Vec<(Pubkey, Role)> {
+        vec![
+            (Pubkey::new_unique(), Role::Vault(0)),
+            (Pubkey::new_unique(), Role::Vault(1)),
+            (Pubkey::new_unique(), Role::VaultLp(0)),
+            (Pubkey::new_unique(), Role::VaultLp(1)),
+            (Pubkey::new_unique(), Role::VaultLpMint(0)),
+            (Pubkey::new_unique(), Role::VaultLpMint(1)),
+        ]
+    }
+
+    #[test]
+    fn dynamic_amm_reserves_are_pool_share_of_vaults() {
+        let cache = ReserveCache::new();
+        let accounts = dynamic_amm_accounts();
+        let pool = insert(&cache, Venue::MeteoraAmm, &accounts);
+
+        let updates = [vault(1_000), vault(4_000), token_account(250), token_account(500), mint(1_000), mint(1_000)];
+        for ((account, _), data) in accounts.iter().zip(&updates) {
+            assert!(cache.meteora_amm(&pool).is_none(), "резервы до прихода всех аккаунтов");
+            cache.update(account, data);
+        }
+
+        let reserves = cache.get(&pool).unwrap();
+        assert_eq!((reserves.reserve_a, reserves.reserve_b), (Some(250), Some(2_000)));
+        assert_eq!(reserves.price, Some(8.0));
+        assert!(reserves.updated_at.is_some());
+
+        let (vaults, amounts) = cache.meteora_amm(&pool).unwrap();
+        assert_eq!((vaults[0].total_amount, vaults[1].total_amount), (1_000, 4_000));
+        assert_eq!(amounts, [250, 2_000]);
+    }
+
+    #[test]
+    fn shared_vault_updates_every_pool_and_is_released_last() {
+        let cache = ReserveCache::new();
+        let first_accounts = dynamic_amm_accounts();
+        let mut second_accounts = dynamic_amm_accounts();
+        // Хранилище A общее для обоих пулов
+        second_accounts[0].0 = first_accounts[0].0;
+        let first = insert(&cache, Venue::MeteoraAmm, &first_accounts);
+        let second = insert(&cache, Venue::MeteoraAmm, &second_accounts);
+
+        cache.update(&first_accounts[0].0, &vault(1_000));
+        for pool in [first, second] {
+            assert!(cache.get(&pool).unwrap().updated_at.is_some(), "обновление хранилища для пула {pool}");
+        }
+
+        let released = cache.untrack(&first);
+        assert_eq!(released.len(), 5, "общее хранилище ещё нужно второму пулу");
+        assert!(!released.contains(&first_accounts[0].0));
+        assert!(cache.untrack(&first).is_empty(), "повторное снятие");
+        assert_eq!(cache.untrack(&second).len(), 6);
+    }
+
+    #[test]
+    fn raydium_reserves_exclude_pnl_and_fees() {
+        let cache = ReserveCache::new();
+        let (coin_vault, pc_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let accounts = [(coin_vault, Role::TokenReserve(0)), (pc_vault, Role::TokenReserve(1))];
+        let amm_v4 = insert(&cache, Venue::RaydiumAmmV4, &accounts);
+        let cpmm = insert(&cache, Venue::RaydiumCpmm, &accounts);
+        cache.update(&coin_vault, &token_account(10_000));
+        cache.update(&pc_vault, &token_account(20_000));
+        assert_eq!(cache.get(&amm_v4).unwrap().reserve_a, None, "без аккаунта пула резервов нет");
+
+        let mut amm: AmmInfo = zeroed();
+        amm.state_data.need_take_pnl_coin = 1_000;
+        amm.state_data.need_take_pnl_pc = 5_000;
+        let mut pool: CpmmPool = zeroed();
+        pool.protocol_fees_token_0 = 100;
+        pool.fund_fees_token_1 = 500;
+        {
+            let mut pools = cache.pools.write().unwrap();
+            pools.get_mut(&amm_v4).unwrap().state.amm_v4 = Some(amm);
+            pools.get_mut(&cpmm).unwrap().state.cpmm = Some(pool);
+        }
+
+        let reserves = cache.get(&amm_v4).unwrap();
+        assert_eq!((reserves.reserve_a, reserves.reserve_b), (Some(9_000), Some(15_000)));
+        let reserves = cache.get(&cpmm).unwrap();
+        assert_eq!((reserves.reserve_a, reserves.reserve_b), (Some(9_900), Some(19_500)));
+        // Для котировки кэш отдаёт балансы хранилищ как есть
+        assert_eq!(cache.raydium_amm_v4(&amm_v4).unwrap().1, [10_000, 20_000]);
+    }
+
+    #[test]
+    fn price_by_venue() {
+        let mut state = PoolState::default();
+        let mut damm_v2: DammV2Pool = zeroed();
+        damm_v2.sqrt_price = 2 << 64;
+        state.damm_v2 = Some(damm_v2);
+        state.lb_pair = Some(zeroed());
+
+        let cases = [
+            // (площадка, резервы, ожидаемая цена)
+            (Venue::MeteoraAmm, [Some(1_000), Some(8_000)], Some(8.0)),
+            (Venue::RaydiumCpmm, [Some(0), Some(8_000)], None),
+            (Venue::RaydiumAmmV4, [None, Some(8_000)], None),
+            (Venue::MeteoraDammV2, [None, None], Some(4.0)),
+            // Активный бин 0: цена 1 независимо от шага
+            (Venue::MeteoraDlmm, [None, None], Some(1.0)),
+        ];
+        for (venue, reserves, expected) in cases {
+            assert_eq!(state.price(venue, reserves), expected, "цена для {venue:?}");
+        }
+    }
+
+    #[test]
+    fn malformed_and_foreign_accounts_are_ignored() {
+        let cache = ReserveCache::new();
+        let reserve = Pubkey::new_unique();
+        let pool = insert(&cache, Venue::MeteoraDlmm, &[(reserve, Role::TokenReserve(0))]);
+
+        cache.update(&Pubkey::new_unique(), &token_account(1_000));
+        cache.update(&reserve, &[0u8; 10]);
+        let reserves = cache.get(&pool).unwrap();
+        assert_eq!(reserves.reserve_a, None);
+        assert!(reserves.updated_at.is_none(), "ошибка разбора не считается обновлением");
+
+        cache.update(&reserve, &token_account(1_000));
+        assert_eq!(cache.get(&pool).unwrap().reserve_a, Some(1_000));
+    }
+
+    #[test]
+    fn excess_evicts_oldest_pools_except_kept() {
+        let cache = ReserveCache::new();
+        let pools: Vec<Pubkey> = (0..3).map(|_| insert(&cache, Venue::MeteoraDlmm, &[])).collect();
+        assert!(!cache.insert(Venue::MeteoraDlmm, pools[0], Pubkey::new_unique(), Pubkey::new_unique(), &[]));
+        {
+            let start = Instant::now();
+            let mut tracked = cache.pools.write().unwrap();
+            for (age, pool) in pools.iter().enumerate() {
+                tracked.get_mut(pool).unwrap().tracked_at = start + Duration::from_secs(age as u64);
+            }
+        }
+
+        let keep = HashSet::from([pools[0]]);
+        let cases = [
+            // (лимит, ожидаемые вытесненные)
+            (3, vec![]),
+            (2, vec![pools[1]]),
+            (1, vec![pools[1], pools[2]]),
+            (0, vec![pools[1], pools[2]]),
+        ];
+        for (max_pools, expected) in cases {
+            assert_eq!(cache.excess(max_pools, &keep), expected, "вытеснение при лимите {max_pools}");
+        }
+    }
+}
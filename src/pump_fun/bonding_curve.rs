@@ -0,0 +1,282 @@
+use crate::config::Lamports;
+use crate::tx_senders::constants::{PUMP_FUN_ACCOUNT_ADDR, PUMP_FUN_PROGRAM_ADDR};
+
+use anyhow::{anyhow, bail, Context, Result};
+use borsh::BorshDeserialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+use std::str::FromStr;
+
+const BPS: u128 = 10_000;
+
+/// Дискриминатор аккаунта Anchor: sha256("account:BondingCurve")[..8]
+pub const BONDING_CURVE_DISCRIMINATOR: [u8; 8] = [23, 183, 248, 55, 96, 216, 172, 96];
+
+/// Глобальные параметры программы Pump.fun (аккаунт `Global`)
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct Global {
+    pub initialized: bool,
+    pub authority: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub token_total_supply: u64,
+    pub fee_basis_points: u64,
+}
+
+/// Состояние bonding curve токена
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct BondingCurve {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub token_total_supply: u64,
+    /// Кривая заполнена, ликвидность переносится на AMM, торговля на кривой закрыта
+    pub complete: bool,
+}
+
+impl BondingCurve {
+    /// Кривая только что созданного токена: аккаунт ещё может быть не виден через RPC
+    pub fn initial(global: &Global) -> Self {
+        Self {
+            virtual_token_reserves: global.initial_virtual_token_reserves,
+            virtual_sol_reserves: global.initial_virtual_sol_reserves,
+            real_token_reserves: global.initial_real_token_reserves,
+            real_sol_reserves: 0,
+            token_total_supply: global.token_total_supply,
+            complete: false,
+        }
+    }
+
+    /// Аккаунт bonding curve из обновления Geyser; `None` для прочих аккаунтов программы
+    pub fn from_account_data(data: &[u8]) -> Option<Self> {
+        if data.get(..8)? != BONDING_CURVE_DISCRIMINATOR {
+            return None;
+        }
+        Self::deserialize(&mut &data[8..]).ok()
+    }
+
+    /// Доля проданных с кривой токенов, проценты; начальный запас берётся из Global
+    pub fn progress_percent(&self, global: &Global) -> u8 {
+        if self.complete || global.initial_real_token_reserves == 0 {
+            return 100;
+        }
+        let initial = global.initial_real_token_reserves as u128;
+        let sold = initial.saturating_sub(self.real_token_reserves as u128);
+        (sold * 100 / initial).min(100) as u8
+    }
+
+    /// Токены, получаемые за `sol_amount` с учётом комиссии программы
+    pub fn buy_quote(&self, sol_amount: Lamports, fee_basis_points: u64) -> u64 {
+        if self.complete || sol_amount.0 == 0 {
+            return 0;
+        }
+
+        // Комиссия берётся сверху стоимости, поэтому на кривую уходит sol / (1 + fee)
+        let sol_in = sol_amount.0 as u128 * BPS / (BPS + fee_basis_points as u128);
+        let virtual_sol = self.virtual_sol_reserves as u128;
+        let virtual_token = self.virtual_token_reserves as u128;
+        let tokens = virtual_token * sol_in / (virtual_sol + sol_in);
+
+        tokens.min(self.real_token_reserves as u128) as u64
+    }
+
+    /// Полная стоимость покупки ровно `token_amount` токенов (как её считает программа)
+    pub fn buy_cost(&self, token_amount: u64, fee_basis_points: u64) -> Option<Lamports> {
+        let sol_cost = self.buy_price(token_amount)?;
+        let fee = sol_cost * fee_basis_points as u128 / BPS;
+
+        u64::try_from(sol_cost + fee).ok().map(Lamports)
+    }
+
+    /// Покупка, уже исполненная на кривой (dev buy в транзакции создания токена)
+    pub fn apply_buy(&mut self, token_amount: u64) {
+        // Программа урезает покупку до остатка реальных резервов
+        let token_amount = token_amount.min(self.real_token_reserves);
+        let Some(sol_cost) = self.buy_price(token_amount).and_then(|cost| u64::try_from(cost).ok()) else {
+            return;
+        };
+
+        self.virtual_token_reserves -= token_amount;
+        self.real_token_reserves -= token_amount;
+        self.virtual_sol_reserves += sol_cost;
+        self.real_sol_reserves += sol_cost;
+    }
+
+    /// Стоимость `token_amount` токенов без комиссии, округлённая вверх
+    fn buy_price(&self, token_amount: u64) -> Option<u128> {
+        if self.complete || token_amount == 0 || token_amount >= self.virtual_token_reserves {
+            return None;
+        }
+
+        let virtual_sol = self.virtual_sol_reserves as u128;
+        let virtual_token = self.virtual_token_reserves as u128;
+        let amount = token_amount as u128;
+        Some(virtual_sol * amount / (virtual_token - amount) + 1)
+    }
+
+    /// SOL, получаемые за продажу `token_amount` токенов, за вычетом комиссии
+    pub fn sell_quote(&self, token_amount: u64, fee_basis_points: u64) -> Lamports {
+        if self.complete || token_amount == 0 {
+            return Lamports(0);
+        }
+
+        let virtual_sol = self.virtual_sol_reserves as u128;
+        let virtual_token = self.virtual_token_reserves as u128;
+        let amount = token_amount as u128;
+        let sol_out = amount * virtual_sol / (virtual_token + amount);
+        let fee = sol_out * fee_basis_points as u128 / BPS;
+
+        Lamports((sol_out - fee) as u64)
+    }
+}
+
+/// Максимальная стоимость покупки: котировка плюс допуск `slippage_bps`
+pub fn max_sol_cost(quote: Lamports, slippage_bps: u16) -> Lamports {
+    Lamports((quote.0 as u128 * (BPS + slippage_bps as u128) / BPS) as u64)
+}
+
+/// Минимальная выручка продажи: котировка минус допуск `slippage_bps`
+pub fn min_sol_output(quote: Lamports, slippage_bps: u16) -> Lamports {
+    Lamports((quote.0 as u128 * BPS.saturating_sub(slippage_bps as u128) / BPS) as u64)
+}
+
+/// PDA bonding curve токена и её токен-аккаунт
+pub fn bonding_curve_accounts(mint: &Pubkey) -> (Pubkey, Pubkey) {
+    let program_id = Pubkey::from_str(PUMP_FUN_PROGRAM_ADDR).unwrap();
+    let (bonding_curve, _) = Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], &program_id);
+    let associated_bonding_curve = get_associated_token_address(&bonding_curve, mint);
+    (bonding_curve, associated_bonding_curve)
+}
+
+/// Данные Anchor-аккаунта без 8-байтового дискриминатора; новые версии программы дописывают поля в конец
+fn decode_account<T: BorshDeserialize>(address: &Pubkey, data: &[u8]) -> Result<T> {
+    let mut body = data
+        .get(8..)
+        .ok_or_else(|| anyhow!("аккаунт {address} слишком короткий"))?;
+    T::deserialize(&mut body).map_err(|e| anyhow!("не удалось разобрать аккаунт {address}: {e}"))
+}
+
+pub async fn fetch_global(rpc: &RpcClient) -> Result<Global> {
+    let address = Pubkey::from_str(PUMP_FUN_ACCOUNT_ADDR).unwrap();
+    let account = rpc
+        .get_account(&address)
+        .await
+        .with_context(|| format!("аккаунт Global Pump.fun {address} не найден"))?;
+
+    let global: Global = decode_account(&address, &account.data)?;
+    if !global.initialized {
+        bail!("аккаунт Global Pump.fun не инициализирован");
+    }
+    Ok(global)
+}
+
+pub async fn fetch_bonding_curve(rpc: &RpcClient, address: &Pubkey) -> Result<BondingCurve> {
+    let account = rpc
+        .get_account(address)
+        .await
+        .with_context(|| format!("bonding curve {address} не найдена"))?;
+
+    decode_account(address, &account.data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEE_BPS: u64 = 100;
+
+    fn global() -> Global {
+        Global {
+            initialized: true,
+            authority: Pubkey::default(),
+            fee_recipient: Pubkey::default(),
+            initial_virtual_token_reserves: 1_073_000_000_000_000,
+            initial_virtual_sol_reserves: 30_000_000_000,
+            initial_real_token_reserves: 793_100_000_000_000,
+            token_total_supply: 1_000_000_000_000_000,
+            fee_basis_points: FEE_BPS,
+        }
+    }
+
+    #[test]
+    fn buy_quote_on_initial_curve() {
+        let curve = BondingCurve::initial(&global());
+        assert_eq!(curve.buy_quote(Lamports(1_000_000_000), FEE_BPS), 34_281_150_129_545);
+        assert_eq!(curve.buy_quote(Lamports(0), FEE_BPS), 0);
+    }
+
+    #[test]
+    fn buy_cost_rounds_up_within_quoted_amount() {
+        let curve = BondingCurve::initial(&global());
+        let tokens = curve.buy_quote(Lamports(1_000_000_000), FEE_BPS);
+
+        // Котировка округляет токены вниз, стоимость — вверх: покупка укладывается в сумму
+        assert_eq!(curve.buy_cost(tokens, FEE_BPS), Some(Lamports(999_999_999)));
+        assert_eq!(curve.buy_cost(tokens + 1, FEE_BPS), Some(Lamports(1_000_000_000)));
+        assert_eq!(curve.buy_cost(0, FEE_BPS), None);
+        assert_eq!(curve.buy_cost(curve.virtual_token_reserves, FEE_BPS), None);
+    }
+
+    #[test]
+    fn buy_quote_is_capped_by_real_reserves() {
+        let mut curve = BondingCurve::initial(&global());
+        curve.real_token_reserves = 1_000;
+        assert_eq!(curve.buy_quote(Lamports(1_000_000_000), FEE_BPS), 1_000);
+    }
+
+    #[test]
+    fn sell_quote_deducts_fee() {
+        let curve = BondingCurve::initial(&global());
+        assert_eq!(curve.sell_quote(34_281_150_129_545, FEE_BPS), Lamports(919_504_643));
+        assert_eq!(curve.sell_quote(0, FEE_BPS), Lamports(0));
+    }
+
+    #[test]
+    fn complete_curve_has_no_quotes() {
+        let mut curve = BondingCurve::initial(&global());
+        curve.complete = true;
+        assert_eq!(curve.buy_quote(Lamports(1_000_000_000), FEE_BPS), 0);
+        assert_eq!(curve.buy_cost(1_000, FEE_BPS), None);
+        assert_eq!(curve.sell_quote(1_000, FEE_BPS), Lamports(0));
+    }
+
+    #[test]
+    fn dev_buy_moves_reserves_before_quote() {
+        let mut curve = BondingCurve::initial(&global());
+        curve.apply_buy(50_000_000_000_000);
+
+        assert_eq!(curve.virtual_token_reserves, 1_023_000_000_000_000);
+        assert_eq!(curve.real_token_reserves, 743_100_000_000_000);
+        assert_eq!(curve.virtual_sol_reserves, 31_466_275_660);
+        assert_eq!(curve.real_sol_reserves, 1_466_275_660);
+        assert_eq!(curve.buy_quote(Lamports(1_000_000_000), FEE_BPS), 31_207_160_273_954);
+    }
+
+    #[test]
+    fn progress_uses_global_reserves() {
+        let global = global();
+        let mut curve = BondingCurve::initial(&global);
+        assert_eq!(curve.progress_percent(&global), 0);
+
+        curve.real_token_reserves = global.initial_real_token_reserves / 5;
+        assert_eq!(curve.progress_percent(&global), 80);
+
+        curve.complete = true;
+        assert_eq!(curve.progress_percent(&global), 100);
+    }
+
+    #[test]
+    fn slippage_bounds() {
+        assert_eq!(max_sol_cost(Lamports(1_000_000), 500), Lamports(1_050_000));
+        assert_eq!(max_sol_cost(Lamports(1_000_000), 0), Lamports(1_000_000));
+        assert_eq!(min_sol_output(Lamports(1_000_000), 500), Lamports(950_000));
+        assert_eq!(min_sol_output(Lamports(1_000_000), 10_000), Lamports(0));
+        // Округление вниз в обе стороны: лимит покупки не превышает допуск
+        assert_eq!(max_sol_cost(Lamports(999), 500), Lamports(1_048));
+        assert_eq!(min_sol_output(Lamports(999), 500), Lamports(949));
+    }
+}
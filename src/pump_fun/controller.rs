@@ -0,0 +1,227 @@
+use crate::config::{RpcType, SharedConfig};
+use crate::core::decoder::pump_fun::PumpFunInstruction;
+use crate::core::decoder::{DecodedInstruction, DecoderRegistry};
+use crate::core::extract_instructions;
+use crate::metrics::{PUMP_FUN_BUY_FAILURE, PUMP_FUN_BUY_SUCCESS, PUMP_FUN_TOKEN_DETECTED};
+use crate::pump_fun::bonding_curve::{fetch_global, max_sol_cost, BondingCurve, Global};
+use crate::tx_senders::create_senders;
+use crate::tx_senders::submission::SubmissionManager;
+use crate::tx_senders::transaction::{
+    build_transaction_with_config, PumpFunSide, PumpFunTrade, TransactionConfig,
+};
+use crate::wallet::pool::WalletPool;
+
+use anyhow::Result;
+use reqwest::Client;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use tokio::sync::OnceCell;
+use tracing::{info, warn};
+use yellowstone_grpc_proto::convert_from::create_tx_meta;
+use yellowstone_grpc_proto::prelude::TransactionStatusMeta as YellowstoneMeta;
+
+/// Новый токен на bonding curve Pump.fun
+#[derive(Debug, Clone)]
+pub struct CreatedToken {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub associated_bonding_curve: Pubkey,
+    /// Покупки в транзакции создания (dev buy), уже исполненные на кривой до нашей
+    pub dev_buys: Vec<u64>,
+}
+
+/// Снайпинг новых токенов Pump.fun: покупка отправляется через RPC и сразу через все
+/// настроенные Bloxroute/NextBlock endpoint'ы, позиция открывается после подтверждения
+#[derive(Clone)]
+pub struct PumpFunController {
+    pub args: SharedConfig,
+    pub wallets: Arc<WalletPool>,
+    pub rpc: Arc<RpcClient>,
+    pub submissions: Arc<SubmissionManager>,
+    pub client: Client,
+    pub decoders: Arc<DecoderRegistry>,
+    pub seen_mints: Arc<RwLock<HashSet<Pubkey>>>,
+    /// Параметры программы загружаются при первой покупке и дальше не меняются
+    pub global: Arc<OnceCell<Global>>,
+}
+
+impl PumpFunController {
+    pub fn new(
+        args: SharedConfig,
+        wallets: Arc<WalletPool>,
+        rpc: Arc<RpcClient>,
+        submissions: Arc<SubmissionManager>,
+    ) -> Self {
+        Self {
+            args,
+            wallets,
+            rpc,
+            submissions,
+            client: Client::new(),
+            decoders: Arc::new(DecoderRegistry::default()),
+            seen_mints: Arc::new(RwLock::new(HashSet::new())),
+            global: Arc::new(OnceCell::new()),
+        }
+    }
+
+    pub async fn transaction_handler(
+        &self,
+        tx: VersionedTransaction,
+        meta: YellowstoneMeta,
+    ) -> Result<()> {
+        if meta.err.is_some() {
+            return Ok(());
+        }
+
+        let parsed_meta = match create_tx_meta(meta) {
+            Ok(parsed_meta) => parsed_meta,
+            Err(e) => {
+                warn!("Не удалось сконвертировать meta: {:?}", e);
+                return Ok(());
+            }
+        };
+        let Ok(instructions) = extract_instructions(parsed_meta, tx) else {
+            warn!("Не удалось извлечь инструкции");
+            return Ok(());
+        };
+
+        let decoded: Vec<PumpFunInstruction> = instructions
+            .iter()
+            .filter_map(|parsed| match self.decoders.decode(&parsed.instruction) {
+                Some(DecodedInstruction::PumpFun(instruction)) => Some(instruction),
+                _ => None,
+            })
+            .collect();
+
+        for instruction in &decoded {
+            let PumpFunInstruction::Create {
+                mint,
+                bonding_curve,
+                associated_bonding_curve,
+                user,
+                symbol,
+                ..
+            } = instruction
+            else {
+                continue;
+            };
+            let mint = *mint;
+
+            if !self.seen_mints.write().unwrap().insert(mint) {
+                continue;
+            }
+
+            info!("Новый токен Pump.fun: {} ({}) | bonding curve: {} | создатель: {}",
+                mint, symbol, bonding_curve, user);
+            PUMP_FUN_TOKEN_DETECTED.inc();
+
+            let dev_buys: Vec<u64> = decoded
+                .iter()
+                .filter_map(|instruction| match instruction {
+                    PumpFunInstruction::Buy { mint: bought, amount, .. } if *bought == mint => Some(*amount),
+                    _ => None,
+                })
+                .collect();
+
+            self.buy(CreatedToken {
+                mint,
+                bonding_curve: *bonding_curve,
+                associated_bonding_curve: *associated_bonding_curve,
+                dev_buys,
+            })
+            .await;
+        }
+
+        Ok(())
+    }
+
+    async fn buy(&self, token: CreatedToken) {
+        let global = match self.global.get_or_try_init(|| fetch_global(&self.rpc)).await {
+            Ok(global) => global,
+            Err(e) => {
+                warn!("Не удалось загрузить параметры Pump.fun: {:?}", e);
+                return;
+            }
+        };
+
+        let args = self.args.load();
+        let amount = args.buy_amount.to_lamports();
+
+        // Аккаунт кривой в момент создания токена ещё может быть не виден через RPC,
+        // а её состояние задаётся параметрами Global и покупками из транзакции создания
+        let mut curve = BondingCurve::initial(global);
+        for amount in &token.dev_buys {
+            curve.apply_buy(*amount);
+        }
+        if !token.dev_buys.is_empty() {
+            info!("Dev buy {}: {} токенов до нашей покупки",
+                token.mint, global.initial_real_token_reserves - curve.real_token_reserves);
+        }
+        let token_amount = curve.buy_quote(amount, global.fee_basis_points);
+        let Some(cost) = curve.buy_cost(token_amount, global.fee_basis_points) else {
+            warn!("Нулевая котировка покупки для токена: {}", token.mint);
+            return;
+        };
+        let trade = PumpFunTrade {
+            mint: token.mint,
+            bonding_curve: token.bonding_curve,
+            associated_bonding_curve: token.associated_bonding_curve,
+            side: PumpFunSide::Buy {
+                token_amount,
+                max_sol_cost: max_sol_cost(cost, args.pump_fun.slippage_bps).0,
+            },
+        };
+        info!("Котировка {}: {} токенов за {}, проскальзывание {} б.п.",
+            token.mint, token_amount, cost, args.pump_fun.slippage_bps);
+
+        let Some(lease) = self.wallets.acquire(&token.mint, amount) else {
+            warn!("Нет кошелька с доступным бюджетом для токена: {}", token.mint);
+            return;
+        };
+
+        let relays = create_senders(&args, &self.client);
+        let tx_config = TransactionConfig::new(&args, lease.wallet.keypair.clone());
+        let result = self
+            .submissions
+            .submit_via(
+                &args,
+                token.mint,
+                args.compute_unit_price,
+                None,
+                &relays,
+                |blockhash, compute_unit_price| {
+                    let tx_config = TransactionConfig {
+                        compute_unit_price: compute_unit_price.0,
+                        ..tx_config.clone()
+                    };
+                    build_transaction_with_config(&tx_config, &RpcType::SolanaRpc, blockhash, &trade)
+                },
+            )
+            .await;
+
+        match result {
+            Ok(signature) => {
+                info!("Покупка {} подтверждена: {}", token.mint, signature);
+                PUMP_FUN_BUY_SUCCESS.inc();
+                self.wallets.open_position(&lease, token.mint, None, Some(signature));
+
+                let controller = self.clone();
+                let wallet = lease.wallet.pubkey();
+                tokio::spawn(async move {
+                    let fill = controller.submissions.fill(&signature, &wallet, &token.mint).await;
+                    if !controller.wallets.record_fill(&wallet, &token.mint, fill) {
+                        warn!("Покупка {} ({}) не исполнена: позиция закрыта", token.mint, signature);
+                    }
+                });
+            }
+            Err(e) => {
+                warn!("Покупка {} не прошла: {:?}", token.mint, e);
+                PUMP_FUN_BUY_FAILURE.inc();
+                self.wallets.release(&lease);
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::config::SharedConfig;
+use crate::metrics::{PUMP_FUN_CURVES_COMPLETED, PUMP_FUN_GRADUATIONS};
+use crate::pump_fun::bonding_curve::{BondingCurve, Global};
+
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Кривые без обновлений дольше этого срока перестают отслеживаться
+const CURVE_TTL: Duration = Duration::from_secs(60 * 60);
+/// Сколько миграция ждёт создания пула Meteora
+const MIGRATION_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// История bonding curve токена, мигрировавшего в Meteora
+#[derive(Debug, Clone)]
+pub struct Graduation {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    /// Первое обновление кривой выше порога `graduation_progress_percent`;
+    /// `None`, если кривая не попала в отслеживание (например, заполнена до старта)
+    pub tracked_since: Option<Instant>,
+    pub completed_at: Option<Instant>,
+    /// Обновления аккаунта кривой за время отслеживания
+    pub updates: u64,
+    /// SOL, собранные кривой к последнему обновлению
+    pub real_sol_reserves: u64,
+    pub migrated_at: Instant,
+}
+
+struct CurveHistory {
+    first_seen: Instant,
+    last_update: Instant,
+    completed_at: Option<Instant>,
+    updates: u64,
+    real_sol_reserves: u64,
+}
+
+/// Отслеживание кривых Pump.fun, близких к заполнению, и их миграций.
+/// Кривые приходят из подписки Geyser на аккаунты программы, миграция — из инструкции withdraw.
+pub struct GraduationTracker {
+    args: SharedConfig,
+    /// Параметры программы для расчёта заполнения; без них кривые не отслеживаются
+    global: Option<Global>,
+    /// Ключ — адрес bonding curve: в обновлении аккаунта mint не виден
+    curves: RwLock<HashMap<Pubkey, CurveHistory>>,
+    /// Мигрировавшие токены, ожидающие пул Meteora, по mint
+    migrations: RwLock<HashMap<Pubkey, Graduation>>,
+}
+
+impl GraduationTracker {
+    pub fn new(args: SharedConfig) -> Self {
+        Self {
+            args,
+            global: None,
+            curves: RwLock::new(HashMap::new()),
+            migrations: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_global(mut self, global: Global) -> Self {
+        self.global = Some(global);
+        self
+    }
+
+    /// Обновление аккаунта bonding curve из Geyser
+    pub fn update(&self, key: &Pubkey, data: &[u8]) {
+        let Some(global) = &self.global else {
+            return;
+        };
+        // Разбор и расчёт заполнения до блокировки: обновления кривых идут непрерывно
+        let Some(curve) = BondingCurve::from_account_data(data) else {
+            return;
+        };
+        let below_threshold =
+            curve.progress_percent(global) < self.args.load().pump_fun.graduation_progress_percent;
+
+        let now = Instant::now();
+        let mut curves = self.curves.write().unwrap();
+
+        if !curves.contains_key(key) {
+            if below_threshold {
+                return;
+            }
+            curves.retain(|_, history| now.duration_since(history.last_update) < CURVE_TTL);
+        }
+
+        let history = curves.entry(*key).or_insert_with(|| CurveHistory {
+            first_seen: now,
+            last_update: now,
+            completed_at: None,
+            updates: 0,
+            real_sol_reserves: 0,
+        });
+        history.last_update = now;
+        history.updates += 1;
+        history.real_sol_reserves = curve.real_sol_reserves;
+
+        if curve.complete && history.completed_at.is_none() {
+            history.completed_at = Some(now);
+            PUMP_FUN_CURVES_COMPLETED.inc();
+            info!("Bonding curve {} заполнена: {} lamports за {:?} отслеживания",
+                key, curve.real_sol_reserves, now.duration_since(history.first_seen));
+        }
+    }
+
+    /// Инструкция withdraw: ликвидность кривой выведена для переноса в пул Meteora
+    pub fn migrated(&self, mint: Pubkey, bonding_curve: Pubkey) {
+        let now = Instant::now();
+        let history = self.curves.write().unwrap().remove(&bonding_curve);
+
+        let graduation = Graduation {
+            mint,
+            bonding_curve,
+            tracked_since: history.as_ref().map(|h| h.first_seen),
+            completed_at: history.as_ref().and_then(|h| h.completed_at),
+            updates: history.as_ref().map(|h| h.updates).unwrap_or_default(),
+            real_sol_reserves: history.as_ref().map(|h| h.real_sol_reserves).unwrap_or_default(),
+            migrated_at: now,
+        };
+
+        let mut migrations = self.migrations.write().unwrap();
+        migrations.retain(|_, pending| now.duration_since(pending.migrated_at) < MIGRATION_TTL);
+        if migrations.insert(mint, graduation).is_none() {
+            PUMP_FUN_GRADUATIONS.inc();
+            info!("Миграция токена Pump.fun {} (bonding curve {})", mint, bonding_curve);
+        }
+    }
+
+    /// История миграции для нового пула, если один из его токенов ожидает пул
+    pub fn take(&self, token_a: &Pubkey, token_b: &Pubkey) -> Option<Graduation> {
+        let mut migrations = self.migrations.write().unwrap();
+        let graduation = migrations.remove(token_a).or_else(|| migrations.remove(token_b))?;
+
+        (graduation.migrated_at.elapsed() < MIGRATION_TTL).then_some(graduation)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod bonding_curve;
+pub mod controller;
+pub mod graduation;
+pub mod sell;
@@ -0,0 +1,60 @@
+use crate::config::{PingThingsArgs, RpcType};
+use crate::pump_fun::bonding_curve::{bonding_curve_accounts, fetch_bonding_curve, fetch_global, min_sol_output};
+use crate::tx_senders::submission::SubmissionManager;
+use crate::tx_senders::transaction::{
+    build_transaction_with_config, PumpFunSide, PumpFunTrade, TransactionConfig,
+};
+use crate::wallet::pool::Wallet;
+
+use anyhow::{bail, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use spl_associated_token_account::get_associated_token_address;
+use tracing::info;
+
+/// Продажа `token_amount` токенов на bonding curve с допуском `pump_fun.slippage_bps`.
+/// Ключ отправки — ATA кошелька: mint уже занят покупкой этого токена.
+pub async fn sell_on_curve(
+    args: &PingThingsArgs,
+    rpc: &RpcClient,
+    submissions: &SubmissionManager,
+    wallet: &Wallet,
+    mint: Pubkey,
+    token_amount: u64,
+) -> Result<Signature> {
+    let global = fetch_global(rpc).await?;
+    let (bonding_curve, associated_bonding_curve) = bonding_curve_accounts(&mint);
+    let curve = fetch_bonding_curve(rpc, &bonding_curve).await?;
+    if curve.complete {
+        bail!("bonding curve {bonding_curve} заполнена, продажа возможна только на AMM");
+    }
+
+    let quote = curve.sell_quote(token_amount, global.fee_basis_points);
+    let min_output = min_sol_output(quote, args.pump_fun.slippage_bps);
+    info!(
+        "Продажа {} токенов {}: котировка {}, минимум {}",
+        token_amount, mint, quote, min_output
+    );
+
+    let trade = PumpFunTrade {
+        mint,
+        bonding_curve,
+        associated_bonding_curve,
+        side: PumpFunSide::Sell {
+            token_amount,
+            min_sol_output: min_output.0,
+        },
+    };
+    let tx_config = TransactionConfig::new(args, wallet.keypair.clone());
+    let key = get_associated_token_address(&wallet.pubkey(), &mint);
+    submissions
+        .submit(args, key, args.compute_unit_price, None, |blockhash, compute_unit_price| {
+            let tx_config = TransactionConfig {
+                compute_unit_price: compute_unit_price.0,
+                ..tx_config.clone()
+            };
+            build_transaction_with_config(&tx_config, &RpcType::SolanaRpc, blockhash, &trade)
+        })
+        .await
+}
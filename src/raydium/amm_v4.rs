@@ -0,0 +1,198 @@
+use crate::raydium::reserves::{constant_product_out, fee_ceil};
+use crate::tx_senders::constants::RAYDIUM_AMM_V4_PROGRAM_ID;
+
+use anyhow::{anyhow, bail, Context, Result};
+use borsh::BorshDeserialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+/// Статусы пула, в которых программа принимает свапы
+const SWAP_STATUSES: [u64; 3] = [1, 6, 7];
+/// Префикс "serum" перед данными рынка OpenBook
+const MARKET_HEADER_LEN: usize = 5;
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct Fees {
+    _min_separate_numerator: u64,
+    _min_separate_denominator: u64,
+    _trade_fee_numerator: u64,
+    _trade_fee_denominator: u64,
+    _pnl_numerator: u64,
+    _pnl_denominator: u64,
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+}
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct StateData {
+    pub need_take_pnl_coin: u64,
+    pub need_take_pnl_pc: u64,
+    _total_pnl_pc: u64,
+    _total_pnl_coin: u64,
+    /// Время открытия торговли, секунды
+    pub pool_open_time: u64,
+    _punish_pc_amount: u64,
+    _punish_coin_amount: u64,
+    _orderbook_to_init_time: u64,
+    _swap_coin_in_amount: u128,
+    _swap_pc_out_amount: u128,
+    _swap_acc_pc_fee: u64,
+    _swap_pc_in_amount: u128,
+    _swap_coin_out_amount: u128,
+    _swap_acc_coin_fee: u64,
+}
+
+/// Аккаунт пула AMM v4 (`AmmInfo`, без дискриминатора); coin — базовый токен, pc — котируемый
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct AmmInfo {
+    pub status: u64,
+    _nonce: u64,
+    _order_num: u64,
+    _depth: u64,
+    _coin_decimals: u64,
+    _pc_decimals: u64,
+    _state: u64,
+    _reset_flag: u64,
+    _min_size: u64,
+    _vol_max_cut_ratio: u64,
+    _amount_wave: u64,
+    _coin_lot_size: u64,
+    _pc_lot_size: u64,
+    _min_price_multiplier: u64,
+    _max_price_multiplier: u64,
+    _sys_decimal_value: u64,
+    pub fees: Fees,
+    pub state_data: StateData,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub coin_vault_mint: Pubkey,
+    pub pc_vault_mint: Pubkey,
+    _lp_mint: Pubkey,
+    pub open_orders: Pubkey,
+    pub market: Pubkey,
+    pub market_program: Pubkey,
+    pub target_orders: Pubkey,
+}
+
+impl AmmInfo {
+    pub fn is_swap_enabled(&self) -> bool {
+        SWAP_STATUSES.contains(&self.status)
+    }
+
+    /// Резервы для котировки: балансы хранилищ без ещё не выведенного PnL
+    pub fn reserves(&self, coin_vault_amount: u64, pc_vault_amount: u64) -> (u64, u64) {
+        (
+            coin_vault_amount.saturating_sub(self.state_data.need_take_pnl_coin),
+            pc_vault_amount.saturating_sub(self.state_data.need_take_pnl_pc),
+        )
+    }
+
+    /// Свап с точным входом (`swap_base_in`): комиссия удерживается со входа
+    pub fn quote_exact_in(&self, reserve_in: u64, reserve_out: u64, amount_in: u64) -> Result<u64> {
+        let fee = fee_ceil(amount_in, self.fees.swap_fee_numerator, self.fees.swap_fee_denominator)
+            .ok_or_else(|| anyhow!("некорректная комиссия пула"))?;
+        Ok(constant_product_out(reserve_in, reserve_out, amount_in.saturating_sub(fee)))
+    }
+}
+
+/// Рынок OpenBook, к которому привязан пул: его аккаунты обязательны в `swap_base_in`
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct MarketState {
+    _account_flags: u64,
+    _own_address: Pubkey,
+    pub vault_signer_nonce: u64,
+    _base_mint: Pubkey,
+    _quote_mint: Pubkey,
+    pub base_vault: Pubkey,
+    _base_deposits_total: u64,
+    _base_fees_accrued: u64,
+    pub quote_vault: Pubkey,
+    _quote_deposits_total: u64,
+    _quote_fees_accrued: u64,
+    _quote_dust_threshold: u64,
+    _request_queue: Pubkey,
+    pub event_queue: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+}
+
+impl MarketState {
+    pub fn vault_signer(&self, market: &Pubkey, market_program: &Pubkey) -> Result<Pubkey> {
+        Pubkey::create_program_address(&[market.as_ref(), &self.vault_signer_nonce.to_le_bytes()], market_program)
+            .map_err(|e| anyhow!("не удалось вычислить vault signer рынка {market}: {e}"))
+    }
+}
+
+pub async fn fetch_amm(rpc: &RpcClient, address: &Pubkey) -> Result<AmmInfo> {
+    let account = rpc
+        .get_account(address)
+        .await
+        .with_context(|| format!("пул AMM v4 {address} не найден"))?;
+    if account.owner != RAYDIUM_AMM_V4_PROGRAM_ID {
+        bail!("{address} не принадлежит программе Raydium AMM v4");
+    }
+
+    AmmInfo::deserialize(&mut account.data.as_slice())
+        .map_err(|e| anyhow!("не удалось разобрать пул AMM v4 {address}: {e}"))
+}
+
+pub async fn fetch_market(rpc: &RpcClient, address: &Pubkey) -> Result<MarketState> {
+    let account = rpc
+        .get_account(address)
+        .await
+        .with_context(|| format!("рынок OpenBook {address} не найден"))?;
+
+    let mut body = account
+        .data
+        .get(MARKET_HEADER_LEN..)
+        .ok_or_else(|| anyhow!("рынок OpenBook {address} слишком короткий"))?;
+    MarketState::deserialize(&mut body).map_err(|e| anyhow!("не удалось разобрать рынок OpenBook {address}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Пул из нулевых байт с комиссией 0,25%
+    fn amm() -> AmmInfo {
+        let mut amm = AmmInfo::deserialize(&mut &[0u8; 2048][..]).unwrap();
+        amm.fees.swap_fee_numerator = 25;
+        amm.fees.swap_fee_denominator = 10_000;
+        amm
+    }
+
+    #[test]
+    fn quote_exact_in_charges_fee_from_input() {
+        let amm = amm();
+        let cases = [
+            // (amount_in, ожидаемый выход): резервы 10 SOL / 1 000 000 токенов
+            (1_000_000_000, 90_702_432_370),
+            (401, 39_899),
+            (1, 0),
+            (0, 0),
+        ];
+        for (amount_in, expected) in cases {
+            assert_eq!(
+                amm.quote_exact_in(10_000_000_000, 1_000_000_000_000, amount_in).unwrap(),
+                expected,
+                "выход для входа {amount_in}"
+            );
+        }
+    }
+
+    #[test]
+    fn quote_exact_in_rejects_zero_fee_denominator() {
+        let mut amm = amm();
+        amm.fees.swap_fee_denominator = 0;
+        assert!(amm.quote_exact_in(10_000_000_000, 1_000_000_000_000, 1_000).is_err());
+    }
+
+    #[test]
+    fn reserves_exclude_pending_pnl() {
+        let mut amm = amm();
+        amm.state_data.need_take_pnl_coin = 1_000;
+        amm.state_data.need_take_pnl_pc = 5_000;
+        assert_eq!(amm.reserves(10_000, 20_000), (9_000, 15_000));
+        assert_eq!(amm.reserves(500, 20_000), (0, 15_000), "PnL больше баланса хранилища");
+    }
+}
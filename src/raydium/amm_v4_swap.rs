@@ -0,0 +1,180 @@
+use crate::config::{Lamports, MicroLamports, PingThingsArgs};
+use crate::core::accounts::unix_now;
+use crate::fees::compute::{ComputeProfiler, TxShape};
+use crate::fees::FeeEstimator;
+use crate::pools::reserves::ReserveCache;
+use crate::raydium::amm_v4::{fetch_amm, fetch_market};
+use crate::raydium::reserves::fetch_vault_amounts;
+use crate::tx_senders::constants::WSOL_MINT;
+use crate::tx_senders::lookup_table::LookupTables;
+use crate::tx_senders::submission::SubmissionManager;
+use crate::tx_senders::transaction::{build_raydium_amm_v4_swap_transaction, RaydiumAmmV4SwapAccounts};
+
+use anyhow::{bail, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{address_lookup_table::AddressLookupTableAccount, hash::Hash, pubkey::Pubkey, signature::{Keypair, Signature}, transaction::VersionedTransaction};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Покупка за SOL в пуле AMM v4: котировка по резервам хранилищ и аккаунты рынка OpenBook
+pub struct RaydiumAmmV4Swap {
+    pub accounts: RaydiumAmmV4SwapAccounts,
+    pub input_mint: Pubkey,
+    pub amount_in: Lamports,
+    /// Котировка за вычетом `swap.slippage_bps`
+    pub min_amount_out: u64,
+    pub compute_unit_price: MicroLamports,
+    pub compute_unit_limit: u32,
+    pub lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl RaydiumAmmV4Swap {
+    pub async fn prepare(
+        args: &PingThingsArgs,
+        fees: &FeeEstimator,
+        compute: &ComputeProfiler,
+        lookup_tables: &LookupTables,
+        reserves: &ReserveCache,
+        rpc: &RpcClient,
+        amm_address: &Pubkey,
+        amount_in: Lamports,
+    ) -> Result<Self> {
+        info!("Получение состояния пула Raydium AMM v4...");
+        // Пул и балансы хранилищ из кэша резервов, если пул отслеживается, иначе по RPC
+        let (amm, cached_vaults) = match reserves.raydium_amm_v4(amm_address) {
+            Some((amm, vaults)) => (amm, Some(vaults)),
+            None => (fetch_amm(rpc, amm_address).await?, None),
+        };
+        if !amm.is_swap_enabled() {
+            bail!("свапы в пуле AMM v4 {amm_address} недоступны (статус {})", amm.status);
+        }
+        let open_time = amm.state_data.pool_open_time;
+        if unix_now() < open_time {
+            bail!("пул AMM v4 {amm_address} откроется в {open_time}");
+        }
+
+        let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+        let (output_mint, sol_is_coin) = if amm.pc_vault_mint == wsol {
+            (amm.coin_vault_mint, false)
+        } else if amm.coin_vault_mint == wsol {
+            (amm.pc_vault_mint, true)
+        } else {
+            bail!("в пуле AMM v4 {amm_address} нет WSOL");
+        };
+
+        let (market, vaults) = tokio::try_join!(
+            fetch_market(rpc, &amm.market),
+            async {
+                match cached_vaults {
+                    Some(vaults) => Ok(vaults.to_vec()),
+                    None => fetch_vault_amounts(rpc, &[amm.coin_vault, amm.pc_vault]).await,
+                }
+            },
+        )?;
+        let (coin_reserve, pc_reserve) = amm.reserves(vaults[0], vaults[1]);
+        let (reserve_in, reserve_out) = if sol_is_coin {
+            (coin_reserve, pc_reserve)
+        } else {
+            (pc_reserve, coin_reserve)
+        };
+        debug!("Резервы AMM v4: {} / {}, mint выхода {}", reserve_in, reserve_out, output_mint);
+
+        let amount_out = amm.quote_exact_in(reserve_in, reserve_out, amount_in.0)?;
+        let slippage = args.swap.slippage_bps as u128;
+        let min_amount_out = (amount_out as u128 * (10_000 - slippage) / 10_000) as u64;
+        info!(
+            "Котировка Raydium AMM v4: {} -> {} токенов (комиссия {}/{}), минимум {}",
+            amount_in, amount_out, amm.fees.swap_fee_numerator, amm.fees.swap_fee_denominator, min_amount_out
+        );
+
+        let writable_accounts = [*amm_address, amm.coin_vault, amm.pc_vault];
+        let compute_unit_price = fees.estimate(rpc, args, &writable_accounts).await;
+        let compute_unit_limit = compute.limit_for(&TxShape::RaydiumAmmV4Swap, args);
+
+        Ok(Self {
+            accounts: RaydiumAmmV4SwapAccounts {
+                amm: *amm_address,
+                open_orders: amm.open_orders,
+                target_orders: amm.target_orders,
+                coin_vault: amm.coin_vault,
+                pc_vault: amm.pc_vault,
+                coin_mint: amm.coin_vault_mint,
+                pc_mint: amm.pc_vault_mint,
+                market_program: amm.market_program,
+                market: amm.market,
+                market_bids: market.bids,
+                market_asks: market.asks,
+                market_event_queue: market.event_queue,
+                market_coin_vault: market.base_vault,
+                market_pc_vault: market.quote_vault,
+                market_vault_signer: market.vault_signer(&amm.market, &amm.market_program)?,
+            },
+            input_mint: wsol,
+            amount_in,
+            min_amount_out,
+            compute_unit_price,
+            compute_unit_limit,
+            lookup_tables: lookup_tables.tables().to_vec(),
+        })
+    }
+
+    pub fn build(
+        &self,
+        user: &Keypair,
+        recent_blockhash: Hash,
+        compute_unit_price: MicroLamports,
+        nonce_account: Option<Pubkey>,
+    ) -> Result<VersionedTransaction> {
+        build_raydium_amm_v4_swap_transaction(
+            user,
+            &self.accounts,
+            &self.input_mint,
+            self.amount_in.0,
+            self.min_amount_out,
+            compute_unit_price,
+            self.compute_unit_limit,
+            recent_blockhash,
+            nonce_account,
+            &self.lookup_tables,
+        )
+    }
+}
+
+pub async fn fetch_and_execute_raydium_amm_v4_swap(
+    args: &PingThingsArgs,
+    fees: &FeeEstimator,
+    compute: &Arc<ComputeProfiler>,
+    lookup_tables: &LookupTables,
+    reserves: &ReserveCache,
+    submissions: &SubmissionManager,
+    user: &Keypair,
+    nonce_account: Option<Pubkey>,
+    pool: &Pubkey,
+    amount_in: Lamports,
+) -> Result<Signature> {
+    info!("Запуск свапа через Raydium AMM v4, пул: {pool}");
+
+    let rpc = RpcClient::new(args.http_rpc.clone());
+    let swap = RaydiumAmmV4Swap::prepare(args, fees, compute, lookup_tables, reserves, &rpc, pool, amount_in).await?;
+
+    info!("Отправка транзакции...");
+    let result = submissions
+        .submit(args, *pool, swap.compute_unit_price, nonce_account, |blockhash, price| {
+            let tx = swap.build(user, blockhash, price, nonce_account)?;
+            compute.profile_in_background(args, TxShape::RaydiumAmmV4Swap, &tx);
+            Ok(tx)
+        })
+        .await;
+
+    match result {
+        Ok(sig) => {
+            info!("Транзакция Raydium AMM v4 успешно отправлена! Signature: {}", sig);
+            Ok(sig)
+        }
+        Err(e) => {
+            warn!("Ошибка отправки транзакции Raydium AMM v4: {:?}", e);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::raydium::reserves::{constant_product_out, fee_ceil};
+use crate::tx_senders::constants::RAYDIUM_CPMM_PROGRAM_ID;
+
+use anyhow::{anyhow, bail, Context, Result};
+use borsh::BorshDeserialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+/// Точность ставок комиссии CPMM (1e6 = 100%)
+const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+/// Бит статуса, запрещающий свапы
+const STATUS_SWAP_DISABLED: u8 = 1 << 2;
+
+/// Аккаунт пула CPMM (без дискриминатора); поля после `open_time` не нужны
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct PoolState {
+    pub amm_config: Pubkey,
+    _pool_creator: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    _lp_mint: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_program: Pubkey,
+    pub token_1_program: Pubkey,
+    pub observation_key: Pubkey,
+    _auth_bump: u8,
+    pub status: u8,
+    _lp_mint_decimals: u8,
+    _mint_0_decimals: u8,
+    _mint_1_decimals: u8,
+    _lp_supply: u64,
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub fund_fees_token_0: u64,
+    pub fund_fees_token_1: u64,
+    /// Время открытия торговли, секунды
+    pub open_time: u64,
+}
+
+impl PoolState {
+    pub fn is_swap_enabled(&self) -> bool {
+        self.status & STATUS_SWAP_DISABLED == 0
+    }
+
+    /// Резервы для котировки: балансы хранилищ без накопленных комиссий протокола и фонда
+    pub fn reserves(&self, vault_0_amount: u64, vault_1_amount: u64) -> (u64, u64) {
+        (
+            vault_0_amount.saturating_sub(self.protocol_fees_token_0 + self.fund_fees_token_0),
+            vault_1_amount.saturating_sub(self.protocol_fees_token_1 + self.fund_fees_token_1),
+        )
+    }
+}
+
+/// Конфигурация комиссий, общая для пулов одного уровня
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct AmmConfig {
+    _bump: u8,
+    _disable_create_pool: bool,
+    _index: u16,
+    /// Комиссия свапа, точность `FEE_RATE_DENOMINATOR`
+    pub trade_fee_rate: u64,
+}
+
+impl AmmConfig {
+    /// Свап с точным входом (`swap_base_input`): комиссия удерживается со входа
+    pub fn quote_exact_in(&self, reserve_in: u64, reserve_out: u64, amount_in: u64) -> Result<u64> {
+        let fee = fee_ceil(amount_in, self.trade_fee_rate, FEE_RATE_DENOMINATOR)
+            .ok_or_else(|| anyhow!("некорректная комиссия пула"))?;
+        Ok(constant_product_out(reserve_in, reserve_out, amount_in.saturating_sub(fee)))
+    }
+}
+
+/// PDA, владеющий хранилищами и LP mint всех пулов программы
+pub fn pool_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"vault_and_lp_mint_auth_seed"], &RAYDIUM_CPMM_PROGRAM_ID).0
+}
+
+/// Данные Anchor-аккаунта CPMM без 8-байтового дискриминатора
+async fn fetch_account<T: BorshDeserialize>(rpc: &RpcClient, address: &Pubkey, label: &str) -> Result<T> {
+    let account = rpc
+        .get_account(address)
+        .await
+        .with_context(|| format!("{label} CPMM {address} не найден"))?;
+    if account.owner != RAYDIUM_CPMM_PROGRAM_ID {
+        bail!("{address} не принадлежит программе Raydium CPMM");
+    }
+
+    let mut body = account
+        .data
+        .get(8..)
+        .ok_or_else(|| anyhow!("{label} CPMM {address} слишком короткий"))?;
+    T::deserialize(&mut body).map_err(|e| anyhow!("не удалось разобрать {label} CPMM {address}: {e}"))
+}
+
+pub async fn fetch_pool(rpc: &RpcClient, address: &Pubkey) -> Result<PoolState> {
+    fetch_account(rpc, address, "пул").await
+}
+
+pub async fn fetch_amm_config(rpc: &RpcClient, address: &Pubkey) -> Result<AmmConfig> {
+    fetch_account(rpc, address, "amm config").await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zeroed<T: BorshDeserialize>() -> T {
+        T::deserialize(&mut &[0u8; 2048][..]).unwrap()
+    }
+
+    #[test]
+    fn quote_exact_in_charges_trade_fee() {
+        let cases = [
+            // (trade_fee_rate, ожидаемый выход за 1 SOL): резервы 10 SOL / 1 000 000 токенов
+            (0, 90_909_090_909),
+            (2_500, 90_702_432_370),
+            (10_000, 90_081_892_629),
+        ];
+        for (trade_fee_rate, expected) in cases {
+            let mut config: AmmConfig = zeroed();
+            config.trade_fee_rate = trade_fee_rate;
+            assert_eq!(
+                config.quote_exact_in(10_000_000_000, 1_000_000_000_000, 1_000_000_000).unwrap(),
+                expected,
+                "выход при комиссии {trade_fee_rate}"
+            );
+        }
+    }
+
+    #[test]
+    fn reserves_exclude_protocol_and_fund_fees() {
+        let mut pool: PoolState = zeroed();
+        pool.protocol_fees_token_0 = 100;
+        pool.fund_fees_token_0 = 50;
+        pool.protocol_fees_token_1 = 1_000;
+        pool.fund_fees_token_1 = 500;
+        assert_eq!(pool.reserves(10_000, 20_000), (9_850, 18_500));
+        assert_eq!(pool.reserves(10_000, 1_000), (9_850, 0), "комиссии больше баланса хранилища");
+    }
+
+    #[test]
+    fn swap_disabled_status_bit() {
+        let mut pool: PoolState = zeroed();
+        assert!(pool.is_swap_enabled());
+        pool.status = STATUS_SWAP_DISABLED;
+        assert!(!pool.is_swap_enabled());
+    }
+}
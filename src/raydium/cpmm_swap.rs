@@ -0,0 +1,176 @@
+use crate::config::{Lamports, MicroLamports, PingThingsArgs};
+use crate::core::accounts::unix_now;
+use crate::fees::compute::{ComputeProfiler, TxShape};
+use crate::fees::FeeEstimator;
+use crate::pools::reserves::ReserveCache;
+use crate::raydium::cpmm::{fetch_amm_config, fetch_pool, pool_authority};
+use crate::raydium::reserves::fetch_vault_amounts;
+use crate::tx_senders::constants::{TOKEN_PROGRAM_ADDR, WSOL_MINT};
+use crate::tx_senders::lookup_table::LookupTables;
+use crate::tx_senders::submission::SubmissionManager;
+use crate::tx_senders::transaction::{build_raydium_cpmm_swap_transaction, RaydiumCpmmSwapAccounts};
+
+use anyhow::{bail, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{address_lookup_table::AddressLookupTableAccount, hash::Hash, pubkey::Pubkey, signature::{Keypair, Signature}, transaction::VersionedTransaction};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Покупка за SOL в пуле CPMM: котировка по резервам хранилищ за вычетом накопленных комиссий
+pub struct RaydiumCpmmSwap {
+    pub accounts: RaydiumCpmmSwapAccounts,
+    pub input_mint: Pubkey,
+    pub amount_in: Lamports,
+    /// Котировка за вычетом `swap.slippage_bps`
+    pub min_amount_out: u64,
+    pub compute_unit_price: MicroLamports,
+    pub compute_unit_limit: u32,
+    pub lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl RaydiumCpmmSwap {
+    pub async fn prepare(
+        args: &PingThingsArgs,
+        fees: &FeeEstimator,
+        compute: &ComputeProfiler,
+        lookup_tables: &LookupTables,
+        reserves: &ReserveCache,
+        rpc: &RpcClient,
+        pool_address: &Pubkey,
+        amount_in: Lamports,
+    ) -> Result<Self> {
+        info!("Получение состояния пула Raydium CPMM...");
+        // Пул и балансы хранилищ из кэша резервов, если пул отслеживается, иначе по RPC
+        let (pool, cached_vaults) = match reserves.raydium_cpmm(pool_address) {
+            Some((pool, vaults)) => (pool, Some(vaults)),
+            None => (fetch_pool(rpc, pool_address).await?, None),
+        };
+        if !pool.is_swap_enabled() {
+            bail!("свапы в пуле CPMM {pool_address} отключены (статус {})", pool.status);
+        }
+        let token_program_id = Pubkey::from_str(TOKEN_PROGRAM_ADDR).unwrap();
+        if pool.token_0_program != token_program_id || pool.token_1_program != token_program_id {
+            bail!("пул CPMM {pool_address} использует Token-2022, свап не поддерживается");
+        }
+        if unix_now() < pool.open_time {
+            bail!("пул CPMM {pool_address} откроется в {}", pool.open_time);
+        }
+
+        let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+        let sol_is_token_0 = if pool.token_0_mint == wsol {
+            true
+        } else if pool.token_1_mint == wsol {
+            false
+        } else {
+            bail!("в пуле CPMM {pool_address} нет WSOL");
+        };
+
+        let (config, vaults) = tokio::try_join!(
+            fetch_amm_config(rpc, &pool.amm_config),
+            async {
+                match cached_vaults {
+                    Some(vaults) => Ok(vaults.to_vec()),
+                    None => fetch_vault_amounts(rpc, &[pool.token_0_vault, pool.token_1_vault]).await,
+                }
+            },
+        )?;
+        let (reserve_0, reserve_1) = pool.reserves(vaults[0], vaults[1]);
+        let (reserve_in, reserve_out) = if sol_is_token_0 {
+            (reserve_0, reserve_1)
+        } else {
+            (reserve_1, reserve_0)
+        };
+        debug!("Резервы CPMM: {} / {}", reserve_in, reserve_out);
+
+        let amount_out = config.quote_exact_in(reserve_in, reserve_out, amount_in.0)?;
+        let slippage = args.swap.slippage_bps as u128;
+        let min_amount_out = (amount_out as u128 * (10_000 - slippage) / 10_000) as u64;
+        info!(
+            "Котировка Raydium CPMM: {} -> {} токенов (комиссия {}/1e6), минимум {}",
+            amount_in, amount_out, config.trade_fee_rate, min_amount_out
+        );
+
+        let writable_accounts = [*pool_address, pool.token_0_vault, pool.token_1_vault, pool.observation_key];
+        let compute_unit_price = fees.estimate(rpc, args, &writable_accounts).await;
+        let compute_unit_limit = compute.limit_for(&TxShape::RaydiumCpmmSwap, args);
+
+        Ok(Self {
+            accounts: RaydiumCpmmSwapAccounts {
+                pool: *pool_address,
+                authority: pool_authority(),
+                amm_config: pool.amm_config,
+                observation: pool.observation_key,
+                token_0_vault: pool.token_0_vault,
+                token_1_vault: pool.token_1_vault,
+                token_0_mint: pool.token_0_mint,
+                token_1_mint: pool.token_1_mint,
+            },
+            input_mint: wsol,
+            amount_in,
+            min_amount_out,
+            compute_unit_price,
+            compute_unit_limit,
+            lookup_tables: lookup_tables.tables().to_vec(),
+        })
+    }
+
+    pub fn build(
+        &self,
+        user: &Keypair,
+        recent_blockhash: Hash,
+        compute_unit_price: MicroLamports,
+        nonce_account: Option<Pubkey>,
+    ) -> Result<VersionedTransaction> {
+        build_raydium_cpmm_swap_transaction(
+            user,
+            &self.accounts,
+            &self.input_mint,
+            self.amount_in.0,
+            self.min_amount_out,
+            compute_unit_price,
+            self.compute_unit_limit,
+            recent_blockhash,
+            nonce_account,
+            &self.lookup_tables,
+        )
+    }
+}
+
+pub async fn fetch_and_execute_raydium_cpmm_swap(
+    args: &PingThingsArgs,
+    fees: &FeeEstimator,
+    compute: &Arc<ComputeProfiler>,
+    lookup_tables: &LookupTables,
+    reserves: &ReserveCache,
+    submissions: &SubmissionManager,
+    user: &Keypair,
+    nonce_account: Option<Pubkey>,
+    pool: &Pubkey,
+    amount_in: Lamports,
+) -> Result<Signature> {
+    info!("Запуск свапа через Raydium CPMM, пул: {pool}");
+
+    let rpc = RpcClient::new(args.http_rpc.clone());
+    let swap = RaydiumCpmmSwap::prepare(args, fees, compute, lookup_tables, reserves, &rpc, pool, amount_in).await?;
+
+    info!("Отправка транзакции...");
+    let result = submissions
+        .submit(args, *pool, swap.compute_unit_price, nonce_account, |blockhash, price| {
+            let tx = swap.build(user, blockhash, price, nonce_account)?;
+            compute.profile_in_background(args, TxShape::RaydiumCpmmSwap, &tx);
+            Ok(tx)
+        })
+        .await;
+
+    match result {
+        Ok(sig) => {
+            info!("Транзакция Raydium CPMM успешно отправлена! Signature: {}", sig);
+            Ok(sig)
+        }
+        Err(e) => {
+            warn!("Ошибка отправки транзакции Raydium CPMM: {:?}", e);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod amm_v4;
+pub mod amm_v4_swap;
+pub mod cpmm;
+pub mod cpmm_swap;
+pub mod reserves;
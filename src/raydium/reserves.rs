@@ -0,0 +1,83 @@
+use crate::core::accounts::token_amount;
+
+use anyhow::{anyhow, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+/// Балансы хранилищ пула одним запросом, в порядке `vaults`
+pub async fn fetch_vault_amounts(rpc: &RpcClient, vaults: &[Pubkey]) -> Result<Vec<u64>> {
+    let accounts = rpc.get_multiple_accounts(vaults).await?;
+    vaults
+        .iter()
+        .zip(accounts)
+        .map(|(vault, account)| {
+            let account = account.ok_or_else(|| anyhow!("хранилище {vault} не найдено"))?;
+            token_amount(&account.data).map_err(|_| anyhow!("{vault} не является токен-аккаунтом"))
+        })
+        .collect()
+}
+
+/// Выход свапа по формуле `x * y = k` для входа, из которого уже вычтена комиссия
+pub fn constant_product_out(reserve_in: u64, reserve_out: u64, amount_in: u64) -> u64 {
+    let reserve_in = reserve_in as u128;
+    let reserve_out = reserve_out as u128;
+    let amount_in = amount_in as u128;
+    if reserve_in + amount_in == 0 {
+        return 0;
+    }
+    (reserve_out * amount_in / (reserve_in + amount_in)) as u64
+}
+
+/// Комиссия со входа, округлённая вверх, как её считают обе программы Raydium
+pub fn fee_ceil(amount: u64, numerator: u64, denominator: u64) -> Option<u64> {
+    if denominator == 0 {
+        return None;
+    }
+    let fee = (amount as u128 * numerator as u128).div_ceil(denominator as u128);
+    u64::try_from(fee).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constant_product_out_rounds_down() {
+        let cases = [
+            // (reserve_in, reserve_out, amount_in, ожидаемый выход)
+            (1_000_000, 2_000_000, 1_000, 1_998),
+            (0, 1_000, 1, 1_000),
+            (1_000, 0, 1_000, 0),
+            (0, 0, 0, 0),
+            (u64::MAX, u64::MAX, u64::MAX, u64::MAX / 2),
+        ];
+        for (reserve_in, reserve_out, amount_in, expected) in cases {
+            assert_eq!(
+                constant_product_out(reserve_in, reserve_out, amount_in),
+                expected,
+                "выход для {reserve_in}/{reserve_out} и входа {amount_in}"
+            );
+        }
+    }
+
+    #[test]
+    fn fee_ceil_rounds_up() {
+        let cases = [
+            // (amount, numerator, denominator, ожидаемая комиссия)
+            (10_000, 25, 10_000, Some(25)),
+            (1, 25, 10_000, Some(1)),
+            (0, 25, 10_000, Some(0)),
+            (400, 25, 10_000, Some(1)),
+            (401, 25, 10_000, Some(2)),
+            (1_000, 25, 0, None),
+            (u64::MAX, u64::MAX, 1, None),
+        ];
+        for (amount, numerator, denominator, expected) in cases {
+            assert_eq!(
+                fee_ceil(amount, numerator, denominator),
+                expected,
+                "комиссия {numerator}/{denominator} с {amount}"
+            );
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use crate::config::PingThingsArgs;
+use crate::strategy::{Action, MarketEvent, Strategy};
+use crate::tx_senders::constants::WSOL_MINT;
+
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Покупка в пулах Meteora, созданных миграцией токена с bonding curve Pump.fun
+pub struct GraduationSniper;
+
+impl Strategy for GraduationSniper {
+    fn name(&self) -> &'static str {
+        "graduation"
+    }
+
+    fn on_event(&self, event: &MarketEvent, args: &PingThingsArgs) -> Action {
+        let MarketEvent::NewPool { venue, pool, token_a, token_b, graduation, .. } = event else {
+            return Action::ignore("не новый пул");
+        };
+
+        let Some(graduation) = graduation else {
+            return Action::ignore("пул создан не миграцией Pump.fun");
+        };
+
+        let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+        if *token_a != wsol && *token_b != wsol {
+            return Action::ignore(format!("токен {} мигрировал не в пару с WSOL", graduation.mint));
+        }
+
+        Action::Buy {
+            venue: *venue,
+            pool: *pool,
+            token_a: *token_a,
+            token_b: *token_b,
+            amount: args.buy_amount.to_lamports(),
+        }
+    }
+}
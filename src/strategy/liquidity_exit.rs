@@ -0,0 +1,19 @@
+use crate::config::PingThingsArgs;
+use crate::strategy::{Action, MarketEvent, Strategy};
+
+/// Выход из позиции, когда из пула выводят ликвидность. Пулы без наших позиций
+/// отбрасывает контроллер, поэтому стратегия решает только по событию.
+pub struct LiquidityExit;
+
+impl Strategy for LiquidityExit {
+    fn name(&self) -> &'static str {
+        "liquidity_exit"
+    }
+
+    fn on_event(&self, event: &MarketEvent, _args: &PingThingsArgs) -> Action {
+        match event {
+            MarketEvent::LiquidityRemoved { pool, .. } => Action::Sell { pool: *pool },
+            _ => Action::ignore("не вывод ликвидности"),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+pub mod graduation;
+pub mod liquidity_exit;
+pub mod new_pool;
+
+use crate::config::{Lamports, PingThingsArgs, StrategyKind};
+use crate::launch::Activation;
+use crate::pump_fun::graduation::Graduation;
+use crate::strategy::graduation::GraduationSniper;
+use crate::strategy::liquidity_exit::LiquidityExit;
+use crate::strategy::new_pool::NewPoolSniper;
+
+use solana_sdk::pubkey::Pubkey;
+
+/// Программа, в которой создан пул: определяет, как собирается свап
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Venue {
+    MeteoraAmm,
+    MeteoraDlmm,
+    MeteoraDammV2,
+    RaydiumAmmV4,
+    RaydiumCpmm,
+}
+
+impl Venue {
+    pub fn is_raydium(&self) -> bool {
+        matches!(self, Venue::RaydiumAmmV4 | Venue::RaydiumCpmm)
+    }
+}
+
+/// Событие рынка, распознанное в транзакции
+#[derive(Debug, Clone)]
+pub enum MarketEvent {
+    NewPool {
+        venue: Venue,
+        pool: Pubkey,
+        token_a: Pubkey,
+        token_b: Pubkey,
+        /// Пояснение для лога: откуда взят пул
+        origin: String,
+        /// История bonding curve, если пул создан миграцией токена Pump.fun
+        graduation: Option<Graduation>,
+        /// Открытие торговли из инструкции создания; `None` — читается из состояния пула
+        activation: Option<Activation>,
+    },
+    LiquidityAdded {
+        pool: Pubkey,
+        user: Pubkey,
+        pool_token_amount: u64,
+    },
+    LiquidityRemoved {
+        pool: Pubkey,
+        user: Pubkey,
+        pool_token_amount: u64,
+    },
+    Swap {
+        pool: Pubkey,
+        user: Pubkey,
+        in_amount: u64,
+    },
+}
+
+impl MarketEvent {
+    pub fn pool(&self) -> &Pubkey {
+        match self {
+            MarketEvent::NewPool { pool, .. }
+            | MarketEvent::LiquidityAdded { pool, .. }
+            | MarketEvent::LiquidityRemoved { pool, .. }
+            | MarketEvent::Swap { pool, .. } => pool,
+        }
+    }
+}
+
+/// Решение стратегии по событию
+#[derive(Debug, Clone)]
+pub enum Action {
+    Buy {
+        venue: Venue,
+        pool: Pubkey,
+        token_a: Pubkey,
+        token_b: Pubkey,
+        amount: Lamports,
+    },
+    /// Закрытие открытых позиций по пулу (для Pump.fun — по mint)
+    Sell { pool: Pubkey },
+    Ignore { reason: String },
+}
+
+impl Action {
+    pub fn ignore(reason: impl Into<String>) -> Self {
+        Action::Ignore {
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Стратегия получает события и решает, что делать; исполнение остаётся за контроллером
+pub trait Strategy: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// `args` — снимок конфигурации на момент события
+    fn on_event(&self, event: &MarketEvent, args: &PingThingsArgs) -> Action;
+}
+
+/// Стратегии из секции `strategies`; пустой список — только снайпинг новых пулов
+pub fn from_config(args: &PingThingsArgs) -> Vec<Box<dyn Strategy>> {
+    let kinds = if args.strategies.is_empty() {
+        vec![StrategyKind::NewPool]
+    } else {
+        args.strategies.clone()
+    };
+
+    kinds
+        .into_iter()
+        .map(|kind| -> Box<dyn Strategy> {
+            match kind {
+                StrategyKind::NewPool => Box::new(NewPoolSniper),
+                StrategyKind::Graduation => Box::new(GraduationSniper),
+                StrategyKind::LiquidityExit => Box::new(LiquidityExit),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,34 @@
+use crate::config::PingThingsArgs;
+use crate::strategy::{Action, MarketEvent, Strategy};
+use crate::tx_senders::constants::WSOL_MINT;
+
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Покупка в каждом новом пуле с WSOL на сумму `buy_amount`
+pub struct NewPoolSniper;
+
+impl Strategy for NewPoolSniper {
+    fn name(&self) -> &'static str {
+        "new_pool"
+    }
+
+    fn on_event(&self, event: &MarketEvent, args: &PingThingsArgs) -> Action {
+        let MarketEvent::NewPool { venue, pool, token_a, token_b, .. } = event else {
+            return Action::ignore("не новый пул");
+        };
+
+        let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+        if *token_a != wsol && *token_b != wsol {
+            return Action::ignore("ни один токен не является WSOL");
+        }
+
+        Action::Buy {
+            venue: *venue,
+            pool: *pool,
+            token_a: *token_a,
+            token_b: *token_b,
+            amount: args.buy_amount.to_lamports(),
+        }
+    }
+}
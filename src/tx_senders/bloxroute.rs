@@ -1,12 +1,10 @@
-use crate::config::{PingThingsArgs, RpcType};
-use crate::tx_senders::transaction::{TransactionConfig, build_transaction_with_config};
 use crate::tx_senders::{TxResult, TxSender};
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use base64;
 use reqwest::Client;
-use solana_sdk::{hash::Hash, pubkey::Pubkey, transaction::VersionedTransaction};
+use solana_sdk::transaction::VersionedTransaction;
 use tracing::debug;
 
 /// Отправщик транзакций через Bloxroute endpoint
@@ -14,23 +12,15 @@ pub struct BloxrouteTxSender {
     pub name: String,
     pub url: String,
     pub auth_key: String,
-    pub args: PingThingsArgs,
     pub client: Client,
 }
 
 impl BloxrouteTxSender {
-    pub fn new(
-        name: String,
-        url: String,
-        auth_key: String,
-        args: PingThingsArgs,
-        client: Client,
-    ) -> Self {
+    pub fn new(name: String, url: String, auth_key: String, client: Client) -> Self {
         Self {
             name,
             url,
             auth_key,
-            args,
             client,
         }
     }
@@ -44,24 +34,6 @@ impl BloxrouteTxSender {
         headers.insert("Content-Type", "application/json".parse().unwrap());
         headers
     }
-
-    fn build_tx(
-        &self,
-        recent_blockhash: Hash,
-        token_address: Pubkey,
-        bonding_curve: Pubkey,
-        associated_bonding_curve: Pubkey,
-    ) -> VersionedTransaction {
-        let tx_config = TransactionConfig::from(self.args.clone());
-        build_transaction_with_config(
-            &tx_config,
-            &RpcType::Bloxroute,
-            recent_blockhash,
-            token_address,
-            bonding_curve,
-            associated_bonding_curve,
-        )
-    }
 }
 
 #[async_trait]
@@ -70,22 +42,8 @@ impl TxSender for BloxrouteTxSender {
         self.name.clone()
     }
 
-    async fn send_transaction(
-        &self,
-        _index: u32,
-        recent_blockhash: Hash,
-        token_address: Pubkey,
-        bonding_curve: Pubkey,
-        associated_bonding_curve: Pubkey,
-    ) -> Result<TxResult> {
-        let tx = self.build_tx(
-            recent_blockhash,
-            token_address,
-            bonding_curve,
-            associated_bonding_curve,
-        );
-
-        let raw_tx = bincode::serialize(&tx)?;
+    async fn send_transaction(&self, tx: &VersionedTransaction) -> Result<TxResult> {
+        let raw_tx = bincode::serialize(tx)?;
         let encoded = base64::encode(raw_tx);
 
         let body = serde_json::json!({ "transaction": encoded });
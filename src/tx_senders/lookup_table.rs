@@ -0,0 +1,87 @@
+use crate::tx_senders::constants::{
+    JITO_TIP_ADDR, METEORA_DAMM_V2_PROGRAM_ID, METEORA_DLMM_PROGRAM_ID, METEORA_PROGRAM_ID, PUMP_FUN_ACCOUNT_ADDR, PUMP_FUN_PROGRAM_ADDR,
+    RAYDIUM_AMM_V4_AUTHORITY, RAYDIUM_AMM_V4_PROGRAM_ID, RAYDIUM_CPMM_PROGRAM_ID,
+    PUMP_FUN_TX_ADDR, RENT_ADDR, SYSTEM_PROGRAM_ADDR, TOKEN_PROGRAM_ADDR, VAULT_PROGRAM_ID, WSOL_MINT,
+};
+
+use anyhow::{anyhow, Context, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use tracing::info;
+
+/// Аккаунты, которые встречаются в каждой нашей транзакции и не зависят от пула
+pub fn static_accounts() -> Vec<Pubkey> {
+    let mut accounts: Vec<Pubkey> = [
+        SYSTEM_PROGRAM_ADDR,
+        TOKEN_PROGRAM_ADDR,
+        RENT_ADDR,
+        WSOL_MINT,
+        JITO_TIP_ADDR,
+        PUMP_FUN_PROGRAM_ADDR,
+        PUMP_FUN_ACCOUNT_ADDR,
+        PUMP_FUN_TX_ADDR,
+    ]
+    .iter()
+    .map(|address| Pubkey::from_str(address).unwrap())
+    .collect();
+
+    accounts.extend([
+        spl_associated_token_account::id(),
+        solana_sdk::compute_budget::id(),
+        METEORA_PROGRAM_ID,
+        VAULT_PROGRAM_ID,
+        METEORA_DLMM_PROGRAM_ID,
+        Pubkey::find_program_address(&[b"__event_authority"], &METEORA_DLMM_PROGRAM_ID).0,
+        METEORA_DAMM_V2_PROGRAM_ID,
+        Pubkey::find_program_address(&[b"pool_authority"], &METEORA_DAMM_V2_PROGRAM_ID).0,
+        Pubkey::find_program_address(&[b"__event_authority"], &METEORA_DAMM_V2_PROGRAM_ID).0,
+        RAYDIUM_AMM_V4_PROGRAM_ID,
+        RAYDIUM_AMM_V4_AUTHORITY,
+        RAYDIUM_CPMM_PROGRAM_ID,
+        Pubkey::find_program_address(&[b"vault_and_lp_mint_auth_seed"], &RAYDIUM_CPMM_PROGRAM_ID).0,
+    ]);
+    accounts
+}
+
+pub async fn fetch_lookup_table(rpc: &RpcClient, address: &Pubkey) -> Result<AddressLookupTableAccount> {
+    let account = rpc
+        .get_account(address)
+        .await
+        .with_context(|| format!("lookup table {address} не найдена"))?;
+    let table = AddressLookupTable::deserialize(&account.data)
+        .map_err(|e| anyhow!("{address} не является lookup table: {e}"))?;
+
+    Ok(AddressLookupTableAccount {
+        key: *address,
+        addresses: table.addresses.to_vec(),
+    })
+}
+
+/// Address lookup tables из конфигурации, загруженные при старте.
+/// Содержимое статично, поэтому повторно таблицы не запрашиваются.
+#[derive(Debug, Clone, Default)]
+pub struct LookupTables {
+    tables: Vec<AddressLookupTableAccount>,
+}
+
+impl LookupTables {
+    pub async fn load(rpc: &RpcClient, addresses: &[String]) -> Result<Self> {
+        let mut tables = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let address = Pubkey::from_str(address)
+                .map_err(|_| anyhow!("некорректный адрес lookup table {address}"))?;
+            let table = fetch_lookup_table(rpc, &address).await?;
+            info!("Lookup table {} ({} адресов)", table.key, table.addresses.len());
+            tables.push(table);
+        }
+
+        Ok(Self { tables })
+    }
+
+    pub fn tables(&self) -> &[AddressLookupTableAccount] {
+        &self.tables
+    }
+}
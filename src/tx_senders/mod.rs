@@ -1,10 +1,17 @@
 pub mod constants;
+pub mod lookup_table;
 pub mod transaction;
 pub mod bloxroute;
 pub mod nextblock;
+pub mod submission;
 
-use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Signature};
+use crate::config::{PingThingsArgs, RpcType};
+use crate::tx_senders::bloxroute::BloxrouteTxSender;
+use crate::tx_senders::nextblock::NextBlockTxSender;
+
+use solana_sdk::{signature::Signature, transaction::VersionedTransaction};
 use async_trait::async_trait;
+use reqwest::Client;
 
 #[derive(Debug, Clone)]
 pub enum TxResult {
@@ -23,12 +30,36 @@ impl Into<String> for TxResult {
 pub trait TxSender: Sync + Send {
     fn name(&self) -> String;
 
-    async fn send_transaction(
-        &self,
-        index: u32,
-        recent_blockhash: Hash,
-        token_address: Pubkey,
-        bonding_curve: Pubkey,
-        associated_bonding_curve: Pubkey,
-    ) -> anyhow::Result<TxResult>;
-}
\ No newline at end of file
+    /// Отправка уже подписанной транзакции: во всех каналах уходит одна и та же подпись
+    async fn send_transaction(&self, tx: &VersionedTransaction) -> anyhow::Result<TxResult>;
+}
+
+/// Отправщики для всех endpoint'ов Bloxroute и NextBlock из секции `rpc`
+pub fn create_senders(args: &PingThingsArgs, client: &Client) -> Vec<Box<dyn TxSender>> {
+    let mut names: Vec<&String> = args.rpc.keys().collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let rpc = &args.rpc[name];
+            let auth = rpc.auth.clone().unwrap_or_default();
+            let sender: Box<dyn TxSender> = match rpc.rpc_type {
+                RpcType::Bloxroute => Box::new(BloxrouteTxSender::new(
+                    name.clone(),
+                    rpc.url.clone(),
+                    auth,
+                    client.clone(),
+                )),
+                RpcType::Nextblock => Box::new(NextBlockTxSender::new(
+                    name.clone(),
+                    rpc.url.clone(),
+                    auth,
+                    client.clone(),
+                )),
+                RpcType::SolanaRpc | RpcType::Jito => return None,
+            };
+            Some(sender)
+        })
+        .collect()
+}
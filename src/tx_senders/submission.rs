@@ -0,0 +1,492 @@
+use crate::config::{ConfirmationLevel, Lamports, MicroLamports, PingThingsArgs};
+use crate::core::balances::balance_changes;
+use crate::core::encoded::fetch_transaction;
+use crate::geyser::signatures::{ObservedStatus, SignatureWatch};
+use crate::nonce::{fetch_nonce_account, NoncePool};
+use crate::metrics::{SUBMISSION_REBROADCASTS, SUBMISSION_RESIGNS};
+use crate::tx_senders::TxSender;
+use crate::wallet::pool::Fill;
+
+use anyhow::{anyhow, bail, Result};
+use futures::future::{join, join_all};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+use yellowstone_grpc_proto::convert_from::create_tx_meta;
+
+/// Попытки получить транзакцию через RPC, если поток Geyser её не показал
+const FILL_FETCH_ATTEMPTS: usize = 5;
+const FILL_FETCH_DELAY: Duration = Duration::from_secs(2);
+
+/// Срок жизни подписанной транзакции
+enum Lifetime {
+    /// Recent blockhash: действителен до заданной высоты блока
+    Blockhash { last_valid_block_height: u64 },
+    /// Durable nonce: действителен, пока значение nonce в аккаунте не изменилось
+    Nonce { account: Pubkey, value: Hash },
+}
+
+/// Итог проверки статусов всех отправленных подписей
+enum SubmissionStatus {
+    Pending,
+    /// Транзакция видна на processed, но ещё не подтверждена
+    Processing,
+    Landed(Signature),
+    Failed(Signature, String),
+}
+
+/// Цена compute unit для повторной подписи: рост на `percent`, но не выше `max`.
+/// Потолок не опускает цену ниже уже использованной (статическая цена может быть выше
+/// потолка динамической оценки).
+fn escalate_price(price: MicroLamports, percent: u32, max: MicroLamports) -> MicroLamports {
+    let escalated = price.0.saturating_mul(100 + percent as u64) / 100;
+    MicroLamports(escalated.min(max.0.max(price.0)))
+}
+
+/// Отправка покупки до подтверждения или истечения blockhash.
+///
+/// Подписанная транзакция переотправляется с заданной периодичностью. Новая подпись
+/// (свежий blockhash и повышенная комиссия) создаётся только после того, как blockhash
+/// предыдущей истёк и ни одна из уже отправленных подписей не прошла — поэтому по одному
+/// ключу (пулу) не может пройти две покупки. В режиме durable nonce транзакция не истекает,
+/// пока nonce не сдвинут, и её можно отправлять через любое число каналов.
+///
+/// Каждая подпись переотправляется не дольше `submission.signature_timeout_secs`: так отправка
+/// не зависает, если nonce не сдвигается или высоту блока не удаётся получить через RPC.
+///
+/// Подтверждение берётся из потока Geyser через [`SignatureWatch`] (транзакции кошельков
+/// и статусы слотов), RPC `getSignatureStatuses` используется как запасной источник.
+pub struct SubmissionManager {
+    rpc: Arc<RpcClient>,
+    watch: Arc<SignatureWatch>,
+    nonces: Arc<NoncePool>,
+    in_flight: Mutex<HashSet<Pubkey>>,
+    landed: Mutex<HashSet<Pubkey>>,
+}
+
+impl SubmissionManager {
+    pub fn new(rpc: Arc<RpcClient>, watch: Arc<SignatureWatch>, nonces: Arc<NoncePool>) -> Self {
+        Self {
+            rpc,
+            watch,
+            nonces,
+            in_flight: Mutex::new(HashSet::new()),
+            landed: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn has_landed(&self, key: &Pubkey) -> bool {
+        self.landed.lock().unwrap().contains(key)
+    }
+
+    /// `build` подписывает транзакцию для заданного blockhash (или значения nonce) и цены compute unit.
+    /// При `nonce` транзакция должна начинаться с `advance_nonce_account` для этого аккаунта.
+    pub async fn submit<F>(
+        &self,
+        args: &PingThingsArgs,
+        key: Pubkey,
+        compute_unit_price: MicroLamports,
+        nonce: Option<Pubkey>,
+        build: F,
+    ) -> Result<Signature>
+    where
+        F: Fn(Hash, MicroLamports) -> Result<VersionedTransaction>,
+    {
+        self.submit_via(args, key, compute_unit_price, nonce, &[], build).await
+    }
+
+    /// Как [`Self::submit`], но каждая подпись дополнительно уходит через `relays` (Bloxroute, NextBlock)
+    pub async fn submit_via<F>(
+        &self,
+        args: &PingThingsArgs,
+        key: Pubkey,
+        compute_unit_price: MicroLamports,
+        nonce: Option<Pubkey>,
+        relays: &[Box<dyn TxSender>],
+        build: F,
+    ) -> Result<Signature>
+    where
+        F: Fn(Hash, MicroLamports) -> Result<VersionedTransaction>,
+    {
+        if self.has_landed(&key) {
+            bail!("покупка по {key} уже прошла");
+        }
+        if !self.in_flight.lock().unwrap().insert(key) {
+            bail!("покупка по {key} уже отправляется");
+        }
+
+        let mut signatures = Vec::new();
+        let result = self
+            .submit_inner(args, compute_unit_price, nonce, relays, build, &mut signatures)
+            .await;
+        self.watch.unwatch(&signatures);
+
+        if result.is_ok() {
+            self.landed.lock().unwrap().insert(key);
+        }
+        self.in_flight.lock().unwrap().remove(&key);
+
+        result
+    }
+
+    async fn submit_inner<F>(
+        &self,
+        args: &PingThingsArgs,
+        mut compute_unit_price: MicroLamports,
+        nonce: Option<Pubkey>,
+        relays: &[Box<dyn TxSender>],
+        build: F,
+        signatures: &mut Vec<Signature>,
+    ) -> Result<Signature>
+    where
+        F: Fn(Hash, MicroLamports) -> Result<VersionedTransaction>,
+    {
+        let config = &args.submission;
+        let interval = Duration::from_millis(config.rebroadcast_interval_ms);
+        let timeout = Duration::from_secs(config.signature_timeout_secs);
+        let mut resigns = 0;
+
+        loop {
+            let (blockhash, lifetime) = self.fresh_lifetime(nonce.as_ref()).await?;
+            let tx = build(blockhash, compute_unit_price)?;
+            self.watch.watch(tx.signatures[0]);
+            signatures.push(tx.signatures[0]);
+            match &lifetime {
+                Lifetime::Blockhash { last_valid_block_height } => info!(
+                    "Отправка {} (blockhash действителен до высоты {})",
+                    tx.signatures[0], last_valid_block_height
+                ),
+                Lifetime::Nonce { account, .. } => {
+                    info!("Отправка {} (durable nonce {})", tx.signatures[0], account)
+                }
+            }
+
+            let deadline = Instant::now() + timeout;
+            loop {
+                self.send(&tx, relays).await;
+
+                self.watch.changed(interval).await;
+                match self.check_statuses(signatures, config.commitment).await {
+                    SubmissionStatus::Landed(signature) => {
+                        self.settle(&lifetime);
+                        return Ok(signature);
+                    }
+                    SubmissionStatus::Failed(signature, err) => {
+                        self.settle(&lifetime);
+                        bail!("транзакция {signature} выполнена с ошибкой: {err}")
+                    }
+                    SubmissionStatus::Pending | SubmissionStatus::Processing => {}
+                }
+
+                // Неизвестное состояние (ошибка RPC) засчитывается в срок подписи
+                match self.is_expired(&lifetime).await {
+                    Some(true) => break,
+                    _ if Instant::now() >= deadline => {
+                        warn!(
+                            "{} не подтверждена за {} с, подпись считается просроченной",
+                            tx.signatures[0], config.signature_timeout_secs
+                        );
+                        break;
+                    }
+                    _ => {}
+                }
+                SUBMISSION_REBROADCASTS.inc();
+            }
+
+            // Blockhash истёк (или nonce сдвинут): новых попаданий прежних подписей быть не может,
+            // но уже обработанную транзакцию нужно дождаться до подтверждения или отката.
+            // При durable nonce повторная подпись берёт то же значение nonce, и пройти может только одна.
+            let deadline = Instant::now() + timeout;
+            loop {
+                match self.check_statuses(signatures, config.commitment).await {
+                    SubmissionStatus::Landed(signature) => {
+                        self.settle(&lifetime);
+                        return Ok(signature);
+                    }
+                    SubmissionStatus::Failed(signature, err) => {
+                        self.settle(&lifetime);
+                        bail!("транзакция {signature} выполнена с ошибкой: {err}")
+                    }
+                    SubmissionStatus::Processing if Instant::now() >= deadline => {
+                        bail!("статус транзакции не определён за {} с", config.signature_timeout_secs)
+                    }
+                    SubmissionStatus::Processing => self.watch.changed(interval).await,
+                    SubmissionStatus::Pending => break,
+                }
+            }
+
+            if !config.resign || resigns >= config.max_resigns {
+                return Err(anyhow!(
+                    "срок транзакции истёк, транзакция не подтверждена ({} подписей)",
+                    signatures.len()
+                ));
+            }
+
+            resigns += 1;
+            SUBMISSION_RESIGNS.inc();
+            compute_unit_price =
+                escalate_price(compute_unit_price, config.fee_escalation_percent, args.priority_fee.max);
+            warn!(
+                "Срок подписи истёк, повторная подпись #{} с ценой {}",
+                resigns, compute_unit_price
+            );
+        }
+    }
+
+    async fn fresh_lifetime(&self, nonce: Option<&Pubkey>) -> Result<(Hash, Lifetime)> {
+        match nonce {
+            Some(account) => {
+                let value = match self.nonces.value(account) {
+                    Some(value) => value,
+                    None => {
+                        let value = fetch_nonce_account(&self.rpc, account).await?.nonce;
+                        self.nonces.set_value(account, value);
+                        value
+                    }
+                };
+                Ok((value, Lifetime::Nonce { account: *account, value }))
+            }
+            None => {
+                let (blockhash, last_valid_block_height) = self
+                    .rpc
+                    .get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+                    .await?;
+                Ok((blockhash, Lifetime::Blockhash { last_valid_block_height }))
+            }
+        }
+    }
+
+    /// `None` — состояние не удалось получить через RPC
+    async fn is_expired(&self, lifetime: &Lifetime) -> Option<bool> {
+        let expired = match lifetime {
+            Lifetime::Blockhash { last_valid_block_height } => self
+                .rpc
+                .get_block_height()
+                .await
+                .map(|height| height > *last_valid_block_height),
+            Lifetime::Nonce { account, value } => match self.nonces.value(account) {
+                Some(current) => return Some(current != *value),
+                None => fetch_nonce_account(&self.rpc, account)
+                    .await
+                    .map(|nonce| nonce.nonce != *value),
+            },
+        };
+
+        expired
+            .inspect_err(|e| debug!("Не удалось проверить срок действия транзакции: {:?}", e))
+            .ok()
+    }
+
+    /// Обработанная транзакция (успешная или нет) сдвинула nonce
+    fn settle(&self, lifetime: &Lifetime) {
+        if let Lifetime::Nonce { account, value } = lifetime {
+            self.nonces.mark_used(account, value);
+        }
+    }
+
+    /// Отправка через RPC и параллельно через все `relays`
+    async fn send(&self, tx: &VersionedTransaction, relays: &[Box<dyn TxSender>]) {
+        let config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            max_retries: Some(0),
+            ..Default::default()
+        };
+
+        let rpc = async {
+            if let Err(e) = self.rpc.send_transaction_with_config(tx, config).await {
+                debug!("Ошибка отправки {}: {:?}", tx.signatures[0], e);
+            }
+        };
+        let relayed = join_all(relays.iter().map(|relay| async move {
+            if let Err(e) = relay.send_transaction(tx).await {
+                debug!("{}: ошибка отправки {}: {:?}", relay.name(), tx.signatures[0], e);
+            }
+        }));
+        join(rpc, relayed).await;
+    }
+
+    /// Фактический результат покупки по pre/post балансам транзакции.
+    /// Транзакция берётся из потока Geyser, `getTransaction` — запасной источник.
+    pub async fn fill(&self, signature: &Signature, wallet: &Pubkey, mint: &Pubkey) -> Option<Fill> {
+        let mut fetched = self
+            .watch
+            .transaction(signature)
+            .and_then(|(tx, meta)| create_tx_meta(meta).ok().map(|meta| (tx, meta)));
+
+        // При commitment processed транзакция может ещё не отдаваться через getTransaction
+        if fetched.is_none() {
+            for _ in 0..FILL_FETCH_ATTEMPTS {
+                match fetch_transaction(&self.rpc, signature).await {
+                    Ok(result) => {
+                        fetched = Some(result);
+                        break;
+                    }
+                    Err(_) => tokio::time::sleep(FILL_FETCH_DELAY).await,
+                }
+            }
+        }
+        let Some((tx, meta)) = fetched else {
+            warn!("Не удалось получить транзакцию {} для учёта исполнения", signature);
+            return None;
+        };
+
+        let changes = balance_changes(&tx, &meta);
+        let decimals = changes
+            .tokens
+            .get(&(*wallet, *mint))
+            .map(|delta| delta.decimals)
+            .unwrap_or_default();
+        // SOL уходит и с кошелька, и с его WSOL-аккаунта (обёрнутый заранее или остаток после свапа)
+        let sol_delta = changes.sol_delta(wallet) + changes.token_delta(wallet, &spl_token::native_mint::id());
+        let fill = Fill {
+            mint: *mint,
+            tokens_received: changes.token_delta(wallet, mint).max(0) as u64,
+            decimals,
+            sol_spent: Lamports((-sol_delta).max(0) as u64),
+        };
+
+        info!(
+            "Исполнение покупки {}: получено {} (decimals {}) токена {}, списано {} lamports",
+            signature, fill.tokens_received, fill.decimals, fill.mint, fill.sol_spent.0
+        );
+        Some(fill)
+    }
+
+    async fn check_statuses(
+        &self,
+        signatures: &[Signature],
+        commitment: ConfirmationLevel,
+    ) -> SubmissionStatus {
+        // Geyser: транзакция обработана, а подтверждение её слота приходит потоком статусов слотов
+        let mut observed = false;
+        for signature in signatures {
+            let Some(observation) = self.watch.status(signature) else {
+                continue;
+            };
+            if observation.orphaned {
+                continue;
+            }
+
+            match observation.status {
+                ObservedStatus::Failed(err) => return SubmissionStatus::Failed(*signature, err),
+                ObservedStatus::Succeeded => {
+                    if commitment == ConfirmationLevel::Processed || observation.confirmed {
+                        return SubmissionStatus::Landed(*signature);
+                    }
+                    observed = true;
+                }
+            }
+        }
+        // Слот ещё не подтверждён: RPC не нужен, если статусы слотов приходят из потока
+        if observed && self.watch.tracks_confirmations() {
+            return SubmissionStatus::Processing;
+        }
+
+        let statuses = match self.rpc.get_signature_statuses(signatures).await {
+            Ok(response) => response.value,
+            Err(e) => {
+                // Статус неизвестен: создавать новую подпись в этом состоянии нельзя
+                debug!("Не удалось получить статусы подписей: {:?}", e);
+                return SubmissionStatus::Processing;
+            }
+        };
+
+        let mut result = if observed {
+            SubmissionStatus::Processing
+        } else {
+            SubmissionStatus::Pending
+        };
+        for (signature, status) in signatures.iter().zip(statuses) {
+            let Some(status) = status else {
+                continue;
+            };
+
+            if let Some(err) = status.err {
+                return SubmissionStatus::Failed(*signature, err.to_string());
+            }
+            if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+                return SubmissionStatus::Landed(*signature);
+            }
+            result = SubmissionStatus::Processing;
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> SubmissionManager {
+        SubmissionManager::new(
+            Arc::new(RpcClient::new("http://127.0.0.1:1".to_string())),
+            Arc::new(SignatureWatch::new()),
+            Arc::new(NoncePool::default()),
+        )
+    }
+
+    #[test]
+    fn escalation_is_capped() {
+        let cases = [
+            // (цена, проценты, потолок, ожидание)
+            (1_000, 25, 10_000, 1_250),
+            (1_000, 0, 10_000, 1_000),
+            (9_000, 25, 10_000, 10_000),
+            (10_000, 25, 10_000, 10_000),
+            // Статическая цена выше потолка не снижается
+            (20_000, 25, 10_000, 20_000),
+            (u64::MAX, 25, 10_000, u64::MAX),
+        ];
+
+        for (price, percent, max, expected) in cases {
+            assert_eq!(
+                escalate_price(MicroLamports(price), percent, MicroLamports(max)),
+                MicroLamports(expected),
+                "цена {price}, рост {percent}%, потолок {max}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn landed_key_is_not_resubmitted() {
+        let manager = manager();
+        let key = Pubkey::new_unique();
+        manager.landed.lock().unwrap().insert(key);
+
+        let result = manager
+            .submit(&PingThingsArgs::default(), key, MicroLamports(1), None, |_, _| {
+                panic!("транзакция не должна подписываться")
+            })
+            .await;
+
+        assert!(result.is_err(), "повторная покупка по прошедшему ключу");
+        assert!(manager.has_landed(&key));
+    }
+
+    #[tokio::test]
+    async fn in_flight_key_is_not_submitted_twice() {
+        let manager = manager();
+        let key = Pubkey::new_unique();
+        manager.in_flight.lock().unwrap().insert(key);
+
+        let result = manager
+            .submit(&PingThingsArgs::default(), key, MicroLamports(1), None, |_, _| {
+                panic!("транзакция не должна подписываться")
+            })
+            .await;
+
+        assert!(result.is_err(), "параллельная покупка по тому же ключу");
+        // Отклонённый дубль не снимает отметку первой отправки
+        assert!(manager.in_flight.lock().unwrap().contains(&key));
+        assert!(!manager.has_landed(&key));
+    }
+}
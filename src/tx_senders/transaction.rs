@@ -1,7 +1,8 @@
-use crate::config::PingThingsArgs;
+use crate::config::{MicroLamports, PingThingsArgs};
 use crate::config::RpcType;
-use solana_client::nonblocking::rpc_client::RpcClient;
+use crate::core::decoder::pump_fun::{BUY_DISCRIMINATOR, SELL_DISCRIMINATOR};
 use solana_sdk::{
+    address_lookup_table::AddressLookupTableAccount,
     compute_budget::ComputeBudgetInstruction,
     hash::Hash,
     instruction::{AccountMeta, Instruction},
@@ -11,56 +12,83 @@ use solana_sdk::{
     system_instruction,
     transaction::VersionedTransaction,
 };
-use spl_associated_token_account::{get_associated_token_address, instruction::create_associated_token_account};
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::{
+    create_associated_token_account, create_associated_token_account_idempotent,
+};
 use solana_sdk::system_program;
-use solana_sdk::native_token::LAMPORTS_PER_SOL;
 use anyhow::Result;
 use std::str::FromStr;
 use std::sync::Arc;
 use tracing::{info, debug, warn};
 
 use crate::tx_senders::constants::{
-    JITO_TIP_ADDR, PUMP_FUN_ACCOUNT_ADDR, PUMP_FUN_PROGRAM_ADDR, PUMP_FUN_TX_ADDR,
-    RENT_ADDR, SYSTEM_PROGRAM_ADDR, TOKEN_PROGRAM_ADDR,
+    JITO_TIP_ADDR, METEORA_DAMM_V2_PROGRAM_ID, METEORA_DLMM_PROGRAM_ID, RAYDIUM_AMM_V4_AUTHORITY,
+    RAYDIUM_AMM_V4_PROGRAM_ID, RAYDIUM_CPMM_PROGRAM_ID, WSOL_MINT, PUMP_FUN_ACCOUNT_ADDR, PUMP_FUN_FEE_RECIPIENT_ADDR, PUMP_FUN_PROGRAM_ADDR,
+    PUMP_FUN_TX_ADDR, RENT_ADDR, SYSTEM_PROGRAM_ADDR, TOKEN_PROGRAM_ADDR,
 };
 
+/// Дискриминатор Anchor: sha256("global:swap")[..8], общий для DLMM и DAMM v2
+const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+/// Дискриминатор Anchor: sha256("global:swap_base_input")[..8]
+const RAYDIUM_CPMM_SWAP_BASE_INPUT_DISCRIMINATOR: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+/// Тег инструкции swap_base_in Raydium AMM v4
+const RAYDIUM_SWAP_BASE_IN: u8 = 9;
+
 #[derive(Clone)]
 pub struct TransactionConfig {
     pub keypair: Arc<Keypair>,
     pub compute_unit_limit: u32,
     pub compute_unit_price: u64,
     pub tip: u64,
-    pub buy_amount: u64,
-    pub min_amount_out: u64,
+    /// Durable nonce аккаунт: `recent_blockhash` тогда должен быть его текущим значением
+    pub nonce_account: Option<Pubkey>,
 }
 
-impl From<PingThingsArgs> for TransactionConfig {
-    fn from(args: PingThingsArgs) -> Self {
-        let keypair = Keypair::from_base58_string(args.private_key.as_str());
-
+impl TransactionConfig {
+    pub fn new(args: &PingThingsArgs, keypair: Arc<Keypair>) -> Self {
         TransactionConfig {
-            keypair: Arc::new(keypair),
+            keypair,
             compute_unit_limit: args.compute_unit_limit,
-            compute_unit_price: args.compute_unit_price,
-            tip: (args.tip * LAMPORTS_PER_SOL as f64) as u64,
-            buy_amount: (args.buy_amount * LAMPORTS_PER_SOL as f64) as u64,
-            min_amount_out: (args.min_amount_out * 1_000_000.0) as u64,
+            compute_unit_price: args.compute_unit_price.0,
+            tip: args.tip.to_lamports().0,
+            nonce_account: None,
         }
     }
 }
 
+/// Сделка на bonding curve Pump.fun с лимитами, рассчитанными по котировке
+#[derive(Debug, Clone)]
+pub struct PumpFunTrade {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub associated_bonding_curve: Pubkey,
+    pub side: PumpFunSide,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PumpFunSide {
+    /// Покупка ровно `token_amount` токенов не дороже `max_sol_cost`
+    Buy { token_amount: u64, max_sol_cost: u64 },
+    /// Продажа `token_amount` токенов не дешевле `min_sol_output`
+    Sell { token_amount: u64, min_sol_output: u64 },
+}
+
 // Pump.fun
 pub fn build_transaction_with_config(
     tx_config: &TransactionConfig,
     rpc_type: &RpcType,
     recent_blockhash: Hash,
-    token_address: Pubkey,
-    bonding_curve: Pubkey,
-    associated_bonding_curve: Pubkey,
-) -> VersionedTransaction {
+    trade: &PumpFunTrade,
+) -> Result<VersionedTransaction> {
     info!("Сборка транзакции Pump.fun");
     let mut instructions = Vec::new();
 
+    if let Some(nonce_account) = tx_config.nonce_account {
+        instructions.push(system_instruction::advance_nonce_account(&nonce_account, &tx_config.keypair.pubkey()));
+        debug!("durable nonce: {}", nonce_account);
+    }
+
     if tx_config.compute_unit_limit > 0 {
         instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(tx_config.compute_unit_limit));
         debug!("compute_unit_limit: {}", tx_config.compute_unit_limit);
@@ -83,54 +111,68 @@ pub fn build_transaction_with_config(
     }
 
     let owner = tx_config.keypair.pubkey();
-    let token_program_pubkey = Pubkey::from_str(TOKEN_PROGRAM_ADDR).unwrap();
-    let user_token_account = get_associated_token_address(&owner, &token_address);
-    let ata_ix = create_associated_token_account(&owner, &owner, &token_address, &token_program_pubkey);
-    instructions.push(ata_ix);
+    if let PumpFunSide::Buy { .. } = trade.side {
+        let token_program_pubkey = Pubkey::from_str(TOKEN_PROGRAM_ADDR).unwrap();
+        let ata_ix = create_associated_token_account(&owner, &owner, &trade.mint, &token_program_pubkey);
+        instructions.push(ata_ix);
+    }
 
-    let mut data = vec![];
-    let buy: u64 = 16927863322537952870;
-    data.extend_from_slice(&buy.to_le_bytes());
-    data.extend_from_slice(&tx_config.min_amount_out.to_le_bytes());
-    data.extend_from_slice(&tx_config.buy_amount.to_le_bytes());
+    debug!("Подготовка инструкции {:?}", trade.side);
+    instructions.push(pump_fun_instruction(&owner, trade));
 
-    debug!("Подготовка инструкции swap");
+    let message_v0 = Message::try_compile(&owner, &instructions, &[], recent_blockhash)?;
+    let versioned_message = VersionedMessage::V0(message_v0);
+    let tx = VersionedTransaction::try_new(versioned_message, &[&tx_config.keypair])?;
 
-    let accounts = vec![
+    info!("Транзакция Pump.fun успешно собрана");
+
+    Ok(tx)
+}
+
+/// Инструкция buy/sell программы Pump.fun; порядок аккаунтов у них различается
+fn pump_fun_instruction(owner: &Pubkey, trade: &PumpFunTrade) -> Instruction {
+    let token_program_pubkey = Pubkey::from_str(TOKEN_PROGRAM_ADDR).unwrap();
+    let user_token_account = get_associated_token_address(owner, &trade.mint);
+
+    let mut accounts = vec![
         AccountMeta::new_readonly(Pubkey::from_str(PUMP_FUN_ACCOUNT_ADDR).unwrap(), false),
-        AccountMeta::new(Pubkey::from_str("CebN5WGQ4jvEPvsVU4EoHEpgzq1VV7AbicfhtW4xC9iM").unwrap(), false),
-        AccountMeta::new_readonly(token_address, false),
-        AccountMeta::new(bonding_curve, false),
-        AccountMeta::new(associated_bonding_curve, false),
+        AccountMeta::new(Pubkey::from_str(PUMP_FUN_FEE_RECIPIENT_ADDR).unwrap(), false),
+        AccountMeta::new_readonly(trade.mint, false),
+        AccountMeta::new(trade.bonding_curve, false),
+        AccountMeta::new(trade.associated_bonding_curve, false),
         AccountMeta::new(user_token_account, false),
-        AccountMeta::new(owner, true),
+        AccountMeta::new(*owner, true),
         AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM_ADDR).unwrap(), false),
-        AccountMeta::new_readonly(token_program_pubkey, false),
-        AccountMeta::new_readonly(Pubkey::from_str(RENT_ADDR).unwrap(), false),
-        AccountMeta::new_readonly(Pubkey::from_str(PUMP_FUN_TX_ADDR).unwrap(), false),
-        AccountMeta::new_readonly(Pubkey::from_str(PUMP_FUN_PROGRAM_ADDR).unwrap(), false),
     ];
 
-    let swap_instruction = Instruction {
+    let mut data = vec![];
+    match trade.side {
+        PumpFunSide::Buy { token_amount, max_sol_cost } => {
+            data.extend_from_slice(&BUY_DISCRIMINATOR);
+            data.extend_from_slice(&token_amount.to_le_bytes());
+            data.extend_from_slice(&max_sol_cost.to_le_bytes());
+            accounts.push(AccountMeta::new_readonly(token_program_pubkey, false));
+            accounts.push(AccountMeta::new_readonly(Pubkey::from_str(RENT_ADDR).unwrap(), false));
+        }
+        PumpFunSide::Sell { token_amount, min_sol_output } => {
+            data.extend_from_slice(&SELL_DISCRIMINATOR);
+            data.extend_from_slice(&token_amount.to_le_bytes());
+            data.extend_from_slice(&min_sol_output.to_le_bytes());
+            accounts.push(AccountMeta::new_readonly(spl_associated_token_account::id(), false));
+            accounts.push(AccountMeta::new_readonly(token_program_pubkey, false));
+        }
+    }
+    accounts.push(AccountMeta::new_readonly(Pubkey::from_str(PUMP_FUN_TX_ADDR).unwrap(), false));
+    accounts.push(AccountMeta::new_readonly(Pubkey::from_str(PUMP_FUN_PROGRAM_ADDR).unwrap(), false));
+
+    Instruction {
         program_id: Pubkey::from_str(PUMP_FUN_PROGRAM_ADDR).unwrap(),
         accounts,
         data,
-    };
-
-    instructions.push(swap_instruction);
-
-    let message_v0 = Message::try_compile(&owner, &instructions, &[], recent_blockhash).unwrap();
-    let versioned_message = VersionedMessage::V0(message_v0);
-    let tx = VersionedTransaction::try_new(versioned_message, &[&tx_config.keypair]).unwrap();
-
-    info!("Транзакция Pump.fun успешно собрана");
-
-    tx
+    }
 }
-
 // Meteora
-pub async fn build_swap_transaction(
-    args: &PingThingsArgs,
+pub fn build_swap_transaction(
     user: &Keypair,
     pool: &Pubkey,
     token_a: &Pubkey,
@@ -147,19 +189,30 @@ pub async fn build_swap_transaction(
     vault_program: &Pubkey,
     amount_in: u64,
     min_out: u64,
+    compute_unit_price: MicroLamports,
+    compute_unit_limit: u32,
+    recent_blockhash: Hash,
+    nonce_account: Option<Pubkey>,
+    lookup_tables: &[AddressLookupTableAccount],
 ) -> Result<VersionedTransaction> {
     info!("Сборка транзакции Meteora");
     let mut instructions: Vec<Instruction> = Vec::new();
     let owner = user.pubkey();
 
-    if args.compute_unit_limit > 0 {
-        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(args.compute_unit_limit));
-        debug!("compute_unit_limit: {}", args.compute_unit_limit);
+    // advance_nonce_account обязана быть первой инструкцией
+    if let Some(nonce_account) = nonce_account {
+        instructions.push(system_instruction::advance_nonce_account(&nonce_account, &owner));
+        debug!("durable nonce: {}", nonce_account);
     }
 
-    if args.compute_unit_price > 0 {
-        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(args.compute_unit_price));
-        debug!("compute_unit_price: {}", args.compute_unit_price);
+    if compute_unit_limit > 0 {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit));
+        debug!("compute_unit_limit: {}", compute_unit_limit);
+    }
+
+    if compute_unit_price.0 > 0 {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price.0));
+        debug!("compute_unit_price: {}", compute_unit_price);
     }
 
     let user_token_account = get_associated_token_address(&owner, token_a);
@@ -195,16 +248,416 @@ pub async fn build_swap_transaction(
 
     instructions.push(swap_instruction);
 
-    let rpc = RpcClient::new(args.http_rpc.clone());
-    info!("Получение blockhash...");
-    let blockhash = rpc.get_latest_blockhash().await?;
-    debug!("Blockhash: {:?}", blockhash);
-
-    let message = Message::try_compile(&owner, &instructions, &[], blockhash)?;
+    debug!("Blockhash: {:?}", recent_blockhash);
+    let message = Message::try_compile(&owner, &instructions, lookup_tables, recent_blockhash)?;
+    let looked_up: usize = message
+        .address_table_lookups
+        .iter()
+        .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+        .sum();
+    debug!("Аккаунтов в сообщении: {} (из lookup tables: {})", message.account_keys.len(), looked_up);
     let versioned_message = VersionedMessage::V0(message);
 
     let transaction = VersionedTransaction::try_new(versioned_message, &[user])?;
     info!("Транзакция Meteora успешно собрана");
 
     Ok(transaction)
-}
\ No newline at end of file
+}
+
+/// Durable nonce и compute budget: начало транзакций свапа
+fn budget_instructions(
+    owner: &Pubkey,
+    nonce_account: Option<Pubkey>,
+    compute_unit_limit: u32,
+    compute_unit_price: MicroLamports,
+) -> Vec<Instruction> {
+    let mut instructions: Vec<Instruction> = Vec::new();
+
+    // advance_nonce_account обязана быть первой инструкцией
+    if let Some(nonce_account) = nonce_account {
+        instructions.push(system_instruction::advance_nonce_account(&nonce_account, owner));
+        debug!("durable nonce: {}", nonce_account);
+    }
+
+    if compute_unit_limit > 0 {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit));
+        debug!("compute_unit_limit: {}", compute_unit_limit);
+    }
+
+    if compute_unit_price.0 > 0 {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price.0));
+        debug!("compute_unit_price: {}", compute_unit_price);
+    }
+
+    instructions
+}
+
+/// ATA входа и выхода свапа. Покупка за SOL: вход оборачивается в WSOL,
+/// остаток возвращается закрытием аккаунта в [`unwrap_sol`]
+fn swap_token_accounts(
+    instructions: &mut Vec<Instruction>,
+    owner: &Pubkey,
+    input_mint: &Pubkey,
+    output_mint: &Pubkey,
+    amount_in: u64,
+) -> Result<(Pubkey, Pubkey)> {
+    let token_program_id = Pubkey::from_str(TOKEN_PROGRAM_ADDR).unwrap();
+    let user_token_in = get_associated_token_address(owner, input_mint);
+    let user_token_out = get_associated_token_address(owner, output_mint);
+
+    instructions.push(create_associated_token_account_idempotent(owner, owner, input_mint, &token_program_id));
+    if *input_mint == Pubkey::from_str(WSOL_MINT).unwrap() {
+        instructions.push(system_instruction::transfer(owner, &user_token_in, amount_in));
+        instructions.push(spl_token::instruction::sync_native(&token_program_id, &user_token_in)?);
+    }
+    instructions.push(create_associated_token_account_idempotent(owner, owner, output_mint, &token_program_id));
+
+    Ok((user_token_in, user_token_out))
+}
+
+fn unwrap_sol(
+    instructions: &mut Vec<Instruction>,
+    owner: &Pubkey,
+    input_mint: &Pubkey,
+    user_token_in: &Pubkey,
+) -> Result<()> {
+    if *input_mint == Pubkey::from_str(WSOL_MINT).unwrap() {
+        let token_program_id = Pubkey::from_str(TOKEN_PROGRAM_ADDR).unwrap();
+        instructions.push(spl_token::instruction::close_account(
+            &token_program_id,
+            user_token_in,
+            owner,
+            owner,
+            &[],
+        )?);
+    }
+    Ok(())
+}
+
+fn compile_transaction(
+    user: &Keypair,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> Result<VersionedTransaction> {
+    let message = Message::try_compile(&user.pubkey(), instructions, lookup_tables, recent_blockhash)?;
+    let versioned_message = VersionedMessage::V0(message);
+
+    Ok(VersionedTransaction::try_new(versioned_message, &[user])?)
+}
+
+/// Аккаунты свапа в паре DLMM
+#[derive(Debug, Clone)]
+pub struct DlmmSwapAccounts {
+    pub lb_pair: Pubkey,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+    pub token_x_mint: Pubkey,
+    pub token_y_mint: Pubkey,
+    pub oracle: Pubkey,
+    /// Bin arrays в порядке прохода свапа
+    pub bin_arrays: Vec<Pubkey>,
+}
+
+// Meteora DLMM
+pub fn build_dlmm_swap_transaction(
+    user: &Keypair,
+    accounts: &DlmmSwapAccounts,
+    input_mint: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    compute_unit_price: MicroLamports,
+    compute_unit_limit: u32,
+    recent_blockhash: Hash,
+    nonce_account: Option<Pubkey>,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Result<VersionedTransaction> {
+    info!("Сборка транзакции Meteora DLMM");
+    let owner = user.pubkey();
+    let token_program_id = Pubkey::from_str(TOKEN_PROGRAM_ADDR).unwrap();
+    let mut instructions = budget_instructions(&owner, nonce_account, compute_unit_limit, compute_unit_price);
+
+    let output_mint = if *input_mint == accounts.token_x_mint {
+        accounts.token_y_mint
+    } else {
+        accounts.token_x_mint
+    };
+    let (user_token_in, user_token_out) =
+        swap_token_accounts(&mut instructions, &owner, input_mint, &output_mint, amount_in)?;
+
+    let mut data = SWAP_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+    debug!("Подготовка инструкции swap DLMM, bin arrays: {:?}", accounts.bin_arrays);
+
+    // Необязательные аккаунты Anchor (bitmap extension, host fee) передаются адресом программы
+    let (event_authority, _) = Pubkey::find_program_address(&[b"__event_authority"], &METEORA_DLMM_PROGRAM_ID);
+    let mut swap_accounts = vec![
+        AccountMeta::new(accounts.lb_pair, false),
+        AccountMeta::new_readonly(METEORA_DLMM_PROGRAM_ID, false),
+        AccountMeta::new(accounts.reserve_x, false),
+        AccountMeta::new(accounts.reserve_y, false),
+        AccountMeta::new(user_token_in, false),
+        AccountMeta::new(user_token_out, false),
+        AccountMeta::new_readonly(accounts.token_x_mint, false),
+        AccountMeta::new_readonly(accounts.token_y_mint, false),
+        AccountMeta::new(accounts.oracle, false),
+        AccountMeta::new_readonly(METEORA_DLMM_PROGRAM_ID, false),
+        AccountMeta::new(owner, true),
+        AccountMeta::new_readonly(token_program_id, false),
+        AccountMeta::new_readonly(token_program_id, false),
+        AccountMeta::new_readonly(event_authority, false),
+        AccountMeta::new_readonly(METEORA_DLMM_PROGRAM_ID, false),
+    ];
+    swap_accounts.extend(accounts.bin_arrays.iter().map(|array| AccountMeta::new(*array, false)));
+
+    instructions.push(Instruction {
+        program_id: METEORA_DLMM_PROGRAM_ID,
+        accounts: swap_accounts,
+        data,
+    });
+    unwrap_sol(&mut instructions, &owner, input_mint, &user_token_in)?;
+
+    let transaction = compile_transaction(user, &instructions, lookup_tables, recent_blockhash)?;
+    info!("Транзакция Meteora DLMM успешно собрана");
+
+    Ok(transaction)
+}
+
+/// Аккаунты свапа в пуле DAMM v2
+#[derive(Debug, Clone)]
+pub struct DammV2SwapAccounts {
+    pub pool: Pubkey,
+    pub pool_authority: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+}
+
+// Meteora DAMM v2
+pub fn build_damm_v2_swap_transaction(
+    user: &Keypair,
+    accounts: &DammV2SwapAccounts,
+    input_mint: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    compute_unit_price: MicroLamports,
+    compute_unit_limit: u32,
+    recent_blockhash: Hash,
+    nonce_account: Option<Pubkey>,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Result<VersionedTransaction> {
+    info!("Сборка транзакции Meteora DAMM v2");
+    let owner = user.pubkey();
+    let token_program_id = Pubkey::from_str(TOKEN_PROGRAM_ADDR).unwrap();
+    let mut instructions = budget_instructions(&owner, nonce_account, compute_unit_limit, compute_unit_price);
+
+    let output_mint = if *input_mint == accounts.token_a_mint {
+        accounts.token_b_mint
+    } else {
+        accounts.token_a_mint
+    };
+    let (user_token_in, user_token_out) =
+        swap_token_accounts(&mut instructions, &owner, input_mint, &output_mint, amount_in)?;
+
+    let mut data = SWAP_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+    debug!("Подготовка инструкции swap DAMM v2, пул: {}", accounts.pool);
+
+    // Реферальный аккаунт необязателен и передаётся адресом программы
+    let (event_authority, _) = Pubkey::find_program_address(&[b"__event_authority"], &METEORA_DAMM_V2_PROGRAM_ID);
+    let swap_accounts = vec![
+        AccountMeta::new_readonly(accounts.pool_authority, false),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new(user_token_in, false),
+        AccountMeta::new(user_token_out, false),
+        AccountMeta::new(accounts.token_a_vault, false),
+        AccountMeta::new(accounts.token_b_vault, false),
+        AccountMeta::new_readonly(accounts.token_a_mint, false),
+        AccountMeta::new_readonly(accounts.token_b_mint, false),
+        AccountMeta::new(owner, true),
+        AccountMeta::new_readonly(token_program_id, false),
+        AccountMeta::new_readonly(token_program_id, false),
+        AccountMeta::new_readonly(METEORA_DAMM_V2_PROGRAM_ID, false),
+        AccountMeta::new_readonly(event_authority, false),
+        AccountMeta::new_readonly(METEORA_DAMM_V2_PROGRAM_ID, false),
+    ];
+
+    instructions.push(Instruction {
+        program_id: METEORA_DAMM_V2_PROGRAM_ID,
+        accounts: swap_accounts,
+        data,
+    });
+    unwrap_sol(&mut instructions, &owner, input_mint, &user_token_in)?;
+
+    let transaction = compile_transaction(user, &instructions, lookup_tables, recent_blockhash)?;
+    info!("Транзакция Meteora DAMM v2 успешно собрана");
+
+    Ok(transaction)
+}
+
+/// Аккаунты свапа в пуле Raydium AMM v4, включая рынок OpenBook
+#[derive(Debug, Clone)]
+pub struct RaydiumAmmV4SwapAccounts {
+    pub amm: Pubkey,
+    pub open_orders: Pubkey,
+    pub target_orders: Pubkey,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub market_program: Pubkey,
+    pub market: Pubkey,
+    pub market_bids: Pubkey,
+    pub market_asks: Pubkey,
+    pub market_event_queue: Pubkey,
+    pub market_coin_vault: Pubkey,
+    pub market_pc_vault: Pubkey,
+    pub market_vault_signer: Pubkey,
+}
+
+// Raydium AMM v4
+pub fn build_raydium_amm_v4_swap_transaction(
+    user: &Keypair,
+    accounts: &RaydiumAmmV4SwapAccounts,
+    input_mint: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    compute_unit_price: MicroLamports,
+    compute_unit_limit: u32,
+    recent_blockhash: Hash,
+    nonce_account: Option<Pubkey>,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Result<VersionedTransaction> {
+    info!("Сборка транзакции Raydium AMM v4");
+    let owner = user.pubkey();
+    let token_program_id = Pubkey::from_str(TOKEN_PROGRAM_ADDR).unwrap();
+    let mut instructions = budget_instructions(&owner, nonce_account, compute_unit_limit, compute_unit_price);
+
+    let output_mint = if *input_mint == accounts.coin_mint {
+        accounts.pc_mint
+    } else {
+        accounts.coin_mint
+    };
+    let (user_token_in, user_token_out) =
+        swap_token_accounts(&mut instructions, &owner, input_mint, &output_mint, amount_in)?;
+
+    // Не Anchor: тег инструкции swap_base_in и два u64
+    let mut data = vec![RAYDIUM_SWAP_BASE_IN];
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+    debug!("Подготовка инструкции swap_base_in Raydium, пул: {}", accounts.amm);
+
+    let swap_accounts = vec![
+        AccountMeta::new_readonly(token_program_id, false),
+        AccountMeta::new(accounts.amm, false),
+        AccountMeta::new_readonly(RAYDIUM_AMM_V4_AUTHORITY, false),
+        AccountMeta::new(accounts.open_orders, false),
+        AccountMeta::new(accounts.target_orders, false),
+        AccountMeta::new(accounts.coin_vault, false),
+        AccountMeta::new(accounts.pc_vault, false),
+        AccountMeta::new_readonly(accounts.market_program, false),
+        AccountMeta::new(accounts.market, false),
+        AccountMeta::new(accounts.market_bids, false),
+        AccountMeta::new(accounts.market_asks, false),
+        AccountMeta::new(accounts.market_event_queue, false),
+        AccountMeta::new(accounts.market_coin_vault, false),
+        AccountMeta::new(accounts.market_pc_vault, false),
+        AccountMeta::new_readonly(accounts.market_vault_signer, false),
+        AccountMeta::new(user_token_in, false),
+        AccountMeta::new(user_token_out, false),
+        AccountMeta::new_readonly(owner, true),
+    ];
+
+    instructions.push(Instruction {
+        program_id: RAYDIUM_AMM_V4_PROGRAM_ID,
+        accounts: swap_accounts,
+        data,
+    });
+    unwrap_sol(&mut instructions, &owner, input_mint, &user_token_in)?;
+
+    let transaction = compile_transaction(user, &instructions, lookup_tables, recent_blockhash)?;
+    info!("Транзакция Raydium AMM v4 успешно собрана");
+
+    Ok(transaction)
+}
+
+/// Аккаунты свапа в пуле Raydium CPMM
+#[derive(Debug, Clone)]
+pub struct RaydiumCpmmSwapAccounts {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub amm_config: Pubkey,
+    pub observation: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+}
+
+// Raydium CPMM
+pub fn build_raydium_cpmm_swap_transaction(
+    user: &Keypair,
+    accounts: &RaydiumCpmmSwapAccounts,
+    input_mint: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    compute_unit_price: MicroLamports,
+    compute_unit_limit: u32,
+    recent_blockhash: Hash,
+    nonce_account: Option<Pubkey>,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Result<VersionedTransaction> {
+    info!("Сборка транзакции Raydium CPMM");
+    let owner = user.pubkey();
+    let token_program_id = Pubkey::from_str(TOKEN_PROGRAM_ADDR).unwrap();
+    let mut instructions = budget_instructions(&owner, nonce_account, compute_unit_limit, compute_unit_price);
+
+    // Хранилища передаются в порядке вход/выход, а не token_0/token_1
+    let (output_mint, input_vault, output_vault) = if *input_mint == accounts.token_0_mint {
+        (accounts.token_1_mint, accounts.token_0_vault, accounts.token_1_vault)
+    } else {
+        (accounts.token_0_mint, accounts.token_1_vault, accounts.token_0_vault)
+    };
+    let (user_token_in, user_token_out) =
+        swap_token_accounts(&mut instructions, &owner, input_mint, &output_mint, amount_in)?;
+
+    let mut data = RAYDIUM_CPMM_SWAP_BASE_INPUT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+    debug!("Подготовка инструкции swap_base_input Raydium CPMM, пул: {}", accounts.pool);
+
+    let swap_accounts = vec![
+        AccountMeta::new(owner, true),
+        AccountMeta::new_readonly(accounts.authority, false),
+        AccountMeta::new_readonly(accounts.amm_config, false),
+        AccountMeta::new(accounts.pool, false),
+        AccountMeta::new(user_token_in, false),
+        AccountMeta::new(user_token_out, false),
+        AccountMeta::new(input_vault, false),
+        AccountMeta::new(output_vault, false),
+        AccountMeta::new_readonly(token_program_id, false),
+        AccountMeta::new_readonly(token_program_id, false),
+        AccountMeta::new_readonly(*input_mint, false),
+        AccountMeta::new_readonly(output_mint, false),
+        AccountMeta::new(accounts.observation, false),
+    ];
+
+    instructions.push(Instruction {
+        program_id: RAYDIUM_CPMM_PROGRAM_ID,
+        accounts: swap_accounts,
+        data,
+    });
+    unwrap_sol(&mut instructions, &owner, input_mint, &user_token_in)?;
+
+    let transaction = compile_transaction(user, &instructions, lookup_tables, recent_blockhash)?;
+    info!("Транзакция Raydium CPMM успешно собрана");
+
+    Ok(transaction)
+}
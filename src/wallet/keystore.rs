@@ -0,0 +1,138 @@
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::Aes256Gcm;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use solana_sdk::signature::{Keypair, Signer};
+use zeroize::Zeroizing;
+
+const KEYSTORE_VERSION: u32 = 1;
+const KDF_NAME: &str = "pbkdf2-sha256";
+const KDF_ITERATIONS: u32 = 600_000;
+
+/// Зашифрованный паролем keystore: PBKDF2-SHA256 + AES-256-GCM
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u32,
+    pub pubkey: String,
+    pub kdf: String,
+    pub iterations: u32,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+impl Keystore {
+    pub fn encrypt(keypair: &Keypair, passphrase: &str) -> Result<Self, String> {
+        Self::encrypt_with_iterations(keypair, passphrase, KDF_ITERATIONS)
+    }
+
+    fn encrypt_with_iterations(keypair: &Keypair, passphrase: &str, iterations: u32) -> Result<Self, String> {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+
+        let key = derive_key(passphrase, &salt, iterations);
+        let cipher = Aes256Gcm::new_from_slice(&key[..]).map_err(|e| e.to_string())?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+        let secret = Zeroizing::new(keypair.to_bytes());
+        let ciphertext = cipher
+            .encrypt(&nonce, &secret[..])
+            .map_err(|_| "ошибка шифрования".to_string())?;
+
+        Ok(Self {
+            version: KEYSTORE_VERSION,
+            pubkey: keypair.pubkey().to_string(),
+            kdf: KDF_NAME.to_string(),
+            iterations,
+            salt: STANDARD.encode(salt),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        })
+    }
+
+    pub fn decrypt(&self, passphrase: &str) -> Result<Keypair, String> {
+        if self.version != KEYSTORE_VERSION || self.kdf != KDF_NAME {
+            return Err(format!(
+                "неподдерживаемый формат (version {}, kdf {})",
+                self.version, self.kdf
+            ));
+        }
+
+        let decode = |field: &str, value: &str| {
+            STANDARD
+                .decode(value)
+                .map_err(|_| format!("поле {field} не является base64"))
+        };
+        let salt = decode("salt", &self.salt)?;
+        let nonce = decode("nonce", &self.nonce)?;
+        let ciphertext = decode("ciphertext", &self.ciphertext)?;
+        if nonce.len() != 12 {
+            return Err("некорректная длина nonce".to_string());
+        }
+
+        let key = derive_key(passphrase, &salt, self.iterations);
+        let cipher = Aes256Gcm::new_from_slice(&key[..]).map_err(|e| e.to_string())?;
+        let secret = Zeroizing::new(
+            cipher
+                .decrypt(GenericArray::from_slice(&nonce), &ciphertext[..])
+                .map_err(|_| "неверный пароль или повреждённый файл".to_string())?,
+        );
+
+        let keypair = Keypair::from_bytes(&secret[..]).map_err(|e| e.to_string())?;
+        if keypair.pubkey().to_string() != self.pubkey {
+            return Err("публичный ключ не совпадает с расшифрованным".to_string());
+        }
+
+        Ok(keypair)
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Zeroizing<[u8; 32]> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key[..]);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Полные 600 000 итераций PBKDF2 слишком медленны для отладочной сборки тестов
+    const TEST_ITERATIONS: u32 = 1_000;
+
+    #[test]
+    fn decrypt_restores_encrypted_keypair() {
+        let keypair = Keypair::new();
+        let keystore = Keystore::encrypt_with_iterations(&keypair, "correct horse", TEST_ITERATIONS).unwrap();
+
+        // Формат переживает сохранение в файл
+        let json = serde_json::to_string(&keystore).unwrap();
+        let keystore: Keystore = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(keystore.pubkey, keypair.pubkey().to_string());
+        let decrypted = keystore.decrypt("correct horse").unwrap();
+        assert_eq!(decrypted.to_bytes(), keypair.to_bytes());
+    }
+
+    #[test]
+    fn decrypt_rejects_wrong_passphrase() {
+        let keypair = Keypair::new();
+        let keystore = Keystore::encrypt_with_iterations(&keypair, "correct horse", TEST_ITERATIONS).unwrap();
+
+        let err = keystore.decrypt("battery staple").unwrap_err();
+        assert_eq!(err, "неверный пароль или повреждённый файл");
+    }
+
+    #[test]
+    fn ciphertext_does_not_contain_secret() {
+        let keypair = Keypair::new();
+        let keystore = Keystore::encrypt_with_iterations(&keypair, "correct horse", TEST_ITERATIONS).unwrap();
+
+        let ciphertext = STANDARD.decode(&keystore.ciphertext).unwrap();
+        assert!(!ciphertext.windows(32).any(|window| window == &keypair.to_bytes()[..32]));
+    }
+}
@@ -0,0 +1,164 @@
+pub mod keystore;
+pub mod pool;
+
+use crate::config::{KeypairConfig, PingThingsArgs, ENV_PREFIX};
+use crate::wallet::keystore::Keystore;
+
+use solana_sdk::signature::Keypair;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+#[derive(Error, Debug)]
+pub enum KeypairError {
+    #[error("ключ не задан: укажите private_key, keypair.env, keypair.path или keypair.keystore")]
+    Missing,
+
+    #[error("задано несколько источников ключа ({0}), оставьте один")]
+    Ambiguous(String),
+
+    #[error("переменная окружения {0} не задана")]
+    EnvMissing(String),
+
+    #[error("не удалось прочитать {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("{origin}: {message}")]
+    Invalid { origin: String, message: String },
+}
+
+/// Источник ключа, выбранный из конфигурации
+enum KeypairSource<'a> {
+    Base58(&'a str),
+    Env(&'a str),
+    File(PathBuf),
+    Keystore(PathBuf),
+}
+
+/// Загрузка основного ключа кошелька из единственного настроенного источника
+pub fn load_keypair(args: &PingThingsArgs) -> Result<Keypair, KeypairError> {
+    load_keypair_from(&args.private_key, &args.keypair)
+}
+
+pub fn load_keypair_from(
+    private_key: &str,
+    config: &KeypairConfig,
+) -> Result<Keypair, KeypairError> {
+    match keypair_source(private_key, config)? {
+        KeypairSource::Base58(raw) => parse_keypair("private_key", raw),
+        KeypairSource::Env(var) => {
+            let raw = Zeroizing::new(
+                std::env::var(var).map_err(|_| KeypairError::EnvMissing(var.to_string()))?,
+            );
+            parse_keypair(var, &raw)
+        }
+        KeypairSource::File(path) => read_keypair_file(&path),
+        KeypairSource::Keystore(path) => {
+            let passphrase = read_passphrase(&format!("Пароль keystore {}: ", path.display()))?;
+            read_keystore(&path, &passphrase)
+        }
+    }
+}
+
+fn keypair_source<'a>(
+    private_key: &'a str,
+    config: &'a KeypairConfig,
+) -> Result<KeypairSource<'a>, KeypairError> {
+    let mut sources = Vec::new();
+
+    if !private_key.is_empty() {
+        sources.push(("private_key", KeypairSource::Base58(private_key)));
+    }
+    if let Some(var) = &config.env {
+        sources.push(("keypair.env", KeypairSource::Env(var)));
+    }
+    if let Some(path) = &config.path {
+        sources.push(("keypair.path", KeypairSource::File(expand_home(path))));
+    }
+    if let Some(path) = &config.keystore {
+        sources.push(("keypair.keystore", KeypairSource::Keystore(expand_home(path))));
+    }
+
+    match sources.len() {
+        0 => Err(KeypairError::Missing),
+        1 => Ok(sources.remove(0).1),
+        _ => Err(KeypairError::Ambiguous(
+            sources.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", "),
+        )),
+    }
+}
+
+/// Разбор ключа из строки: base58 или JSON-массив байт в формате Solana CLI
+pub fn parse_keypair(origin: &str, raw: &str) -> Result<Keypair, KeypairError> {
+    let invalid = |message: String| KeypairError::Invalid {
+        origin: origin.to_string(),
+        message,
+    };
+    let raw = raw.trim();
+
+    let bytes = if raw.starts_with('[') {
+        Zeroizing::new(
+            serde_json::from_str::<Vec<u8>>(raw)
+                .map_err(|_| invalid("ожидается JSON-массив байт".to_string()))?,
+        )
+    } else {
+        Zeroizing::new(
+            bs58::decode(raw)
+                .into_vec()
+                .map_err(|_| invalid("ожидается base58-строка".to_string()))?,
+        )
+    };
+
+    if bytes.len() != 64 {
+        return Err(invalid(format!("ожидается 64 байта, получено {}", bytes.len())));
+    }
+
+    Keypair::from_bytes(&bytes[..]).map_err(|e| invalid(e.to_string()))
+}
+
+/// Чтение `id.json` в формате `solana-keygen`
+pub fn read_keypair_file(path: &Path) -> Result<Keypair, KeypairError> {
+    let content = Zeroizing::new(fs::read_to_string(path).map_err(|source| KeypairError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?);
+    parse_keypair(&path.display().to_string(), &content)
+}
+
+pub fn read_keystore(path: &Path, passphrase: &str) -> Result<Keypair, KeypairError> {
+    let content = fs::read_to_string(path).map_err(|source| KeypairError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let invalid = |message: String| KeypairError::Invalid {
+        origin: path.display().to_string(),
+        message,
+    };
+
+    let keystore: Keystore =
+        serde_json::from_str(&content).map_err(|e| invalid(format!("некорректный keystore: {e}")))?;
+    keystore.decrypt(passphrase).map_err(invalid)
+}
+
+/// Пароль из `SNIPER_KEYSTORE_PASSPHRASE`, иначе интерактивный ввод
+pub fn read_passphrase(prompt: &str) -> Result<Zeroizing<String>, KeypairError> {
+    let var = format!("{ENV_PREFIX}KEYSTORE_PASSPHRASE");
+    if let Ok(passphrase) = std::env::var(&var) {
+        return Ok(Zeroizing::new(passphrase));
+    }
+
+    rpassword::prompt_password(prompt)
+        .map(Zeroizing::new)
+        .map_err(|_| KeypairError::EnvMissing(var))
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
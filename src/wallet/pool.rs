@@ -0,0 +1,398 @@
+use crate::config::{Lamports, PingThingsArgs, WalletSelection};
+use crate::metrics::WALLET_EXPOSURE_LAMPORTS;
+use crate::strategy::Venue;
+use crate::wallet::{load_keypair, load_keypair_from, KeypairError};
+
+use rand::Rng;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+#[derive(Clone)]
+pub struct Wallet {
+    pub name: String,
+    pub keypair: Arc<Keypair>,
+    /// Максимальная суммарная экспозиция кошелька, `None` — без ограничения
+    pub budget: Option<Lamports>,
+}
+
+impl Wallet {
+    pub fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+}
+
+/// Открытая позиция кошелька по пулу
+#[derive(Debug, Clone)]
+pub struct Position {
+    pub pool: Pubkey,
+    /// Площадка покупки; `None` — bonding curve Pump.fun (`pool` — mint токена)
+    pub venue: Option<Venue>,
+    pub amount_in: Lamports,
+    pub signature: Option<Signature>,
+    pub opened_at: Instant,
+    /// Фактический результат покупки по балансам транзакции, когда он известен
+    pub fill: Option<Fill>,
+}
+
+/// Фактически исполненная покупка
+#[derive(Debug, Clone)]
+pub struct Fill {
+    pub mint: Pubkey,
+    /// Полученные токены в минимальных единицах
+    pub tokens_received: u64,
+    pub decimals: u8,
+    /// Списано с кошелька, включая комиссии и ренту созданных аккаунтов
+    pub sol_spent: Lamports,
+}
+
+#[derive(Default)]
+struct WalletState {
+    /// Суммы, зарезервированные под сделки в процессе отправки
+    reserved: u64,
+    positions: HashMap<Pubkey, Position>,
+}
+
+impl WalletState {
+    fn exposure(&self) -> u64 {
+        self.reserved + self.positions.values().map(|p| p.amount_in.0).sum::<u64>()
+    }
+}
+
+/// Набор кошельков для параллельных покупок с учётом бюджета каждого
+pub struct WalletPool {
+    wallets: Vec<Wallet>,
+    selection: WalletSelection,
+    cursor: AtomicUsize,
+    state: Mutex<HashMap<Pubkey, WalletState>>,
+}
+
+/// Кошелёк, выбранный под сделку, с зарезервированной суммой
+#[derive(Clone)]
+pub struct WalletLease {
+    pub wallet: Wallet,
+    pub amount: Lamports,
+}
+
+impl WalletPool {
+    /// Кошельки из секции `wallets`, либо единственный основной ключ без ограничения бюджета
+    pub fn from_config(args: &PingThingsArgs) -> Result<Self, KeypairError> {
+        let wallets = if args.wallets.is_empty() {
+            vec![Wallet {
+                name: "main".to_string(),
+                keypair: Arc::new(load_keypair(args)?),
+                budget: None,
+            }]
+        } else {
+            args.wallets
+                .iter()
+                .map(|config| {
+                    Ok(Wallet {
+                        name: config.name.clone(),
+                        keypair: Arc::new(load_keypair_from(&config.private_key, &config.keypair)?),
+                        budget: config.budget.map(|budget| budget.to_lamports()),
+                    })
+                })
+                .collect::<Result<Vec<_>, KeypairError>>()?
+        };
+
+        Ok(Self::new(wallets, args.wallet_selection))
+    }
+
+    pub fn new(wallets: Vec<Wallet>, selection: WalletSelection) -> Self {
+        let state = wallets
+            .iter()
+            .map(|wallet| (wallet.pubkey(), WalletState::default()))
+            .collect();
+
+        Self {
+            wallets,
+            selection,
+            cursor: AtomicUsize::new(0),
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn wallets(&self) -> &[Wallet] {
+        &self.wallets
+    }
+
+    /// Выбор кошелька под сделку на `amount` с резервированием суммы.
+    /// Кошельки без остатка бюджета и с уже открытой позицией по пулу пропускаются.
+    pub fn acquire(&self, pool: &Pubkey, amount: Lamports) -> Option<WalletLease> {
+        let mut state = self.state.lock().unwrap();
+
+        let candidates: Vec<usize> = self
+            .wallets
+            .iter()
+            .enumerate()
+            .filter(|(_, wallet)| {
+                let wallet_state = &state[&wallet.pubkey()];
+                let fits = wallet
+                    .budget
+                    .map_or(true, |budget| wallet_state.exposure() + amount.0 <= budget.0);
+                fits && !wallet_state.positions.contains_key(pool)
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let index = match self.selection {
+            WalletSelection::RoundRobin => {
+                let start = self.cursor.fetch_add(1, Ordering::Relaxed) % self.wallets.len();
+                *candidates
+                    .iter()
+                    .min_by_key(|&&index| (index + self.wallets.len() - start) % self.wallets.len())
+                    .unwrap()
+            }
+            WalletSelection::LeastExposure => *candidates
+                .iter()
+                .min_by_key(|&&index| state[&self.wallets[index].pubkey()].exposure())
+                .unwrap(),
+            WalletSelection::Random => candidates[rand::thread_rng().gen_range(0..candidates.len())],
+        };
+
+        let wallet = self.wallets[index].clone();
+        let wallet_state = state.get_mut(&wallet.pubkey()).unwrap();
+        wallet_state.reserved += amount.0;
+        WALLET_EXPOSURE_LAMPORTS
+            .with_label_values(&[&wallet.name])
+            .set(wallet_state.exposure() as i64);
+
+        Some(WalletLease { wallet, amount })
+    }
+
+    /// Сделка прошла: резерв превращается в открытую позицию
+    pub fn open_position(
+        &self,
+        lease: &WalletLease,
+        pool: Pubkey,
+        venue: Option<Venue>,
+        signature: Option<Signature>,
+    ) {
+        let mut state = self.state.lock().unwrap();
+        let wallet_state = state.get_mut(&lease.wallet.pubkey()).unwrap();
+
+        wallet_state.reserved = wallet_state.reserved.saturating_sub(lease.amount.0);
+        wallet_state.positions.insert(
+            pool,
+            Position {
+                pool,
+                venue,
+                amount_in: lease.amount,
+                signature,
+                opened_at: Instant::now(),
+                fill: None,
+            },
+        );
+        WALLET_EXPOSURE_LAMPORTS
+            .with_label_values(&[&lease.wallet.name])
+            .set(wallet_state.exposure() as i64);
+    }
+
+    /// Сделка не состоялась: резерв снимается
+    pub fn release(&self, lease: &WalletLease) {
+        let mut state = self.state.lock().unwrap();
+        let wallet_state = state.get_mut(&lease.wallet.pubkey()).unwrap();
+
+        wallet_state.reserved = wallet_state.reserved.saturating_sub(lease.amount.0);
+        WALLET_EXPOSURE_LAMPORTS
+            .with_label_values(&[&lease.wallet.name])
+            .set(wallet_state.exposure() as i64);
+    }
+
+    /// Фактическое исполнение открытой позиции. Если транзакцию не удалось получить
+    /// или токены не пришли, позиция закрывается и больше не занимает бюджет кошелька;
+    /// тогда возвращается `false`.
+    pub fn record_fill(&self, wallet: &Pubkey, pool: &Pubkey, fill: Option<Fill>) -> bool {
+        let Some(fill) = fill.filter(|fill| fill.tokens_received > 0) else {
+            self.close_position(wallet, pool);
+            return false;
+        };
+
+        let mut state = self.state.lock().unwrap();
+        if let Some(position) = state
+            .get_mut(wallet)
+            .and_then(|wallet_state| wallet_state.positions.get_mut(pool))
+        {
+            position.fill = Some(fill);
+        }
+        true
+    }
+
+    /// Закрытие позиции (после продажи или неисполненной покупки)
+    pub fn close_position(&self, wallet: &Pubkey, pool: &Pubkey) -> Option<Position> {
+        let mut state = self.state.lock().unwrap();
+        let wallet_state = state.get_mut(wallet)?;
+        let position = wallet_state.positions.remove(pool);
+
+        if let Some(named) = self.wallets.iter().find(|w| w.pubkey() == *wallet) {
+            WALLET_EXPOSURE_LAMPORTS
+                .with_label_values(&[&named.name])
+                .set(wallet_state.exposure() as i64);
+        }
+
+        position
+    }
+
+    /// Кошельки с открытой позицией по пулу
+    pub fn holders(&self, pool: &Pubkey) -> Vec<(Wallet, Position)> {
+        let state = self.state.lock().unwrap();
+
+        self.wallets
+            .iter()
+            .filter_map(|wallet| {
+                let position = state[&wallet.pubkey()].positions.get(pool)?;
+                Some((wallet.clone(), position.clone()))
+            })
+            .collect()
+    }
+
+    /// Открытые позиции по всем кошелькам
+    pub fn positions(&self) -> Vec<(String, Position)> {
+        let state = self.state.lock().unwrap();
+
+        self.wallets
+            .iter()
+            .flat_map(|wallet| {
+                state[&wallet.pubkey()]
+                    .positions
+                    .values()
+                    .map(|position| (wallet.name.clone(), position.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(budgets: &[Option<u64>], selection: WalletSelection) -> WalletPool {
+        let wallets = budgets
+            .iter()
+            .enumerate()
+            .map(|(index, budget)| Wallet {
+                name: format!("w{index}"),
+                keypair: Arc::new(Keypair::new()),
+                budget: budget.map(Lamports),
+            })
+            .collect();
+        WalletPool::new(wallets, selection)
+    }
+
+    fn fill(tokens_received: u64) -> Fill {
+        Fill {
+            mint: Pubkey::new_unique(),
+            tokens_received,
+            decimals: 6,
+            sol_spent: Lamports(100),
+        }
+    }
+
+    /// Индекс кошелька, выбранного под сделку
+    fn acquire(wallets: &WalletPool, pool: &Pubkey, amount: u64) -> Option<usize> {
+        let lease = wallets.acquire(pool, Lamports(amount))?;
+        wallets.wallets().iter().position(|wallet| wallet.pubkey() == lease.wallet.pubkey())
+    }
+
+    #[test]
+    fn round_robin_rotates_wallets() {
+        let wallets = pool(&[None, None, None], WalletSelection::RoundRobin);
+        let chosen: Vec<_> = (0..4).map(|_| acquire(&wallets, &Pubkey::new_unique(), 100)).collect();
+        assert_eq!(chosen, vec![Some(0), Some(1), Some(2), Some(0)]);
+    }
+
+    #[test]
+    fn round_robin_skips_wallets_over_budget() {
+        let wallets = pool(&[Some(100), None], WalletSelection::RoundRobin);
+        assert_eq!(acquire(&wallets, &Pubkey::new_unique(), 100), Some(0));
+        // Очередь w1, затем снова w0, но его бюджет занят резервом
+        assert_eq!(acquire(&wallets, &Pubkey::new_unique(), 100), Some(1));
+        assert_eq!(acquire(&wallets, &Pubkey::new_unique(), 100), Some(1));
+    }
+
+    #[test]
+    fn least_exposure_prefers_lightest_wallet() {
+        let wallets = pool(&[None, None, None], WalletSelection::LeastExposure);
+        let first = wallets.acquire(&Pubkey::new_unique(), Lamports(100)).unwrap();
+        assert_eq!(acquire(&wallets, &Pubkey::new_unique(), 300), Some(1));
+        assert_eq!(acquire(&wallets, &Pubkey::new_unique(), 200), Some(2));
+
+        // w0 освободил резерв — у него снова наименьшая экспозиция
+        wallets.release(&first);
+        assert_eq!(acquire(&wallets, &Pubkey::new_unique(), 100), Some(0));
+        // w0 100, w1 300, w2 200
+        assert_eq!(acquire(&wallets, &Pubkey::new_unique(), 150), Some(0));
+        // w0 250, w1 300, w2 200
+        assert_eq!(acquire(&wallets, &Pubkey::new_unique(), 100), Some(2));
+    }
+
+    #[test]
+    fn budget_counts_reserves_and_positions() {
+        let wallets = pool(&[Some(150)], WalletSelection::RoundRobin);
+        let (first_pool, second_pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let lease = wallets.acquire(&first_pool, Lamports(100)).unwrap();
+        assert!(wallets.acquire(&second_pool, Lamports(100)).is_none(), "резерв занимает бюджет");
+        assert!(wallets.acquire(&second_pool, Lamports(50)).is_some(), "остаток бюджета доступен");
+
+        wallets.open_position(&lease, first_pool, None, None);
+        assert!(wallets.acquire(&Pubkey::new_unique(), Lamports(100)).is_none(), "позиция занимает бюджет");
+
+        assert!(wallets.close_position(&lease.wallet.pubkey(), &first_pool).is_some());
+        assert!(wallets.acquire(&Pubkey::new_unique(), Lamports(100)).is_some(), "закрытие освобождает бюджет");
+    }
+
+    #[test]
+    fn open_position_blocks_same_pool() {
+        let wallets = pool(&[None], WalletSelection::RoundRobin);
+        let pool_address = Pubkey::new_unique();
+        let lease = wallets.acquire(&pool_address, Lamports(100)).unwrap();
+        wallets.open_position(&lease, pool_address, None, None);
+
+        assert!(wallets.acquire(&pool_address, Lamports(100)).is_none());
+        assert!(wallets.acquire(&Pubkey::new_unique(), Lamports(100)).is_some());
+    }
+
+    #[test]
+    fn unfilled_buy_closes_position() {
+        let wallets = pool(&[Some(100)], WalletSelection::RoundRobin);
+        let cases = [(None, false), (Some(fill(0)), false), (Some(fill(1_000)), true)];
+        for (fill, kept) in cases {
+            let pool_address = Pubkey::new_unique();
+            let lease = wallets.acquire(&pool_address, Lamports(100)).unwrap();
+            wallets.open_position(&lease, pool_address, Some(Venue::MeteoraAmm), None);
+
+            assert_eq!(wallets.record_fill(&lease.wallet.pubkey(), &pool_address, fill.clone()), kept);
+            assert_eq!(wallets.positions().len(), kept as usize, "исполнение {fill:?}");
+            if kept {
+                wallets.close_position(&lease.wallet.pubkey(), &pool_address);
+            }
+        }
+    }
+
+    #[test]
+    fn holders_of_pool() {
+        let wallets = pool(&[None, None, None], WalletSelection::RoundRobin);
+        let (pool_address, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        for (target, venue) in [(pool_address, None), (other, Some(Venue::MeteoraDammV2)), (pool_address, None)] {
+            let lease = wallets.acquire(&target, Lamports(100)).unwrap();
+            wallets.open_position(&lease, target, venue, None);
+        }
+
+        let names: Vec<String> = wallets.holders(&pool_address).into_iter().map(|(wallet, _)| wallet.name).collect();
+        assert_eq!(names, ["w0", "w2"]);
+        let holders = wallets.holders(&other);
+        assert_eq!(holders.len(), 1);
+        assert_eq!(holders[0].1.venue, Some(Venue::MeteoraDammV2));
+        assert!(wallets.holders(&Pubkey::new_unique()).is_empty());
+    }
+}